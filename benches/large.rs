@@ -0,0 +1,125 @@
+//! Timings for large values, compared against `{:#?}`.
+//!
+//! ```sh
+//! cargo bench --bench large
+//! ```
+
+use std::{collections::BTreeMap, fmt::Debug};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dbg_pls::{pretty, DebugPls};
+
+#[derive(Debug, DebugPls)]
+struct Wide {
+    a: u8,
+    b: u16,
+    c: u32,
+    d: u64,
+    e: i8,
+    f: i16,
+    g: i32,
+    h: i64,
+    i: f32,
+    j: f64,
+    k: bool,
+    l: char,
+    m: &'static str,
+    n: Option<u32>,
+    o: (u8, u8),
+    p: [u8; 4],
+}
+
+#[derive(Debug, DebugPls)]
+enum Tree {
+    Leaf(u32),
+    Node(Box<Tree>, Box<Tree>),
+}
+
+fn tree(depth: u32) -> Tree {
+    match depth {
+        0 => Tree::Leaf(0),
+        _ => Tree::Node(Box::new(tree(depth - 1)), Box::new(tree(depth - 1))),
+    }
+}
+
+fn wide() -> Wide {
+    Wide {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+        e: -5,
+        f: -6,
+        g: -7,
+        h: -8,
+        i: 9.5,
+        j: 10.25,
+        k: true,
+        l: 'x',
+        m: "hello world",
+        n: Some(12),
+        o: (13, 14),
+        p: [15, 16, 17, 18],
+    }
+}
+
+fn bench<T: Debug + DebugPls>(c: &mut Criterion, name: &str, samples: usize, value: &T) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(samples);
+    group.bench_function("dbg_pls", |b| {
+        b.iter(|| format!("{}", pretty(black_box(value))))
+    });
+    group.bench_function("std", |b| b.iter(|| format!("{:#?}", black_box(value))));
+    group.finish();
+}
+
+fn large(c: &mut Criterion) {
+    bench(c, "wide struct", 100, &wide());
+    bench(
+        c,
+        "wide structs x 10k",
+        10,
+        &(0..10_000).map(|_| wide()).collect::<Vec<_>>(),
+    );
+    bench(c, "deep nesting (2^14)", 10, &tree(14));
+    bench(
+        c,
+        "vec<u64> x 1M",
+        10,
+        &(0..1_000_000u64).collect::<Vec<_>>(),
+    );
+    bench(
+        c,
+        "btreemap x 100k",
+        10,
+        &(0..100_000u32)
+            .map(|i| (format!("key {}", i), i))
+            .collect::<BTreeMap<_, _>>(),
+    );
+    bench(c, "text (4 MB)", 10, &text(4 << 20));
+}
+
+criterion_group!(benches, large);
+criterion_main!(benches);
+
+/// Mostly plain prose, with the odd quote, newline and non-ASCII word.
+fn text(len: usize) -> String {
+    let words = [
+        "lorem",
+        "ipsum",
+        "\"dolor\"",
+        "sit",
+        "amet,",
+        "naïve",
+        "café.\n",
+    ];
+    let mut text = String::with_capacity(len + 16);
+    for word in words.iter().cycle() {
+        if text.len() >= len {
+            break;
+        }
+        text.push_str(word);
+        text.push(' ');
+    }
+    text
+}
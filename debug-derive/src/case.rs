@@ -0,0 +1,115 @@
+//! The case conversions of the `rename_all` arg, which follow serde's.
+
+use syn::LitStr;
+
+/// How the names of the fields of a struct, or of the variants of an enum,
+/// are written
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    /// `lowercase`
+    Lower,
+    /// `UPPERCASE`
+    Upper,
+    /// `PascalCase`
+    Pascal,
+    /// `camelCase`
+    Camel,
+    /// `snake_case`
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+}
+
+const RULES: &[(&str, RenameRule)] = &[
+    ("lowercase", RenameRule::Lower),
+    ("UPPERCASE", RenameRule::Upper),
+    ("PascalCase", RenameRule::Pascal),
+    ("camelCase", RenameRule::Camel),
+    ("snake_case", RenameRule::Snake),
+    ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+];
+
+/// serde's kebab cases, which are not valid Rust names, with the case to
+/// use instead
+const KEBAB_RULES: &[(&str, &str)] = &[
+    ("kebab-case", "snake_case"),
+    ("SCREAMING-KEBAB-CASE", "SCREAMING_SNAKE_CASE"),
+];
+
+impl RenameRule {
+    pub fn parse(lit: &LitStr) -> syn::Result<Self> {
+        let name = lit.value();
+        if let Some(&(_, rule)) = RULES.iter().find(|(rule, _)| *rule == name) {
+            return Ok(rule);
+        }
+        let message = match KEBAB_RULES.iter().find(|(kebab, _)| *kebab == name) {
+            Some((_, snake)) => format!("`{name}` names are not valid Rust, use `{snake}` instead"),
+            None => {
+                let rules: Vec<_> = RULES.iter().map(|(rule, _)| format!("`{rule}`")).collect();
+                format!(
+                    "unknown `rename_all` case, expected one of {}",
+                    rules.join(", ")
+                )
+            }
+        };
+        Err(syn::Error::new(lit.span(), message))
+    }
+
+    /// The name of a field, which is in `snake_case` to begin with.
+    pub fn field(self, field: &str) -> String {
+        let field = field.trim_start_matches("r#");
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_owned(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::with_capacity(field.len());
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.field(field);
+                lower_first(&pascal)
+            }
+        }
+    }
+
+    /// The name of a variant, which is in `PascalCase` to begin with.
+    pub fn variant(self, variant: &str) -> String {
+        let variant = variant.trim_start_matches("r#");
+        match self {
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Pascal => variant.to_owned(),
+            RenameRule::Camel => lower_first(variant),
+            RenameRule::Snake => {
+                let mut snake = String::with_capacity(variant.len() + 4);
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake.variant(variant).to_ascii_uppercase(),
+        }
+    }
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
-use proc_macro2::Ident;
+use proc_macro2::{Ident, TokenStream as TokenStream2};
 use quote::ToTokens;
-use syn::{parse_macro_input, DeriveInput, Fields, Generics, Path};
+use syn::{parse_macro_input, DeriveInput, Fields, Generics, Path, Type};
 
+mod case;
 mod parse;
 mod predicate;
 mod to_tokens;
@@ -19,18 +20,93 @@ pub fn derive(input: TokenStream) -> TokenStream {
 struct DebugImpl {
     krate: Path,
     ident: Ident,
+    /// The name the struct is written with, `Foo` or, with the
+    /// `module_path` arg, the path to it, and with the `generics` arg, its
+    /// generic arguments after that
+    name: TokenStream2,
     mode: Mode,
     generics: Generics,
+    /// Whether a `Debug` impl is derived too, from the `std_debug` arg
+    std_debug: bool,
+    /// Whether a `Display` impl is derived too, from the `impl_display` arg
+    impl_display: bool,
 }
 
 enum Mode {
     Struct(StructFields),
+    /// A struct with the `transparent` arg, formatted as its one field
+    Transparent(StructFields),
     Enum(Vec<Var>),
 }
 
 struct Var {
     ident: Ident,
+    /// The name the variant is written with, `Circle` or, with the
+    /// `qualify_variants` arg on the enum, `Shape::Circle`, with
+    /// `module_path`, the path to that, and with `generics`, the generic
+    /// arguments of the enum after its name
+    name: TokenStream2,
+    fields: StructFields,
+    /// Whether the variant is written with its fields left out, from its
+    /// `skip` arg
+    skip: bool,
+    /// Whether the variant is written as its one field, from its
+    /// `transparent` arg
+    transparent: bool,
+}
+
+struct StructFields {
+    krate: Path,
     fields: Fields,
+    /// The name each named field is written with
+    names: Vec<String>,
+    /// How each field is formatted
+    formats: Vec<Format>,
+    /// The predicate each field is left out by when it holds, from its
+    /// `skip_if = "..."` arg
+    skip_ifs: Vec<Option<Path>>,
+    /// Whether `..` is written after the fields, from the `non_exhaustive`
+    /// arg of the type
+    non_exhaustive: bool,
 }
 
-struct StructFields(Fields);
+/// How a field is formatted, as chosen by its `#[dbg_pls(..)]` args
+#[derive(Default)]
+enum Format {
+    #[default]
+    Value,
+    /// `opaque`, as its type name
+    Opaque,
+    /// `len_only`, as its type and length, or `len_only = N`, as
+    /// those once it is longer than `N`
+    LenOnly(Option<usize>),
+    /// `bytes`, followed by the size in a readable unit
+    Bytes,
+    /// `vec`, with its lists written as `vec![..]`
+    Vec,
+    /// `raw`, with its strings written as raw string literals
+    Raw,
+    /// `skip`, left out of the output
+    Skip,
+    /// `with = "..."`, by the function at the path, which takes the field
+    /// and a `Formatter`
+    With(Path),
+    /// `redact`, as the string `"[REDACTED]"`, or `redact = "..."`, as the
+    /// given one
+    Redact(String),
+    /// `flatten`, with its fields in place of its own
+    Flatten,
+    /// `hex`, with its integers written in hexadecimal
+    Hex,
+    /// `binary`, with its integers written in binary
+    Binary,
+    /// `display`, as the string its `Display` impl writes
+    Display,
+    /// `debug`, as what its `Debug` impl writes
+    Debug,
+    /// `as = "..."`, as a clone of it converted into the type by `Into`
+    As(Box<Type>),
+    /// `truncate = N`, with only the first `N` entries of its collection,
+    /// or chars of its string
+    Truncate(usize),
+}
@@ -1,11 +1,14 @@
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
 use syn::{
     parse::{Parse, ParseStream},
+    punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, Data, DeriveInput, Path, PathSegment, Token,
+    Attribute, Data, DeriveInput, Fields, GenericParam, Generics, LitInt, LitStr, Path,
+    PathSegment, Token, Type, WherePredicate,
 };
 
-use crate::{predicate::predicate, DebugImpl, Mode, Var};
+use crate::{case::RenameRule, predicate::predicate, DebugImpl, Format, Mode, StructFields, Var};
 
 impl TryFrom<DeriveInput> for DebugImpl {
     type Error = syn::Error;
@@ -19,44 +22,354 @@ impl TryFrom<DeriveInput> for DebugImpl {
             ..
         } = input;
 
-        let args = Args::parse_attrs(&*attrs)?;
-        let Krate(krate) = args.krate.unwrap_or_default();
+        let mut args = Args::parse_attrs(&attrs)?;
+        let Krate(krate) = args.krate.take().unwrap_or_default();
 
-        predicate(&mut generics, krate.clone());
+        if args.generics {
+            args.generic_args = generic_args(&generics);
+        }
 
+        let type_name = args.rename.clone().unwrap_or_else(|| ident.to_string());
         let mode = match data {
-            Data::Struct(s) => Mode::Struct(crate::StructFields(s.fields)),
+            Data::Struct(_) if args.qualify_variants => {
+                return Err(syn::Error::new(
+                    span,
+                    "`qualify_variants` can only be given to enums",
+                ))
+            }
+            Data::Struct(ref s) if args.sort_fields && !matches!(s.fields, Fields::Named(_)) => {
+                return Err(syn::Error::new(
+                    span,
+                    "`sort_fields` can only be given to structs with named fields",
+                ))
+            }
+            Data::Struct(_) if args.transparent && args.non_exhaustive => {
+                return Err(syn::Error::new(
+                    span,
+                    "only one of `transparent` and `non_exhaustive` can be given",
+                ))
+            }
+            Data::Struct(s) if args.transparent => {
+                let fields = StructFields::new(&krate, s.fields, args.rename_all, false)?;
+                if fields.shown(fields.fields.iter()).count() != 1 {
+                    return Err(syn::Error::new(
+                        span,
+                        "`transparent` needs a struct with exactly one field that is not skipped",
+                    ));
+                }
+                fields.no_skip_if("struct")?;
+                Mode::Transparent(fields)
+            }
+            Data::Struct(s) => {
+                let mut fields =
+                    StructFields::new(&krate, s.fields, args.rename_all, args.non_exhaustive)?;
+                if args.sort_fields {
+                    fields.sort_by_name();
+                }
+                Mode::Struct(fields)
+            }
+            Data::Enum(_) if args.transparent => {
+                return Err(syn::Error::new(
+                    span,
+                    "`transparent` can only be given to structs",
+                ))
+            }
             Data::Enum(e) => Mode::Enum(
                 e.variants
                     .into_iter()
-                    .map(|v| Var {
-                        ident: v.ident,
-                        fields: v.fields,
+                    .map(|v| {
+                        let var_args = VariantArgs::parse_attrs(&v.attrs)?;
+                        if var_args.skip && var_args.transparent {
+                            return Err(syn::Error::new(
+                                v.span(),
+                                "only one of `skip` and `transparent` can be given",
+                            ));
+                        }
+                        let variant = match var_args.rename {
+                            Some(name) => name,
+                            None => match args.rename_all {
+                                Some(rule) => rule.variant(&v.ident.to_string()),
+                                None => v.ident.to_string(),
+                            },
+                        };
+                        // the path to a variant goes through the enum, which
+                        // then takes the generic arguments
+                        let name = if args.qualify_variants || args.module_path {
+                            let variant = format!("::{variant}");
+                            args.generic_name(type_name.clone(), &krate)
+                                .map(|name| quote! { &(#name + #variant) })
+                                .unwrap_or_else(|| args.name(format!("{type_name}{variant}")))
+                        } else {
+                            args.generic_name(variant.clone(), &krate)
+                                .map(|name| quote! { &#name })
+                                .unwrap_or_else(|| args.name(variant))
+                        };
+                        let mut fields =
+                            StructFields::new(&krate, v.fields, None, args.non_exhaustive)?;
+                        if args.sort_fields {
+                            fields.sort_by_name();
+                        }
+                        if var_args.transparent {
+                            if fields.shown(fields.fields.iter()).count() != 1 {
+                                return Err(syn::Error::new(
+                                    v.ident.span(),
+                                    "`transparent` needs a variant with exactly one field that is not skipped",
+                                ));
+                            }
+                            fields.no_skip_if("variant")?;
+                        }
+                        Ok(Var {
+                            ident: v.ident,
+                            name,
+                            fields,
+                            skip: var_args.skip,
+                            transparent: var_args.transparent,
+                        })
                     })
-                    .collect(),
+                    .collect::<syn::Result<_>>()?,
             ),
             Data::Union(_) => return Err(syn::Error::new(span, "unions not supported")),
         };
+        match args.bound.take() {
+            Some(bound) => generics.make_where_clause().predicates.extend(bound),
+            None => predicate(&mut generics, &krate, &ident, mode.shown_fields()),
+        }
+        let name = args
+            .generic_name(type_name.clone(), &krate)
+            .map(|name| quote! { &#name })
+            .unwrap_or_else(|| args.name(type_name));
         Ok(Self {
             krate,
+            name,
             ident,
             generics,
             mode,
+            std_debug: args.std_debug,
+            impl_display: args.impl_display,
         })
     }
 }
 
+impl StructFields {
+    /// Parses the args of the fields, whose names are written by the
+    /// `rename_all` rule of the struct, if it has one, and which are
+    /// followed by `..` if it is `non_exhaustive`.
+    fn new(
+        krate: &Path,
+        fields: Fields,
+        rename_all: Option<RenameRule>,
+        non_exhaustive: bool,
+    ) -> syn::Result<Self> {
+        let mut names = Vec::with_capacity(fields.len());
+        let mut formats = Vec::with_capacity(fields.len());
+        let mut skip_ifs = Vec::with_capacity(fields.len());
+        for field in &fields {
+            let args = FieldArgs::parse_attrs(&field.attrs)?;
+            let name = match (&field.ident, args.rename) {
+                (Some(_), Some(name)) => name,
+                (Some(ident), None) => match rename_all {
+                    Some(rule) => rule.field(&ident.to_string()),
+                    None => ident.to_string(),
+                },
+                (None, Some(_)) => {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        "`rename` can only be given to named fields",
+                    ))
+                }
+                (None, None) => String::new(),
+            };
+            if field.ident.is_none() && matches!(args.format, Format::Flatten) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "`flatten` can only be given to named fields",
+                ));
+            }
+            if args.show && !is_phantom(&field.ty) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "`show` only applies to PhantomData fields",
+                ));
+            }
+            // a marker that holds nothing, and only stands for a bound
+            // on its type parameter, unless it is asked for
+            let format = match args.format {
+                Format::Value if !args.show && is_phantom(&field.ty) => Format::Skip,
+                Format::Skip if args.show => {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        "only one of `skip` and `show` can be given",
+                    ))
+                }
+                format => format,
+            };
+            if field.ident.is_none() && args.skip_if.is_some() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "`skip_if` can only be given to named fields",
+                ));
+            }
+            names.push(name);
+            formats.push(format);
+            skip_ifs.push(args.skip_if);
+        }
+        Ok(Self {
+            krate: krate.clone(),
+            fields,
+            names,
+            formats,
+            skip_ifs,
+            non_exhaustive,
+        })
+    }
+
+    /// Rejects `skip_if` on the fields of a `transparent` struct or variant,
+    /// which is always written as its one field.
+    fn no_skip_if(&self, kind: &str) -> syn::Result<()> {
+        match self
+            .fields
+            .iter()
+            .zip(&self.skip_ifs)
+            .find(|(_, skip_if)| skip_if.is_some())
+        {
+            Some((field, _)) => Err(syn::Error::new(
+                field.span(),
+                format!("`skip_if` can't be given to the fields of a `transparent` {kind}"),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Puts the named fields in the order of the names they are written
+    /// with, keeping the order of those with the same name.
+    fn sort_by_name(&mut self) {
+        let Fields::Named(named) = &mut self.fields else {
+            return;
+        };
+        let mut fields: Vec<_> = std::mem::take(&mut named.named)
+            .into_iter()
+            .zip(std::mem::take(&mut self.names))
+            .zip(std::mem::take(&mut self.formats))
+            .zip(std::mem::take(&mut self.skip_ifs))
+            .collect();
+        fields.sort_by(|(((_, a), _), _), (((_, b), _), _)| a.cmp(b));
+        for (((field, name), format), skip_if) in fields {
+            named.named.push(field);
+            self.names.push(name);
+            self.formats.push(format);
+            self.skip_ifs.push(skip_if);
+        }
+    }
+}
+
+/// Whether the type is a `PhantomData`, by its name or by its path in
+/// `std` or `core`.
+fn is_phantom(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else {
+        return false;
+    };
+    let idents: Vec<String> = ty
+        .path
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect();
+    let idents: Vec<&str> = idents.iter().map(String::as_str).collect();
+    ty.qself.is_none()
+        && matches!(
+            idents[..],
+            ["PhantomData"] | ["marker", "PhantomData"] | ["std" | "core", "marker", "PhantomData"]
+        )
+}
+
+impl Mode {
+    /// The types of the fields that are formatted, and how, which the impl
+    /// bounds.
+    fn shown_fields(&self) -> Vec<(&Type, &Format)> {
+        let fields: Vec<&StructFields> = match self {
+            Mode::Struct(fields) | Mode::Transparent(fields) => vec![fields],
+            // the fields of skipped variants are never formatted
+            Mode::Enum(variants) => variants
+                .iter()
+                .filter(|v| !v.skip)
+                .map(|v| &v.fields)
+                .collect(),
+        };
+        fields
+            .into_iter()
+            .flat_map(|fields| fields.shown(fields.fields.iter().zip(&fields.formats)))
+            .map(|(field, format)| (&field.ty, format))
+            .collect()
+    }
+}
+
 const ATTR: &str = "dbg_pls";
 const CRATE: &str = "dbg_pls";
+/// What redacted fields are written as, unless they are given a placeholder
+const REDACTED: &str = "[REDACTED]";
 
 /// Args of `dbg_pls`
 #[derive(Default)]
 struct Args {
     /// Optional `crate = $:path` arg
     krate: Option<Krate>,
+    /// `qualify_variants` arg, which writes the variants of an enum with
+    /// its name ahead of them
+    qualify_variants: bool,
+    /// `module_path` arg, which writes the name of the type with the path
+    /// to its module ahead of it
+    module_path: bool,
+    /// `generics` arg, which writes the name of the type with its generic
+    /// arguments after it
+    generics: bool,
+    /// The expressions for those arguments
+    generic_args: Vec<TokenStream>,
+    /// `rename = "..."` arg, the name the type is written with instead of
+    /// its own
+    rename: Option<String>,
+    /// `rename_all = "..."` arg, the case the fields of a struct, or the
+    /// variants of an enum, are written in
+    rename_all: Option<RenameRule>,
+    /// `transparent` arg, which writes a struct of one field as that field
+    transparent: bool,
+    /// `bound = "..."` arg, the where clause of the impl instead of the
+    /// bounds on the types of its fields
+    bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    /// `std_debug` arg, which derives a `Debug` impl that writes the type
+    /// as its `DebugPls` impl does
+    std_debug: bool,
+    /// `impl_display` arg, which derives a `Display` impl that pretty
+    /// prints the type
+    impl_display: bool,
+    /// `non_exhaustive` arg, which writes `..` after the fields of the
+    /// struct or of each variant
+    non_exhaustive: bool,
+    /// `sort_fields` arg, which writes the named fields in the order of
+    /// their names rather than the order they are declared in
+    sort_fields: bool,
 }
 
 impl Args {
+    /// The expression for the string that `name` is written as.
+    fn name(&self, name: String) -> TokenStream {
+        if self.module_path {
+            quote! { ::core::concat!(::core::module_path!(), "::", #name) }
+        } else {
+            quote! { #name }
+        }
+    }
+
+    /// The expression for the `String` that `name` is written as with the
+    /// generic arguments after it, if it takes any.
+    fn generic_name(&self, name: String, krate: &Path) -> Option<TokenStream> {
+        if self.generic_args.is_empty() {
+            return None;
+        }
+        let name = self.name(name);
+        let args = &self.generic_args;
+        Some(quote! { #krate::__private::generic_name(#name, &[#( #args ),*]) })
+    }
+
     fn parse_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
         let mut args = Args::default();
         for attr in attrs {
@@ -83,6 +396,77 @@ impl Args {
                         return Err(input.error("duplicate `crate` arg"));
                     }
                 }
+                _ if input.peek(kw::qualify_variants) => {
+                    input.parse::<kw::qualify_variants>()?;
+                    if std::mem::replace(&mut self.qualify_variants, true) {
+                        return Err(input.error("duplicate `qualify_variants` arg"));
+                    }
+                }
+                _ if input.peek(kw::module_path) => {
+                    input.parse::<kw::module_path>()?;
+                    if std::mem::replace(&mut self.module_path, true) {
+                        return Err(input.error("duplicate `module_path` arg"));
+                    }
+                }
+                _ if input.peek(kw::rename) => {
+                    if self.rename.replace(parse_rename(input)?).is_some() {
+                        return Err(input.error("duplicate `rename` arg"));
+                    }
+                }
+                _ if input.peek(kw::rename_all) => {
+                    input.parse::<kw::rename_all>()?;
+                    input.parse::<Token![=]>()?;
+                    let rule = RenameRule::parse(&input.parse()?)?;
+                    if self.rename_all.replace(rule).is_some() {
+                        return Err(input.error("duplicate `rename_all` arg"));
+                    }
+                }
+                _ if input.peek(kw::transparent) => {
+                    input.parse::<kw::transparent>()?;
+                    if std::mem::replace(&mut self.transparent, true) {
+                        return Err(input.error("duplicate `transparent` arg"));
+                    }
+                }
+                _ if input.peek(kw::bound) => {
+                    input.parse::<kw::bound>()?;
+                    input.parse::<Token![=]>()?;
+                    let bound = input
+                        .parse::<LitStr>()?
+                        .parse_with(Punctuated::parse_terminated)?;
+                    if self.bound.replace(bound).is_some() {
+                        return Err(input.error("duplicate `bound` arg"));
+                    }
+                }
+                _ if input.peek(kw::std_debug) => {
+                    input.parse::<kw::std_debug>()?;
+                    if std::mem::replace(&mut self.std_debug, true) {
+                        return Err(input.error("duplicate `std_debug` arg"));
+                    }
+                }
+                _ if input.peek(kw::impl_display) => {
+                    input.parse::<kw::impl_display>()?;
+                    if std::mem::replace(&mut self.impl_display, true) {
+                        return Err(input.error("duplicate `impl_display` arg"));
+                    }
+                }
+                _ if input.peek(kw::non_exhaustive) => {
+                    input.parse::<kw::non_exhaustive>()?;
+                    if std::mem::replace(&mut self.non_exhaustive, true) {
+                        return Err(input.error("duplicate `non_exhaustive` arg"));
+                    }
+                }
+                _ if input.peek(kw::sort_fields) => {
+                    input.parse::<kw::sort_fields>()?;
+                    if std::mem::replace(&mut self.sort_fields, true) {
+                        return Err(input.error("duplicate `sort_fields` arg"));
+                    }
+                }
+                _ if input.peek(kw::generics) => {
+                    input.parse::<kw::generics>()?;
+                    if std::mem::replace(&mut self.generics, true) {
+                        return Err(input.error("duplicate `generics` arg"));
+                    }
+                }
                 _ => return Err(input.error("unknown argument found")),
             }
         }
@@ -117,3 +501,260 @@ impl Parse for Krate {
         Ok(Krate(krate))
     }
 }
+
+mod kw {
+    syn::custom_keyword!(opaque);
+    syn::custom_keyword!(len_only);
+    syn::custom_keyword!(bytes);
+    syn::custom_keyword!(vec);
+    syn::custom_keyword!(raw);
+    syn::custom_keyword!(skip);
+    syn::custom_keyword!(skip_if);
+    syn::custom_keyword!(show);
+    syn::custom_keyword!(with);
+    syn::custom_keyword!(redact);
+    syn::custom_keyword!(flatten);
+    syn::custom_keyword!(hex);
+    syn::custom_keyword!(binary);
+    syn::custom_keyword!(display);
+    syn::custom_keyword!(debug);
+    syn::custom_keyword!(truncate);
+    syn::custom_keyword!(qualify_variants);
+    syn::custom_keyword!(module_path);
+    syn::custom_keyword!(generics);
+    syn::custom_keyword!(rename);
+    syn::custom_keyword!(rename_all);
+    syn::custom_keyword!(transparent);
+    syn::custom_keyword!(bound);
+    syn::custom_keyword!(std_debug);
+    syn::custom_keyword!(impl_display);
+    syn::custom_keyword!(non_exhaustive);
+    syn::custom_keyword!(sort_fields);
+}
+
+/// Parses a `rename = "..."` arg into the name it gives.
+fn parse_rename(input: ParseStream) -> syn::Result<String> {
+    input.parse::<kw::rename>()?;
+    input.parse::<Token![=]>()?;
+    Ok(input.parse::<LitStr>()?.value())
+}
+
+/// Args of `dbg_pls` on an enum variant
+#[derive(Default)]
+struct VariantArgs {
+    /// `rename = "..."` arg, the name the variant is written with instead
+    /// of its own
+    rename: Option<String>,
+    /// `skip` arg, which writes the variant with its fields left out
+    skip: bool,
+    /// `transparent` arg, which writes the variant as its one field
+    transparent: bool,
+}
+
+impl VariantArgs {
+    fn parse_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut args = VariantArgs::default();
+        for attr in attrs {
+            if attr.path.get_ident().map(|x| x == ATTR) == Some(true) {
+                args = attr.parse_args_with(|input: ParseStream| args.parse(input))?;
+            }
+        }
+        Ok(args)
+    }
+
+    fn parse(mut self, input: ParseStream) -> syn::Result<Self> {
+        let mut first = true;
+        while !input.is_empty() {
+            if !first {
+                input.parse::<Token![,]>()?;
+            }
+            first = false;
+
+            match () {
+                _ if input.peek(kw::rename) => {
+                    if self.rename.replace(parse_rename(input)?).is_some() {
+                        return Err(input.error("duplicate `rename` arg"));
+                    }
+                }
+                _ if input.peek(kw::skip) => {
+                    input.parse::<kw::skip>()?;
+                    if std::mem::replace(&mut self.skip, true) {
+                        return Err(input.error("duplicate `skip` arg"));
+                    }
+                }
+                _ if input.peek(kw::transparent) => {
+                    input.parse::<kw::transparent>()?;
+                    if std::mem::replace(&mut self.transparent, true) {
+                        return Err(input.error("duplicate `transparent` arg"));
+                    }
+                }
+                _ => return Err(input.error("unknown argument found")),
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// The expressions for the generic arguments of the type: the names of the
+/// types its type parameters are instantiated with, and the values of its
+/// const parameters. Lifetimes are left out.
+fn generic_args(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(ty) => {
+                let ident = &ty.ident;
+                Some(quote! { &::core::any::type_name::<#ident>() })
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                Some(quote! { &#ident })
+            }
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect()
+}
+
+/// Args of `dbg_pls` on a field
+#[derive(Default)]
+struct FieldArgs {
+    /// The arg the field is formatted by, see [`Format`]
+    format: Format,
+    /// `rename = "..."` arg, the name the field is written with instead of
+    /// its own
+    rename: Option<String>,
+    /// `skip_if = "..."` arg, the path to a function that takes the field
+    /// and leaves it out when it returns `true`
+    skip_if: Option<Path>,
+    /// `show` arg, which formats a `PhantomData` field rather than leaving
+    /// it out
+    show: bool,
+}
+
+impl FieldArgs {
+    fn parse_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut args = FieldArgs::default();
+        for attr in attrs {
+            if attr.path.get_ident().map(|x| x == ATTR) == Some(true) {
+                args = attr.parse_args_with(|input: ParseStream| args.parse(input))?;
+            }
+        }
+        Ok(args)
+    }
+
+    fn parse(mut self, input: ParseStream) -> syn::Result<Self> {
+        let mut first = true;
+        while !input.is_empty() {
+            if !first {
+                input.parse::<Token![,]>()?;
+            }
+            first = false;
+
+            if input.peek(kw::rename) {
+                if self.rename.replace(parse_rename(input)?).is_some() {
+                    return Err(input.error("duplicate `rename` arg"));
+                }
+                continue;
+            }
+            if input.peek(kw::show) {
+                input.parse::<kw::show>()?;
+                if std::mem::replace(&mut self.show, true) {
+                    return Err(input.error("duplicate `show` arg"));
+                }
+                continue;
+            }
+            if input.peek(kw::skip_if) {
+                input.parse::<kw::skip_if>()?;
+                input.parse::<Token![=]>()?;
+                let skip_if = input.parse::<LitStr>()?.parse()?;
+                if self.skip_if.replace(skip_if).is_some() {
+                    return Err(input.error("duplicate `skip_if` arg"));
+                }
+                continue;
+            }
+            let format = match () {
+                _ if input.peek(kw::opaque) => {
+                    input.parse::<kw::opaque>()?;
+                    Format::Opaque
+                }
+                _ if input.peek(kw::len_only) => {
+                    input.parse::<kw::len_only>()?;
+                    let max = if input.peek(Token![=]) {
+                        input.parse::<Token![=]>()?;
+                        Some(input.parse::<LitInt>()?.base10_parse()?)
+                    } else {
+                        None
+                    };
+                    Format::LenOnly(max)
+                }
+                _ if input.peek(kw::bytes) => {
+                    input.parse::<kw::bytes>()?;
+                    Format::Bytes
+                }
+                _ if input.peek(kw::vec) => {
+                    input.parse::<kw::vec>()?;
+                    Format::Vec
+                }
+                _ if input.peek(kw::raw) => {
+                    input.parse::<kw::raw>()?;
+                    Format::Raw
+                }
+                _ if input.peek(kw::skip) => {
+                    input.parse::<kw::skip>()?;
+                    Format::Skip
+                }
+                _ if input.peek(kw::with) => {
+                    input.parse::<kw::with>()?;
+                    input.parse::<Token![=]>()?;
+                    Format::With(input.parse::<LitStr>()?.parse()?)
+                }
+                _ if input.peek(kw::redact) => {
+                    input.parse::<kw::redact>()?;
+                    let placeholder = if input.peek(Token![=]) {
+                        input.parse::<Token![=]>()?;
+                        input.parse::<LitStr>()?.value()
+                    } else {
+                        REDACTED.to_owned()
+                    };
+                    Format::Redact(placeholder)
+                }
+                _ if input.peek(kw::flatten) => {
+                    input.parse::<kw::flatten>()?;
+                    Format::Flatten
+                }
+                _ if input.peek(kw::hex) => {
+                    input.parse::<kw::hex>()?;
+                    Format::Hex
+                }
+                _ if input.peek(kw::binary) => {
+                    input.parse::<kw::binary>()?;
+                    Format::Binary
+                }
+                _ if input.peek(kw::display) => {
+                    input.parse::<kw::display>()?;
+                    Format::Display
+                }
+                _ if input.peek(kw::debug) => {
+                    input.parse::<kw::debug>()?;
+                    Format::Debug
+                }
+                _ if input.peek(Token![as]) => {
+                    input.parse::<Token![as]>()?;
+                    input.parse::<Token![=]>()?;
+                    Format::As(input.parse::<LitStr>()?.parse()?)
+                }
+                _ if input.peek(kw::truncate) => {
+                    input.parse::<kw::truncate>()?;
+                    input.parse::<Token![=]>()?;
+                    Format::Truncate(input.parse::<LitInt>()?.base10_parse()?)
+                }
+                _ => return Err(input.error("unknown argument found")),
+            };
+            if !matches!(std::mem::replace(&mut self.format, format), Format::Value) {
+                return Err(input.error("only one formatting argument can be given per field"));
+            }
+        }
+        Ok(self)
+    }
+}
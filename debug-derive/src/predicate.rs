@@ -1,46 +1,113 @@
-use proc_macro2::{Ident, Span};
-use syn::{
-    punctuated::Punctuated, token, Generics, Path, PredicateType, TraitBound, Type, TypeParamBound,
-    TypePath, WhereClause, WherePredicate,
-};
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{parse_quote, GenericParam, Generics, Path, Type, WherePredicate};
 
-const TRAIT: &str = "DebugPls";
-
-pub fn predicate(generics: &mut Generics, mut krate: Path) {
-    let Generics {
-        params,
-        where_clause,
-        ..
-    } = generics;
+use crate::Format;
 
-    let mut bounds = Punctuated::new();
-    krate.segments.push(syn::PathSegment {
-        ident: Ident::new(TRAIT, Span::call_site()),
-        arguments: syn::PathArguments::None,
-    });
-    bounds.push(TypeParamBound::Trait(TraitBound {
-        paren_token: None,
-        modifier: syn::TraitBoundModifier::None,
-        lifetimes: None,
-        path: krate,
-    }));
+const TRAIT: &str = "DebugPls";
 
-    let wc = where_clause.get_or_insert_with(|| WhereClause {
-        where_token: Default::default(),
-        predicates: Punctuated::new(),
-    });
+/// Bounds the types of the formatted fields that mention a type parameter by
+/// the trait they are formatted through, `Vec<T>: DebugPls`, so that `T`
+/// only needs what the field's own impl asks of it. A field that holds the
+/// type itself bounds the parameters it mentions instead, `T: DebugPls`.
+/// Fields formatted without a trait, or skipped, such as a `PhantomData`,
+/// need no bound. `as` and `bytes` fields are bounded by what converting
+/// them takes as well.
+pub fn predicate(
+    generics: &mut Generics,
+    krate: &Path,
+    ident: &Ident,
+    fields: Vec<(&Type, &Format)>,
+) {
+    let mut debug_pls = krate.clone();
+    debug_pls
+        .segments
+        .push(Ident::new(TRAIT, Span::call_site()).into());
+    let params: Vec<Ident> = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(param.ident.clone()),
+            _ => None,
+        })
+        .collect();
 
-    for ty in params {
-        if let syn::GenericParam::Type(ty) = ty {
-            wc.predicates.push(WherePredicate::Type(PredicateType {
-                lifetimes: None,
-                bounded_ty: Type::Path(TypePath {
-                    qself: None,
-                    path: ty.ident.clone().into(),
-                }),
-                colon_token: token::Colon::default(),
-                bounds: bounds.clone(),
-            }))
+    let this = Ident::new("Self", Span::call_site());
+    let mut predicates: Vec<WherePredicate> = Vec::new();
+    let mut push = |predicate: WherePredicate| {
+        let tokens = predicate.to_token_stream().to_string();
+        if !predicates
+            .iter()
+            .any(|other| other.to_token_stream().to_string() == tokens)
+        {
+            predicates.push(predicate);
+        }
+    };
+    for (ty, format) in fields {
+        let generic = |ty: &Type| {
+            let tokens = ty.to_token_stream();
+            params
+                .iter()
+                .filter(|param| mentions(tokens.clone(), param))
+                .collect::<Vec<_>>()
+        };
+        // an `as` field is cloned and converted before it is formatted
+        if let Format::As(target) = format {
+            if !generic(ty).is_empty() {
+                push(parse_quote! {
+                    #ty: ::core::convert::Into<#target> + ::core::clone::Clone
+                });
+            }
+        }
+        let (ty, bound) = match format {
+            Format::Value
+            | Format::Flatten
+            | Format::LenOnly(Some(_))
+            | Format::Vec
+            | Format::Raw
+            | Format::Hex
+            | Format::Binary
+            | Format::Truncate(_) => (ty, debug_pls.to_token_stream()),
+            Format::As(target) => (&**target, debug_pls.to_token_stream()),
+            Format::Display => (ty, quote! { ::core::fmt::Display }),
+            Format::Debug => (ty, quote! { ::core::fmt::Debug }),
+            Format::Opaque
+            | Format::LenOnly(None)
+            | Format::Skip
+            | Format::With(_)
+            | Format::Redact(_) => continue,
+            // a `bytes` field is copied out and converted to a count of bytes
+            Format::Bytes => {
+                if !generic(ty).is_empty() {
+                    push(parse_quote! {
+                        #ty: #debug_pls + ::core::marker::Copy + ::core::convert::TryInto<u64>
+                    });
+                }
+                continue;
+            }
+        };
+        let mentioned = generic(ty);
+        if mentioned.is_empty() {
+            continue;
+        }
+        // a field that holds the type itself would ask for the impl it bounds
+        let tokens = ty.to_token_stream();
+        if mentions(tokens.clone(), ident) || mentions(tokens, &this) {
+            for param in mentioned {
+                push(parse_quote! { #param: #bound });
+            }
+        } else {
+            push(parse_quote! { #ty: #bound });
         }
     }
+    generics.make_where_clause().predicates.extend(predicates);
+}
+
+/// Whether `ident` is among the tokens, at any depth.
+fn mentions(tokens: TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(token) => token == *ident,
+        TokenTree::Group(group) => mentions(group.stream(), ident),
+        TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+    })
 }
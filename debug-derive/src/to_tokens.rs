@@ -1,4 +1,4 @@
-use crate::{DebugImpl, StructFields, Var};
+use crate::{DebugImpl, Format, StructFields, Var};
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote, ToTokens};
 use syn::{spanned::Spanned, Field, Fields, Index};
@@ -8,19 +8,23 @@ impl ToTokens for DebugImpl {
         let Self {
             krate,
             ident,
+            name,
             generics,
             mode,
+            std_debug,
+            impl_display,
         } = self;
 
         let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
         let body = match mode {
-            crate::Mode::Struct(fields) => {
-                let name = ident.to_string();
-                quote! {{
-                    let name = #name;
-                    #fields
-                }}
+            crate::Mode::Struct(fields) => quote! {{
+                let name = #name;
+                #fields
+            }},
+            crate::Mode::Transparent(fields) => {
+                let fmt = fields.finish_transparent(fields.members());
+                quote! {{ #fmt }}
             }
             crate::Mode::Enum(variants) => quote! {{
                 match *self {
@@ -33,33 +37,92 @@ impl ToTokens for DebugImpl {
             impl #impl_generics #krate::DebugPls for #ident #ty_generics #where_clause {
                 fn fmt(&self, f: #krate::Formatter<'_>) #body
             }
-        })
+        });
+        if *std_debug {
+            tokens.extend(quote! {
+                #krate::__private::pretty_only! {
+                    "std_debug",
+                    #[automatically_derived]
+                    impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+                        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                            #krate::__private::std_debug(self, f)
+                        }
+                    }
+                }
+            });
+        }
+        if *impl_display {
+            tokens.extend(quote! {
+                #krate::__private::pretty_only! {
+                    "impl_display",
+                    #[automatically_derived]
+                    impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+                        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                            ::core::fmt::Display::fmt(&#krate::pretty(self), f)
+                        }
+                    }
+                }
+            });
+        }
     }
 }
 
 impl ToTokens for Var {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let Var { ident, fields } = self;
-        let name = ident.to_string();
-        match fields {
+        let Var {
+            ident,
+            name,
+            fields,
+            skip,
+            transparent,
+        } = self;
+        if *skip {
+            return tokens.extend(match &fields.fields {
+                Fields::Named(_) => quote! {
+                    Self::#ident { .. } => f.debug_struct(#name).finish_non_exhaustive(),
+                },
+                Fields::Unnamed(_) => quote! {
+                    Self::#ident(..) => f.debug_tuple_struct(#name).finish_non_exhaustive(),
+                },
+                Fields::Unit => quote! { Self::#ident => f.debug_ident(#name), },
+            });
+        }
+        match &fields.fields {
             Fields::Named(n) => {
-                let pat_args = n.named.iter().map(|f| f.ident.as_ref().unwrap().clone());
-                let args = pat_args.clone().map(|f| {
-                    let name = f.to_string();
-                    quote! { #name, #f }
-                });
+                let idents = n.named.iter().map(|f| f.ident.as_ref().unwrap().clone());
+                let pat_args = fields.shown(idents.clone());
+                let args: Vec<_> = idents.map(ToTokens::into_token_stream).collect();
+                let finish = if *transparent {
+                    fields.finish_transparent(args.into_iter())
+                } else {
+                    fields.finish_named(name, &args)
+                };
                 tokens.extend(quote! {
-                    Self::#ident { #( ref #pat_args ),* } => f.debug_struct(#name) #( .field(#args) )* .finish(),
+                    Self::#ident { #( ref #pat_args, )* .. } => #finish,
                 });
             }
             Fields::Unnamed(n) => {
                 pub fn i((i, field): (usize, &Field)) -> Ident {
                     format_ident!("__self_{}", i, span = field.span())
                 }
-                let pat_args = n.unnamed.iter().enumerate().map(i);
-                let args = pat_args.clone();
+                let idents = n.unnamed.iter().enumerate().map(i);
+                // skipped fields are matched by `_`
+                let pats =
+                    idents
+                        .clone()
+                        .zip(&fields.formats)
+                        .map(|(ident, format)| match format {
+                            Format::Skip => quote! { _ },
+                            _ => quote! { ref #ident },
+                        });
+                let idents = idents.map(ToTokens::into_token_stream);
+                let finish = if *transparent {
+                    fields.finish_transparent(idents)
+                } else {
+                    fields.finish_unnamed(name, idents)
+                };
                 tokens.extend(quote! {
-                    Self::#ident ( #( ref #pat_args ),* ) => f.debug_tuple_struct(#name) #( .field(#args) )* .finish(),
+                    Self::#ident ( #( #pats ),* ) => #finish,
                 });
             }
             Fields::Unit => tokens.extend(quote! { Self::#ident => f.debug_ident(#name), }),
@@ -67,32 +130,156 @@ impl ToTokens for Var {
     }
 }
 
-impl ToTokens for StructFields {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
-        let fields = &self.0;
-        match fields {
-            Fields::Named(n) => {
-                let pat_args = n.named.iter().map(|f| f.ident.as_ref().unwrap().clone());
-                let args = pat_args.clone().map(|f| {
-                    let name = f.to_string();
-                    quote! { #name, &self.#f }
-                });
-                tokens.extend(quote! {
-                    f.debug_struct(name) #( .field(#args) )* .finish()
+impl StructFields {
+    /// The values the fields are formatted as, by their [`Format`].
+    fn values(&self, fields: impl Iterator<Item = TokenStream>) -> Vec<TokenStream> {
+        let krate = &self.krate;
+        fields
+            .zip(&self.formats)
+            .filter_map(|(field, format)| {
+                Some(match format {
+                    Format::Value | Format::Flatten => field,
+                    Format::Opaque => quote! { &#krate::__private::Opaque::of(#field) },
+                    Format::LenOnly(None) => quote! { &#krate::__private::LenOnly::of(#field) },
+                    Format::LenOnly(Some(max)) => {
+                        quote! { &#krate::__private::LenOnlyAbove::of(#field, #max) }
+                    }
+                    Format::Bytes => quote! { &#krate::__private::Bytes::of(#field) },
+                    Format::Vec => quote! { &#krate::__private::VecMacro::of(#field) },
+                    Format::Raw => quote! { &#krate::__private::RawStrings::of(#field) },
+                    Format::Hex => quote! { &#krate::__private::Hex::of(#field) },
+                    Format::Binary => quote! { &#krate::__private::Binary::of(#field) },
+                    Format::Display => quote! { &#krate::__private::AsDisplay::of(#field) },
+                    Format::Debug => quote! { &#krate::__private::AsDebug::of(#field) },
+                    Format::As(ty) => quote! {
+                        &::core::convert::Into::<#ty>::into(::core::clone::Clone::clone(#field))
+                    },
+                    Format::Truncate(max) => {
+                        quote! { &#krate::__private::Truncated::of(#field, #max) }
+                    }
+                    Format::Skip => return None,
+                    Format::With(path) => {
+                        quote! { &#krate::__private::FormatWith::of(#field, #path) }
+                    }
+                    Format::Redact(placeholder) => quote! { &#placeholder },
+                })
+            })
+            .collect()
+    }
+
+    /// The call that writes the named fields, given references to them.
+    /// Structs with flattened fields, or fields skipped by a predicate, are
+    /// written by a builder, which splices in the fields of the former and
+    /// leaves out the latter when their predicate holds, as are
+    /// `non_exhaustive` structs, which it ends with `..`.
+    fn finish_named(&self, name: &TokenStream, fields: &[TokenStream]) -> TokenStream {
+        let names = self.shown(self.names.iter());
+        let values = self.values(fields.iter().cloned());
+        let flatten = |format: &Format| matches!(format, Format::Flatten);
+        if !self.formats.iter().any(flatten)
+            && self.skip_ifs.iter().all(Option::is_none)
+            && !self.non_exhaustive
+        {
+            return quote! {
+                f.debug_struct_fields_finish(#name, &[#( #names ),*], &[#( #values ),*])
+            };
+        }
+        let fields = self.shown(fields.iter().zip(&self.formats).zip(&self.skip_ifs));
+        let calls =
+            fields
+                .zip(names)
+                .zip(values)
+                .map(|((((field, format), skip_if), name), value)| {
+                    let call = match format {
+                        Format::Flatten => quote! { __struct = __struct.flatten(#name, #value); },
+                        _ => quote! { __struct = __struct.field(#name, #value); },
+                    };
+                    match skip_if {
+                        Some(skip_if) => quote! { if !#skip_if(#field) { #call } },
+                        None => call,
+                    }
                 });
-            }
-            Fields::Unnamed(n) => {
-                pub fn i((i, field): (usize, &Field)) -> Index {
-                    Index {
+        let finish = self.finish_call();
+        quote! {{
+            let mut __struct = f.debug_struct(#name);
+            #( #calls )*
+            __struct.#finish()
+        }}
+    }
+
+    /// The call that writes the unnamed fields, given references to them.
+    fn finish_unnamed(
+        &self,
+        name: &TokenStream,
+        fields: impl Iterator<Item = TokenStream>,
+    ) -> TokenStream {
+        let values = self.values(fields);
+        if !self.non_exhaustive {
+            return quote! { f.debug_tuple_struct_fields_finish(#name, &[#( #values ),*]) };
+        }
+        let finish = self.finish_call();
+        quote! {{
+            let mut __tuple = f.debug_tuple_struct(#name);
+            #( __tuple = __tuple.field(#values); )*
+            __tuple.#finish()
+        }}
+    }
+
+    /// The method that closes off the builder of the fields.
+    fn finish_call(&self) -> Ident {
+        if self.non_exhaustive {
+            format_ident!("finish_non_exhaustive")
+        } else {
+            format_ident!("finish")
+        }
+    }
+
+    /// The call that writes the one field that is not skipped as itself,
+    /// given references to the fields.
+    fn finish_transparent(&self, fields: impl Iterator<Item = TokenStream>) -> TokenStream {
+        let krate = &self.krate;
+        let value = self.values(fields);
+        quote! { #krate::DebugPls::fmt(#( #value )*, f) }
+    }
+
+    /// The references to each field of `self`, `&self.foo` or `&self.0`.
+    fn members(&self) -> impl Iterator<Item = TokenStream> + '_ {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match &field.ident {
+                Some(ident) => quote! { &self.#ident },
+                None => {
+                    let index = Index {
                         index: i as u32,
                         span: field.span(),
-                    }
+                    };
+                    quote! { &self.#index }
                 }
-                let pat_args = n.unnamed.iter().enumerate().map(i);
-                let args = pat_args.clone();
-                tokens.extend(quote! {
-                    f.debug_tuple_struct(name) #( .field(&self.#args) )* .finish()
-                });
+            })
+    }
+
+    /// The items of each field that is not skipped.
+    pub(crate) fn shown<'a, T: 'a>(
+        &'a self,
+        items: impl Iterator<Item = T> + 'a,
+    ) -> impl Iterator<Item = T> + 'a {
+        items
+            .zip(&self.formats)
+            .filter(|(_, format)| !matches!(format, Format::Skip))
+            .map(|(item, _)| item)
+    }
+}
+
+impl ToTokens for StructFields {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match &self.fields {
+            Fields::Named(_) => {
+                let args: Vec<_> = self.members().collect();
+                tokens.extend(self.finish_named(&quote! { name }, &args));
+            }
+            Fields::Unnamed(_) => {
+                tokens.extend(self.finish_unnamed(&quote! { name }, self.members()));
             }
             Fields::Unit => tokens.extend(quote! { f.debug_ident(name) }),
         };
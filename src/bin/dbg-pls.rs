@@ -0,0 +1,243 @@
+//! Pretty prints `{:?}` output or Rust expressions read from stdin.
+//!
+//! ```sh
+//! cargo run --features cli -- --color < dump.txt
+//! ```
+//!
+//! The input is pretty printed as a whole if it is a single expression.
+//! Otherwise it is read line by line, and each line that ends in a value,
+//! such as `state: Foo { bar: 1 }` in a log, has that value pretty printed.
+//! Every other line is written back as it was.
+#![warn(clippy::pedantic)]
+#![forbid(unsafe_code)]
+
+use std::{
+    io::{self, Read, Write},
+    process::ExitCode,
+    str::FromStr,
+};
+
+use dbg_pls::{DebugPls, Formatter};
+use proc_macro2::{Delimiter, Group, Punct, Spacing, TokenStream, TokenTree};
+
+const USAGE: &str = "\
+Pretty prints `{:?}` output or Rust expressions read from stdin
+
+Usage: dbg-pls [--color]
+
+Options:
+  --color  highlight the output
+  --help   print this message";
+
+struct Expr(syn::Expr);
+
+impl DebugPls for Expr {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_expr(self.0.clone());
+    }
+}
+
+fn main() -> ExitCode {
+    let mut color = false;
+    for arg in std::env::args().skip(1) {
+        match &*arg {
+            "--color" if cfg!(feature = "colors") => color = true,
+            "--color" => {
+                eprintln!("dbg-pls was built without the `colors` feature");
+                return ExitCode::FAILURE;
+            }
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => {
+                eprintln!("unknown argument `{arg}`\n\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut input = String::new();
+    if let Err(err) = io::stdin().read_to_string(&mut input) {
+        eprintln!("failed to read stdin: {err}");
+        return ExitCode::FAILURE;
+    }
+    let output = match parse(&input) {
+        Some(expr) => render(&expr, color) + "\n",
+        None => input.lines().map(|line| line_of(line, color)).collect(),
+    };
+    match io::stdout().lock().write_all(output.as_bytes()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // such as when piped into `head`
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("failed to write stdout: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The line, with the value it ends in pretty printed.
+fn line_of(line: &str, color: bool) -> String {
+    // the value starts after the longest prefix of words that is not part of it
+    let starts = std::iter::once(0).chain(
+        line.char_indices()
+            .filter(|&(_, c)| c == ' ')
+            .map(|(i, _)| i + 1),
+    );
+    for start in starts {
+        match parse(&line[start..]) {
+            Some(expr) if is_value(&expr) => {
+                return format!("{}{}\n", &line[..start], render(&expr, color));
+            }
+            _ => {}
+        }
+    }
+    format!("{line}\n")
+}
+
+/// Whether the expression is worth pretty printing, rather than a word of
+/// the surrounding text.
+fn is_value(expr: &syn::Expr) -> bool {
+    matches!(
+        expr,
+        syn::Expr::Struct(_)
+            | syn::Expr::Call(_)
+            | syn::Expr::Tuple(_)
+            | syn::Expr::Array(_)
+            | syn::Expr::Block(_)
+    )
+}
+
+fn render(expr: &syn::Expr, color: bool) -> String {
+    let expr = Expr(expr.clone());
+    #[cfg(feature = "colors")]
+    if color {
+        return dbg_pls::color(&expr).to_string();
+    }
+    // only ever set with the `colors` feature
+    let _ = color;
+    dbg_pls::pretty(&expr).to_string()
+}
+
+fn parse(input: &str) -> Option<syn::Expr> {
+    let tokens = TokenStream::from_str(input).ok()?;
+    syn::parse2(rewrite(tokens)).ok()
+}
+
+/// `{:?}` writes maps as `{k: v}` and sets as `{a, b}`, which are not
+/// expressions. They are rewritten into the blocks that maps and sets are
+/// pretty printed as, `{ [k] = v; }` and `{ a; b }`.
+fn rewrite(tokens: TokenStream) -> TokenStream {
+    let mut out = Vec::new();
+    for token in tokens {
+        let token = match token {
+            TokenTree::Group(group) => {
+                let mut stream = rewrite(group.stream());
+                // a struct is preceded by its name, `Foo { bar: 1 }`
+                let named = matches!(out.last(), Some(TokenTree::Ident(_)));
+                if group.delimiter() == Delimiter::Brace && !named {
+                    stream = entries(stream);
+                }
+                let mut rewritten = Group::new(group.delimiter(), stream);
+                rewritten.set_span(group.span());
+                TokenTree::Group(rewritten)
+            }
+            token => token,
+        };
+        out.push(token);
+    }
+    out.into_iter().collect()
+}
+
+/// Rewrites the entries of a map or set written by `{:?}`.
+fn entries(stream: TokenStream) -> TokenStream {
+    let tokens: Vec<_> = stream.into_iter().collect();
+    let entries: Vec<_> = tokens
+        .split(|token| is_punct(token, ',', None))
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    let mut out = TokenStream::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(colon) = colon(entry) {
+            let key = entry[..colon].iter().cloned().collect();
+            out.extend([
+                TokenTree::Group(Group::new(Delimiter::Bracket, key)),
+                punct('='),
+            ]);
+            out.extend(entry[colon + 1..].iter().cloned());
+            out.extend([punct(';')]);
+        } else {
+            out.extend(entry.iter().cloned());
+            if i + 1 < entries.len() {
+                out.extend([punct(';')]);
+            }
+        }
+    }
+    out
+}
+
+/// The `:` between a key and its value, as opposed to the `::` of a path.
+fn colon(entry: &[TokenTree]) -> Option<usize> {
+    (0..entry.len()).find(|&i| {
+        let joined = i > 0 && is_punct(&entry[i - 1], ':', Some(Spacing::Joint));
+        is_punct(&entry[i], ':', Some(Spacing::Alone)) && !joined
+    })
+}
+
+fn is_punct(token: &TokenTree, ch: char, spacing: Option<Spacing>) -> bool {
+    match token {
+        TokenTree::Punct(punct) => {
+            punct.as_char() == ch && (spacing.is_none() || spacing == Some(punct.spacing()))
+        }
+        _ => false,
+    }
+}
+
+fn punct(ch: char) -> TokenTree {
+    TokenTree::Punct(Punct::new(ch, Spacing::Alone))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{line_of, parse, render};
+
+    fn pretty(input: &str) -> String {
+        render(&parse(input).unwrap(), false)
+    }
+
+    #[test]
+    fn debug_output() {
+        assert_eq!(
+            pretty("Foo { bar: 1, baz: [Some(Baz), None] }"),
+            "Foo {\n    bar: 1,\n    baz: [Some(Baz), None],\n}",
+        );
+        assert_eq!(
+            pretty(r#"{"a": 1, "b": Foo { c: std::f64::NAN }}"#),
+            "{\n    [\"a\"] = 1;\n    [\"b\"] = Foo { c: std::f64::NAN };\n}",
+        );
+        assert_eq!(pretty("{1, 2}"), "{\n    1;\n    2\n}");
+        assert_eq!(pretty("Bar(1.5s, {})"), "Bar(1.5s, {})");
+        assert_eq!(
+            pretty("Foo {\n    bar: (\n        1,\n    ),\n}"),
+            "Foo { bar: (1,) }"
+        );
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            line_of("[INFO] state: Foo { bar: 1 }", false),
+            "[INFO] state: Foo { bar: 1 }\n",
+        );
+        assert_eq!(
+            line_of("got {\"key\": [1, 2]}", false),
+            "got {\n    [\"key\"] = [1, 2];\n}\n",
+        );
+        assert_eq!(
+            line_of("nothing to see here", false),
+            "nothing to see here\n"
+        );
+        assert_eq!(line_of("unbalanced ( paren", false), "unbalanced ( paren\n");
+    }
+}
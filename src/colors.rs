@@ -19,9 +19,9 @@ use syntect::{
 };
 
 use crate::{
-    pretty::pretty_string,
+    pretty,
     DebugPls,
-    Formatter
+    Pretty
 };
 
 fn syntax() -> &'static SyntaxSet {
@@ -50,7 +50,7 @@ fn theme() -> &'static Theme {
     })
 }
 
-fn highlight(
+pub(crate) fn highlight(
     s: &str,
     mut w: impl std::fmt::Write
 ) -> std::fmt::Result {
@@ -70,28 +70,60 @@ fn highlight(
 /// Implementation detail for the `color!` macro
 pub struct ColorStr<'a>(pub &'a str);
 
-impl<'a> std::fmt::Display for ColorStr<'a> {
+impl std::fmt::Display for ColorStr<'_> {
+    #[cfg(not(feature = "syn-backend"))]
     fn fmt(
         &self,
         f: &mut std::fmt::Formatter<'_>
     ) -> std::fmt::Result {
-        let expr = syn::parse_str(self.0).map_err(|_| std::fmt::Error)?;
-        highlight(&pretty_string(expr), f)
+        // highlighted as it is if it can't be laid out
+        match crate::pretty::layout_source(self.0) {
+            Some(output) => highlight(&output, f),
+            None => highlight(self.0, f),
+        }
+    }
+
+    #[cfg(feature = "syn-backend")]
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>
+    ) -> std::fmt::Result {
+        // highlighted as it is if it can't be laid out
+        match syn::parse_str(self.0).ok().and_then(|expr| crate::pretty::unparse(&expr)) {
+            Some(output) => highlight(&output, f),
+            None => highlight(self.0, f),
+        }
     }
 }
 
-struct Color<'a>(&'a dyn DebugPls);
+#[cfg_attr(docsrs, doc(cfg(feature = "colors")))]
+/// A [`DebugPls`] type wrapped into a [`std::fmt::Debug`] type with syntax
+/// highlighting, see [`color`]
+#[derive(Clone, Copy)]
+pub struct Color<'a>(Pretty<'a>);
+
+impl<'a> Color<'a> {
+    /// Puts a label in front of the value, which is highlighted along with
+    /// it, see [`Pretty::named`].
+    #[must_use]
+    pub fn named(
+        self,
+        label: &'a str
+    ) -> Self {
+        Color(self.0.named(label))
+    }
+}
 
-impl<'a> std::fmt::Debug for Color<'a> {
+impl std::fmt::Debug for Color<'_> {
     fn fmt(
         &self,
         f: &mut std::fmt::Formatter<'_>
     ) -> std::fmt::Result {
-        highlight(&pretty_string(Formatter::process(self.0)), f)
+        highlight(&self.0.to_string(), f)
     }
 }
 
-impl<'a> std::fmt::Display for Color<'a> {
+impl std::fmt::Display for Color<'_> {
     fn fmt(
         &self,
         f: &mut std::fmt::Formatter<'_>
@@ -103,9 +135,9 @@ impl<'a> std::fmt::Display for Color<'a> {
 #[cfg_attr(docsrs, doc(cfg(feature = "colors")))]
 /// Wraps a [`DebugPls`] type into a [`std::fmt::Debug`] type for use in regular [`format!`]
 pub fn color(
-    value: &impl DebugPls
-) -> impl std::fmt::Debug + std::fmt::Display + '_ {
-    Color(value)
+    value: &dyn DebugPls
+) -> Color<'_> {
+    Color(pretty(value))
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "colors")))]
@@ -129,6 +161,10 @@ pub fn color(
 /// source location of the macro invocation as well as the source code
 /// of the expression.
 ///
+/// The dump is laid out in full and then written at once, so the dumps
+/// of different threads never interleave. [`lock_output`](crate::lock_output)
+/// keeps several of them together.
+///
 /// Invoking the macro on an expression moves and takes ownership of it
 /// before returning the evaluated expression unchanged. If the type
 /// of the expression does not implement `Copy` and you don't want
@@ -155,12 +191,12 @@ macro_rules! color {
     ($val:expr $(,)?) => {
         match $val {
             tmp => {
-                ::std::eprintln!(
-                    "[{}:{}] {} => {}",
+                $crate::__private::print(
                     ::std::file!(),
                     ::std::line!(),
-                    $crate::__private::ColorStr(::std::stringify!($val)),
-                    $crate::color(&tmp)
+                    ::std::stringify!($val),
+                    &$crate::__private::ColorStr(::std::stringify!($val)),
+                    &$crate::color(&tmp),
                 );
                 tmp
             }
@@ -175,8 +211,6 @@ macro_rules! color {
 mod tests {
     use std::collections::HashMap;
 
-    use crate::color;
-
     #[test]
     fn colors() {
         let map = color! {
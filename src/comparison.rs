@@ -0,0 +1,331 @@
+use std::fmt;
+
+use crate::{pretty, DebugPls};
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RED_EMPHASIS: &str = "\x1b[1;41m";
+const GREEN_EMPHASIS: &str = "\x1b[1;42m";
+const RESET: &str = "\x1b[0m";
+
+/// The most cells of the table that two sequences are diffed with, which
+/// keeps the diff of two large, mostly different dumps from taking up
+/// gigabytes. Past it, the lines that differ are all replaced.
+const MAX_CELLS: usize = 1 << 22;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// Two values as a colored diff of their pretty printed forms, as the
+/// [`assert_eq_pls!`](crate::assert_eq_pls) panic message shows them.
+///
+/// Lines only on the left are marked with a red `<`, lines only on the
+/// right with a green `>`. Where a line was changed, the words and symbols
+/// that differ within it are highlighted too.
+///
+/// ```
+/// use dbg_pls::Comparison;
+///
+/// let diff = Comparison::new(&(1, "two"), &(1, "three")).to_string();
+/// assert_eq!(
+///     diff.lines().last(),
+///     Some("\x1b[32m>(1, \"\x1b[1;42mthree\x1b[0m\x1b[32m\")\x1b[0m"),
+/// );
+/// ```
+pub struct Comparison<'a, T> {
+    left: &'a T,
+    right: &'a T,
+}
+
+impl<'a, T: DebugPls> Comparison<'a, T> {
+    /// Compares `left` to `right`.
+    pub fn new(left: &'a T, right: &'a T) -> Self {
+        Comparison { left, right }
+    }
+}
+
+impl<T: DebugPls> fmt::Debug for Comparison<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let left = pretty(self.left).to_string();
+        let right = pretty(self.right).to_string();
+        let left: Vec<_> = left.lines().collect();
+        let right: Vec<_> = right.lines().collect();
+
+        write!(f, "Diff {RED}<{RESET} left / right {GREEN}>{RESET} :")?;
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+        for edit in diff(&left, &right) {
+            match edit {
+                Edit::Same(line) => {
+                    changed(f, &removed, &added)?;
+                    removed.clear();
+                    added.clear();
+                    write!(f, "\n {line}")?;
+                }
+                Edit::Removed(line) => removed.push(line),
+                Edit::Added(line) => added.push(line),
+            }
+        }
+        changed(f, &removed, &added)
+    }
+}
+
+impl<T: DebugPls> fmt::Display for Comparison<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Writes a run of lines that were replaced. Each removed line is paired
+/// with the added line in the same place, if any, to highlight the tokens
+/// that changed between the two.
+fn changed(f: &mut fmt::Formatter<'_>, removed: &[&str], added: &[&str]) -> fmt::Result {
+    for (i, line) in removed.iter().enumerate() {
+        match added.get(i) {
+            Some(other) => {
+                let edits = diff(&tokens(line), &tokens(other));
+                highlighted(f, '<', RED, RED_EMPHASIS, &edits, |edit| match *edit {
+                    Edit::Same(token) => Some((token, false)),
+                    Edit::Removed(token) => Some((token, true)),
+                    Edit::Added(_) => None,
+                })?;
+            }
+            None => write!(f, "\n{RED}<{line}{RESET}")?,
+        }
+    }
+    for (i, line) in added.iter().enumerate() {
+        match removed.get(i) {
+            Some(other) => {
+                let edits = diff(&tokens(other), &tokens(line));
+                highlighted(f, '>', GREEN, GREEN_EMPHASIS, &edits, |edit| match *edit {
+                    Edit::Same(token) => Some((token, false)),
+                    Edit::Added(token) => Some((token, true)),
+                    Edit::Removed(_) => None,
+                })?;
+            }
+            None => write!(f, "\n{GREEN}>{line}{RESET}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes one side of a changed line, with the tokens that `side` marks as
+/// changed emphasized.
+fn highlighted<'s>(
+    f: &mut fmt::Formatter<'_>,
+    marker: char,
+    color: &str,
+    emphasis: &str,
+    edits: &[Edit<&'s str>],
+    side: impl Fn(&Edit<&'s str>) -> Option<(&'s str, bool)>,
+) -> fmt::Result {
+    write!(f, "\n{color}{marker}")?;
+    let mut emphasized = false;
+    for (token, changed) in edits.iter().filter_map(side) {
+        if changed != emphasized {
+            if changed {
+                f.write_str(emphasis)?;
+            } else {
+                write!(f, "{RESET}{color}")?;
+            }
+            emphasized = changed;
+        }
+        f.write_str(token)?;
+    }
+    f.write_str(RESET)
+}
+
+/// Splits a line into words, runs of whitespace, and single symbols, which
+/// are the units that changes within a line are highlighted in.
+fn tokens(line: &str) -> Vec<&str> {
+    fn class(ch: char) -> u8 {
+        if ch.is_alphanumeric() || ch == '_' {
+            0
+        } else if ch.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    }
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        let next = chars.peek().map(|&(_, next)| next);
+        // symbols are tokens of their own, `((` is two of them
+        if class(ch) == 2 || next.map(class) != Some(class(ch)) {
+            let end = i + ch.len_utf8();
+            tokens.push(&line[start..end]);
+            start = end;
+        }
+    }
+    tokens
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Edit<T> {
+    Same(T),
+    Removed(T),
+    Added(T),
+}
+
+/// The edits that turn `left` into `right`, with the fewest lines
+/// removed and added, and the removed lines of a run coming first.
+fn diff<T: PartialEq + Copy>(left: &[T], right: &[T]) -> Vec<Edit<T>> {
+    let prefix = left
+        .iter()
+        .zip(right)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let (a, b) = (
+        &left[prefix..left.len() - suffix],
+        &right[prefix..right.len() - suffix],
+    );
+
+    let mut edits: Vec<_> = left[..prefix].iter().map(|&x| Edit::Same(x)).collect();
+    if (a.len() + 1).saturating_mul(b.len() + 1) > MAX_CELLS {
+        edits.extend(a.iter().map(|&x| Edit::Removed(x)));
+        edits.extend(b.iter().map(|&x| Edit::Added(x)));
+    } else {
+        // `lcs[i * width + j]` is the length of the longest common
+        // subsequence of `a[i..]` and `b[j..]`
+        let width = b.len() + 1;
+        let mut lcs = vec![0_u32; (a.len() + 1) * width];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * width + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                edits.push(Edit::Same(a[i]));
+                i += 1;
+                j += 1;
+            } else if j == b.len()
+                || (i < a.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+            {
+                edits.push(Edit::Removed(a[i]));
+                i += 1;
+            } else {
+                edits.push(Edit::Added(b[j]));
+                j += 1;
+            }
+        }
+    }
+    edits.extend(left[left.len() - suffix..].iter().map(|&x| Edit::Same(x)));
+    edits
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+#[macro_export]
+/// Asserts that two values are equal, like [`assert_eq!`], but shows them
+/// as a [`Comparison`](crate::Comparison) of their pretty printed forms
+/// when they are not.
+///
+/// ```
+/// use dbg_pls::assert_eq_pls;
+///
+/// assert_eq_pls!(vec![1, 2, 3], (1..=3).collect::<Vec<_>>());
+/// assert_eq_pls!(Some("a"), Some("a"), "a message, {}", "formatted");
+/// ```
+///
+/// Both values have to be of the same type, which implements both
+/// [`PartialEq`] and [`DebugPls`](crate::DebugPls).
+macro_rules! assert_eq_pls {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left == *right) {
+                    ::std::panic!(
+                        "assertion failed: `(left == right)`\n\n{}\n",
+                        $crate::Comparison::new(left, right)
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left == *right) {
+                    ::std::panic!(
+                        "assertion failed: `(left == right)`: {}\n\n{}\n",
+                        ::std::format_args!($($arg)+),
+                        $crate::Comparison::new(left, right)
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{diff, tokens, Comparison, Edit};
+
+    #[test]
+    fn diffs() {
+        use Edit::{Added, Removed, Same};
+
+        assert_eq!(
+            diff(&["a", "b", "c", "d"], &["a", "x", "c", "d", "e"]),
+            [
+                Same("a"),
+                Removed("b"),
+                Added("x"),
+                Same("c"),
+                Same("d"),
+                Added("e")
+            ],
+        );
+        assert_eq!(diff(&["a"], &[]), [Removed("a")]);
+        assert_eq!(
+            diff(&[1, 2, 3], &[3, 2, 1]),
+            [Removed(1), Removed(2), Same(3), Added(2), Added(1)],
+        );
+        assert_eq!(
+            tokens("    foo_bar: [1.5, \"a b\"],"),
+            [
+                "    ", "foo_bar", ":", " ", "[", "1", ".", "5", ",", " ", "\"", "a", " ", "b",
+                "\"", "]", ","
+            ],
+        );
+    }
+
+    #[test]
+    fn comparison() {
+        let left = BTreeMap::from([(1, "one"), (2, "two"), (3, "three")]);
+        let right = BTreeMap::from([(1, "one"), (2, "deux"), (3, "three"), (4, "four")]);
+        let diff = Comparison::new(&left, &right).to_string();
+        assert_eq!(
+            diff,
+            concat!(
+                "Diff \x1b[31m<\x1b[0m left / right \x1b[32m>\x1b[0m :\n",
+                " {\n",
+                "     [1] = \"one\";\n",
+                "\x1b[31m<    [2] = \"\x1b[1;41mtwo\x1b[0m\x1b[31m\";\x1b[0m\n",
+                "\x1b[32m>    [2] = \"\x1b[1;42mdeux\x1b[0m\x1b[32m\";\x1b[0m\n",
+                "     [3] = \"three\";\n",
+                "\x1b[32m>    [4] = \"four\";\x1b[0m\n",
+                " }",
+            ),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: `(left == right)`: retry 3\n\nDiff")]
+    fn assert_eq_pls() {
+        crate::assert_eq_pls!(Some(1), Some(2), "retry {}", 3);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{DebugStruct, DebugTupleStruct, Formatter};
+
+/// A helper designed to assist with creation of
+/// [`DebugPls`](crate::DebugPls) implementations for enums, whose variants
+/// are written with the name of the enum ahead of them, so that they can't
+/// be mistaken for the variants of another enum.
+///
+/// # Examples
+///
+/// ```rust
+/// use dbg_pls::{pretty, DebugPls, Formatter};
+///
+/// enum Shape {
+///     Circle { radius: f64 },
+///     Square(f64),
+///     Empty,
+/// }
+///
+/// impl DebugPls for Shape {
+///     fn fmt(&self, f: Formatter<'_>) {
+///         match self {
+///             Shape::Circle { radius } => f
+///                 .debug_enum("Shape", "Circle")
+///                 .struct_variant()
+///                 .field("radius", radius)
+///                 .finish(),
+///             Shape::Square(side) => f
+///                 .debug_enum("Shape", "Square")
+///                 .tuple_variant()
+///                 .field(side)
+///                 .finish(),
+///             Shape::Empty => f.debug_enum("Shape", "Empty").unit_variant(),
+///         }
+///     }
+/// }
+///
+/// let shapes = [Shape::Circle { radius: 1.0 }, Shape::Square(2.0), Shape::Empty];
+/// assert_eq!(
+///     format!("{}", pretty(&shapes)),
+///     "[Shape::Circle { radius: 1.0 }, Shape::Square(2.0), Shape::Empty]",
+/// );
+/// ```
+pub struct DebugEnum<'a> {
+    formatter: Formatter<'a>,
+    /// `Shape::Circle`
+    path: String,
+}
+
+impl<'a> DebugEnum<'a> {
+    pub(crate) fn new(formatter: Formatter<'a>, name: &str, variant: &str) -> Self {
+        DebugEnum {
+            formatter,
+            path: format!("{name}::{variant}"),
+        }
+    }
+
+    /// Writes a variant with named fields, `Shape::Circle { radius: 1.0 }`.
+    #[must_use]
+    pub fn struct_variant(self) -> DebugStruct<'a> {
+        self.formatter.debug_struct(&self.path)
+    }
+
+    /// Writes a variant with unnamed fields, `Shape::Square(2.0)`.
+    #[must_use]
+    pub fn tuple_variant(self) -> DebugTupleStruct<'a> {
+        self.formatter.debug_tuple_struct(&self.path)
+    }
+
+    /// Writes a variant without fields, `Shape::Empty`.
+    pub fn unit_variant(self) {
+        self.formatter.debug_ident(&self.path);
+    }
+}
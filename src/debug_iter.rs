@@ -0,0 +1,46 @@
+use crate::{DebugPls, Formatter};
+
+/// Formats the items of an iterator as a list.
+///
+/// The items are formatted one at a time, as they come out of the
+/// iterator, without collecting them first. Under a
+/// [`Budget`](crate::Budget) no more of them are taken than fit, so even
+/// an endless iterator can be printed.
+///
+/// The iterator is cloned for every time the list is formatted.
+///
+/// # Examples
+///
+/// ```rust
+/// use dbg_pls::{debug_iter, pretty, Budget};
+///
+/// let squares = (1..=5).map(|i| i * i);
+/// assert_eq!(format!("{}", pretty(&debug_iter(squares))), "[1, 4, 9, 16, 25]");
+///
+/// let budget = Budget::new().bytes(12);
+/// assert_eq!(
+///     format!("{}", pretty(&debug_iter(1..)).budget(budget)),
+///     "[1, 2, 3, 4, ..]",
+/// );
+/// ```
+pub fn debug_iter<I>(iter: I) -> DebugIter<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: DebugPls,
+{
+    DebugIter(iter)
+}
+
+/// An iterator wrapped to be formatted as a list, see [`debug_iter`]
+#[derive(Clone, Copy, Debug)]
+pub struct DebugIter<I>(I);
+
+impl<I> DebugPls for DebugIter<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: DebugPls,
+{
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_list().entries(self.0.clone()).finish();
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{DebugPls, Formatter};
+use crate::{
+    node::{Children, Hint, Node, Truncate},
+    with_fn::WithFn,
+    DebugPls, Formatter,
+};
 
 /// A helper designed to assist with creation of
 /// [`DebugPls`] implementations for list-like structures.
@@ -21,28 +25,194 @@ use crate::{DebugPls, Formatter};
 /// ```
 pub struct DebugList<'a> {
     formatter: Formatter<'a>,
-    expr: syn::ExprArray,
+    elems: Children,
+    /// Written as `vec![..]`
+    vec: bool,
+    truncate: Truncate,
 }
 
 impl<'a> DebugList<'a> {
     pub(crate) fn new(formatter: Formatter<'a>) -> Self {
+        let truncate = formatter.tree.truncation(formatter.id);
         DebugList {
             formatter,
-            expr: syn::ExprArray {
-                attrs: vec![],
-                bracket_token: syn::token::Bracket::default(),
-                elems: syn::punctuated::Punctuated::default(),
-            },
+            elems: Children::default(),
+            vec: false,
+            truncate,
         }
     }
 
-    /// Adds a new entry to the list output.
+    /// Writes the list as the `vec!` that builds it, `vec![10, 11]`, as
+    /// [`Pretty::vec_macros`](crate::Pretty::vec_macros) writes a `Vec`.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Stack(Vec<i32>);
+    ///
+    /// impl DebugPls for Stack {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_list().vec_macro().entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let value = Stack(vec![10, 11]);
+    /// assert_eq!(format!("{}", pretty(&value)), "vec![10, 11]");
+    /// ```
+    #[must_use]
+    pub fn vec_macro(mut self) -> Self {
+        self.vec = true;
+        self
+    }
+
+    /// Breaks the list output over lines, an entry to each, however short
+    /// it is, like [`DebugStruct::expanded`](crate::DebugStruct::expanded).
+    #[must_use]
+    pub fn expanded(self) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.hint_as(id, Hint::Expanded);
+        self
+    }
+
+    /// Keeps the list output on one line, with its entries, however long
+    /// it is, like [`DebugStruct::compact`](crate::DebugStruct::compact).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Samples(Vec<u32>);
+    ///
+    /// impl DebugPls for Samples {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_list().compact().entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let samples = Samples((0..40).collect());
+    /// assert_eq!(format!("{}", pretty(&samples)), format!("{:?}", samples.0));
+    /// ```
+    #[must_use]
+    pub fn compact(self) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.hint_as(id, Hint::Compact);
+        self
+    }
+
+    /// Chooses whether the list output, when it is broken over lines, ends
+    /// in a comma after its last entry, like
+    /// [`DebugStruct::trailing_comma`](crate::DebugStruct::trailing_comma).
+    #[must_use]
+    pub fn trailing_comma(self, trailing: bool) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.trailing_comma_as(id, trailing);
+        self
+    }
+
+    /// Keeps the list output to its first `max` entries, with the number of
+    /// those left out after them, `[1, 2, .. /* 4032 more */]`. Iterators
+    /// that know how many entries they have left, as those of most
+    /// collections do, are not gone through for the rest.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Samples(Vec<u64>);
+    ///
+    /// impl DebugPls for Samples {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_list().truncate(3).entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let samples = Samples((0..4035).collect());
+    /// assert_eq!(
+    ///     format!("{}", pretty(&samples)),
+    ///     "[0, 1, 2, .. /* 4032 more */]",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn truncate(mut self, max: usize) -> Self {
+        self.truncate.max = Some(max);
+        self
+    }
+
+    /// Adds a new entry to the list output. The entries can be of
+    /// different types, and mixed with those of [`entries`](Self::entries).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Row<'a>(u32, &'a str, &'a [f64]);
+    ///
+    /// impl DebugPls for Row<'_> {
+    ///     fn fmt(&self, f: Formatter) {
+    ///         f.debug_list()
+    ///             .entry(&self.0)
+    ///             .entry(&self.1)
+    ///             .entries(self.2)
+    ///             .entry(&None::<()>)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let row = Row(7, "seven", &[0.5, 1.5]);
+    /// assert_eq!(format!("{}", pretty(&row)), "[7, \"seven\", 0.5, 1.5, None]");
+    /// ```
     #[must_use]
     pub fn entry(mut self, entry: &dyn DebugPls) -> Self {
-        self.expr.elems.push(Formatter::process(entry));
+        let tree = &mut *self.formatter.tree;
+        if self.truncate.full(tree.children(self.elems).len()) {
+            self.truncate.skip();
+        } else {
+            tree.append(&mut self.elems, entry);
+        }
         self
     }
 
+    /// Adds a new entry to the list output, formatted by `fmt`.
+    #[must_use]
+    pub fn entry_with(self, fmt: impl FnOnce(Formatter<'_>)) -> Self {
+        self.entry(&WithFn::new(fmt))
+    }
+
+    /// Adds a new entry to the list output, followed by a comment on the
+    /// rest of its line, like [`DebugStruct::field_commented`](crate::DebugStruct::field_commented).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Registers([u16; 2]);
+    ///
+    /// impl DebugPls for Registers {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_list()
+    ///             .entry_commented(&self.0[0], "status")
+    ///             .entry_commented(&self.0[1], "control")
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Registers([1, 0]))),
+    ///     "\
+    /// [
+    ///     1, // status
+    ///     0, // control
+    /// ]",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn entry_commented(self, entry: &dyn DebugPls, comment: &str) -> Self {
+        let len = self.formatter.tree.children(self.elems).len();
+        let list = self.entry(entry);
+        let tree = &mut *list.formatter.tree;
+        // unless it was left out past the truncation
+        if tree.children(list.elems).len() > len {
+            tree.comment_last(list.elems, comment);
+        }
+        list
+    }
+
     /// Adds all the entries to the list output.
     #[must_use]
     pub fn entries<D, I>(mut self, entries: I) -> Self
@@ -54,16 +224,147 @@ impl<'a> DebugList<'a> {
         self
     }
 
+    /// Adds all the entries to the list output, each formatted by `fmt`, for
+    /// entries that are written as a projection of themselves.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct User {
+    ///     name: String,
+    ///     password: String,
+    /// }
+    ///
+    /// struct Users(Vec<User>);
+    ///
+    /// impl DebugPls for Users {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_list()
+    ///             .entries_with(&self.0, |user, f| user.name.fmt(f))
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let user = |name: &str| User {
+    ///     name: name.to_owned(),
+    ///     password: "hunter2".to_owned(),
+    /// };
+    /// let users = Users(vec![user("alice"), user("bob")]);
+    /// assert_eq!(format!("{}", pretty(&users)), r#"["alice", "bob"]"#);
+    /// ```
+    #[must_use]
+    pub fn entries_with<T, I>(self, entries: I, fmt: impl Fn(T, Formatter<'_>)) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let fmt = &fmt;
+        self.entries(
+            entries
+                .into_iter()
+                .map(|entry| WithFn::new(move |f| fmt(entry, f))),
+        )
+    }
+
+    /// Adds all the entries of a slice to the list output.
+    ///
+    /// Long slices are formatted in parallel with rayon, which pays off
+    /// for lists of many thousands of entries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Samples(Vec<u64>);
+    ///
+    /// impl DebugPls for Samples {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_list().par_entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let samples = Samples((0..100_000).collect());
+    /// assert_eq!(format!("{}", pretty(&samples)), format!("{}", pretty(&samples.0)));
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    #[must_use]
+    pub fn par_entries<D: DebugPls + Sync>(mut self, entries: &[D]) -> Self {
+        let tree = &mut *self.formatter.tree;
+        let room = self.truncate.room(tree.children(self.elems).len());
+        let (entries, rest) = entries.split_at(room.min(entries.len()));
+        tree.append_slice(&mut self.elems, entries);
+        self.truncate.skip_all(rest.iter());
+        self
+    }
+
     /// Closes off the list
-    pub fn finish(self) {
-        self.formatter.write_expr(self.expr);
+    pub fn finish(mut self) {
+        let tree = &mut *self.formatter.tree;
+        tree.truncated(&mut self.elems, self.truncate);
+        let node = if self.vec {
+            Node::Vec(self.elems)
+        } else {
+            Node::List(self.elems)
+        };
+        self.formatter.write(node);
     }
 }
 
-impl<'f, D: DebugPls> Extend<D> for DebugList<'f> {
+impl<D: DebugPls> Extend<D> for DebugList<'_> {
     fn extend<T: IntoIterator<Item = D>>(&mut self, iter: T) {
-        self.expr
-            .elems
-            .extend(iter.into_iter().map(|entry| Formatter::process(&entry)));
+        self.formatter
+            .tree
+            .append_truncated(&mut self.elems, &mut self.truncate, iter);
+    }
+}
+
+#[cfg(all(test, feature = "pretty"))]
+mod tests {
+    use crate::{pretty, DebugPls, Formatter};
+
+    #[test]
+    fn truncated_collections() {
+        struct Truncated;
+
+        impl DebugPls for Truncated {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple()
+                    .field_with(|f| {
+                        let mut list = f.debug_list().truncate(2);
+                        for i in 0..4 {
+                            list = list.entry(&i);
+                        }
+                        list.finish();
+                    })
+                    // an iterator that may never end can't be counted
+                    .field_with(|f| f.debug_list().truncate(1).entries(0..).finish())
+                    .field_with(|f| f.debug_set().truncate(0).entries([1, 2]).finish())
+                    .field_with(|f| {
+                        f.debug_map()
+                            .truncate(1)
+                            .sorted()
+                            .entries([(2, 0), (1, 0)])
+                            .key(&3)
+                            .value(&0)
+                            .finish();
+                    })
+                    .finish();
+            }
+        }
+
+        assert_eq!(
+            pretty(&Truncated).to_string(),
+            "\
+(
+    [0, 1, .. /* 2 more */],
+    [0, ..],
+    { .. /* 2 more */ },
+    {
+        [2] = 0;
+        .. /* 2 more */
+    },
+)",
+        );
     }
 }
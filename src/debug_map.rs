@@ -1,8 +1,10 @@
-use std::iter::FromIterator;
+use std::cmp::Ordering;
 
-use syn::punctuated::Punctuated;
-
-use crate::{DebugPls, Formatter};
+use crate::{
+    node::{Children, Hint, Node, NodeId, Text, Tree, Truncate},
+    with_fn::WithFn,
+    DebugPls, Formatter,
+};
 
 /// A helper designed to assist with creation of
 /// [`DebugPls`] implementations for maps.
@@ -34,22 +36,240 @@ use crate::{DebugPls, Formatter};
 /// ```
 pub struct DebugMap<'a> {
     formatter: Formatter<'a>,
-    set: syn::Block,
-    key: Option<syn::Expr>,
+    entries: Children,
+    key: Option<NodeId>,
+    /// The path to the constructor, when the map is written as one
+    from: Option<Text>,
+    sort: Option<Sort>,
+    truncate: Truncate,
+    /// Whether the pending key was left out for being past the truncation
+    skipped: bool,
+}
+
+/// How the entries of a map are sorted by their keys
+#[derive(Clone, Copy)]
+enum Sort {
+    /// See [`DebugMap::sorted`]
+    Value,
+    /// See [`DebugMap::sorted_by_key_repr`]
+    #[cfg(feature = "pretty")]
+    Repr,
 }
 
 impl<'a> DebugMap<'a> {
     pub(crate) fn new(formatter: Formatter<'a>) -> Self {
+        let truncate = formatter.tree.truncation(formatter.id);
         DebugMap {
             formatter,
-            set: syn::Block {
-                brace_token: syn::token::Brace::default(),
-                stmts: vec![],
-            },
+            entries: Children::default(),
             key: None,
+            from: None,
+            sort: None,
+            truncate,
+            skipped: false,
         }
     }
 
+    /// Names the type of the map, such as `HashMap`, so that it can be
+    /// written as the constructor that builds it when asked to by
+    /// [`Pretty::constructors`](crate::Pretty::constructors). Maps that are
+    /// not named are written as an array of their entries then,
+    /// `[(1, 2), (3, 4)]`.
+    #[must_use]
+    pub fn type_name(mut self, name: &str) -> Self {
+        if self.formatter.tree.style.constructors {
+            self.from = Some(self.formatter.tree.constructor_path(name));
+        }
+        self
+    }
+
+    /// Sorts the entries by their keys, for maps such as `HashMap` that are
+    /// iterated in no particular order. Number keys are sorted by their
+    /// value, and the rest by their text or, for structs, lists and the
+    /// like, by what they hold, one field or element after another.
+    ///
+    /// Once the limit on the output is reached, only the entries that came
+    /// before it are sorted.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::collections::HashMap;
+    ///
+    /// struct Ports(HashMap<u16, &'static str>);
+    ///
+    /// impl DebugPls for Ports {
+    ///     fn fmt(&self, f: Formatter) {
+    ///         f.debug_map().sorted().entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let ports = Ports(HashMap::from([(443, "https"), (80, "http"), (8080, "proxy")]));
+    /// assert_eq!(
+    ///     format!("{}", pretty(&ports)),
+    /// "{
+    ///     [80] = \"http\";
+    ///     [443] = \"https\";
+    ///     [8080] = \"proxy\";
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn sorted(mut self) -> Self {
+        self.sort = Some(Sort::Value);
+        self
+    }
+
+    /// Sorts the entries by their keys as they are written out, as text.
+    /// Unlike with [`sorted`](Self::sorted), `[10]` comes before `[9]`.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::collections::HashMap;
+    ///
+    /// struct Ports(HashMap<u16, &'static str>);
+    ///
+    /// impl DebugPls for Ports {
+    ///     fn fmt(&self, f: Formatter) {
+    ///         f.debug_map().sorted_by_key_repr().entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let ports = Ports(HashMap::from([(443, "https"), (80, "http"), (8080, "proxy")]));
+    /// assert_eq!(
+    ///     format!("{}", pretty(&ports)),
+    /// "{
+    ///     [443] = \"https\";
+    ///     [80] = \"http\";
+    ///     [8080] = \"proxy\";
+    /// }",
+    /// );
+    /// ```
+    #[cfg(feature = "pretty")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+    #[must_use]
+    pub fn sorted_by_key_repr(mut self) -> Self {
+        self.sort = Some(Sort::Repr);
+        self
+    }
+
+    /// Adds all the entries to the map output in the order `cmp` sorts them
+    /// in, for maps of metrics and the like that read best by their values,
+    /// or by the numbers that their keys stand for. Unlike
+    /// [`sorted`](Self::sorted), which sorts the entries that were written,
+    /// this sorts the entries themselves, so with [`truncate`](Self::truncate)
+    /// the first of them in this order are kept.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::collections::HashMap;
+    ///
+    /// struct Hits(HashMap<&'static str, u64>);
+    ///
+    /// impl DebugPls for Hits {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_map()
+    ///             .entries_sorted_by(&self.0, |a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)))
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let hits = Hits(HashMap::from([("/", 120), ("/about", 7), ("/blog", 31)]));
+    /// assert_eq!(
+    ///     format!("{}", pretty(&hits)),
+    /// "{
+    ///     [\"/\"] = 120;
+    ///     [\"/blog\"] = 31;
+    ///     [\"/about\"] = 7;
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn entries_sorted_by<K, V, I>(
+        self,
+        entries: I,
+        cmp: impl FnMut(&(K, V), &(K, V)) -> Ordering,
+    ) -> Self
+    where
+        K: DebugPls,
+        V: DebugPls,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by(cmp);
+        self.entries(entries)
+    }
+
+    /// Breaks the map output over lines, an entry to each, however short
+    /// it is, like [`DebugStruct::expanded`](crate::DebugStruct::expanded).
+    /// Maps are always written so, but not the arrays of their entries
+    /// that [`Pretty::constructors`](crate::Pretty::constructors) writes.
+    #[must_use]
+    pub fn expanded(self) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.hint_as(id, Hint::Expanded);
+        self
+    }
+
+    /// Keeps the map output on one line, `{ [1] = 2; [3] = 4; }`, however
+    /// long it is, like [`DebugStruct::compact`](crate::DebugStruct::compact).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::collections::BTreeMap;
+    ///
+    /// struct Headers(BTreeMap<&'static str, &'static str>);
+    ///
+    /// impl DebugPls for Headers {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_map().compact().entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let headers = Headers(BTreeMap::from([("Host", "a.com"), ("Accept", "*/*")]));
+    /// assert_eq!(
+    ///     format!("{}", pretty(&headers)),
+    ///     r#"{ ["Accept"] = "*/*"; ["Host"] = "a.com"; }"#,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn compact(self) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.hint_as(id, Hint::Compact);
+        self
+    }
+
+    /// Keeps the map output to its first `max` entries, with the number of
+    /// those left out after them, as [`DebugList::truncate`](crate::DebugList::truncate)
+    /// does. With [`sorted`](Self::sorted), the entries that are kept are
+    /// sorted, rather than the first of all of them being kept.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::collections::BTreeMap;
+    ///
+    /// struct Env(BTreeMap<&'static str, &'static str>);
+    ///
+    /// impl DebugPls for Env {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_map().truncate(1).entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let env = Env(BTreeMap::from([("HOME", "/root"), ("PATH", "/bin"), ("TERM", "xterm")]));
+    /// assert_eq!(
+    ///     format!("{}", pretty(&env)),
+    /// "{
+    ///     [\"HOME\"] = \"/root\";
+    ///     .. /* 2 more */
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn truncate(mut self, max: usize) -> Self {
+        self.truncate.max = Some(max);
+        self
+    }
+
     /// Adds the key part to the map output.
     ///
     /// # Panics
@@ -58,9 +278,18 @@ impl<'a> DebugMap<'a> {
     /// by a corresponding call to `value`. Otherwise this method will panic.
     #[must_use]
     pub fn key(mut self, key: &dyn DebugPls) -> Self {
-        if self.key.replace(Formatter::process(key)).is_some() {
-            panic!("attempted to begin a new map entry without completing the previous one");
+        if self.full() {
+            assert!(
+                !std::mem::replace(&mut self.skipped, true),
+                "attempted to begin a new map entry without completing the previous one"
+            );
+            return self;
         }
+        let key = self.formatter.tree.process(key);
+        assert!(
+            self.key.replace(key).is_none(),
+            "attempted to begin a new map entry without completing the previous one"
+        );
         self
     }
 
@@ -72,55 +301,322 @@ impl<'a> DebugMap<'a> {
     /// by a corresponding call to `value`. Otherwise this method will panic.
     #[must_use]
     pub fn value(mut self, value: &dyn DebugPls) -> Self {
+        if std::mem::take(&mut self.skipped) {
+            self.truncate.skip();
+            return self;
+        }
         let key = self
             .key
             .take()
             .expect("attempted to format a map value before its key");
-        let value = Formatter::process(value);
-        let entry = syn::ExprAssign {
-            attrs: vec![],
-            left: Box::new(
-                syn::ExprArray {
-                    attrs: vec![],
-                    bracket_token: syn::token::Bracket::default(),
-                    elems: Punctuated::from_iter([key]),
-                }
-                .into(),
-            ),
-            eq_token: syn::token::Eq::default(),
-            right: Box::new(value),
-        };
-        self.set
-            .stmts
-            .push(syn::Stmt::Semi(entry.into(), syn::token::Semi::default()));
+        let tree = &mut *self.formatter.tree;
+        if tree.get(key) == Node::Elided {
+            // the rest of the map is marked by a lone key
+            tree.elide(&mut self.entries);
+        } else {
+            // an entry is kept whole once its key made it in
+            tree.link(&mut self.entries, key);
+            let value = tree.capture_node(value);
+            tree.link(&mut self.entries, value);
+        }
         self
     }
 
+    /// Adds the key part to the map output, formatted by `fmt`, as
+    /// [`key`](Self::key) does.
+    ///
+    /// # Panics
+    ///
+    /// If the previous key was not followed by a value.
+    #[must_use]
+    pub fn key_with(self, fmt: impl FnOnce(Formatter<'_>)) -> Self {
+        self.key(&WithFn::new(fmt))
+    }
+
+    /// Adds the value part to the map output, formatted by `fmt`, as
+    /// [`value`](Self::value) does.
+    ///
+    /// # Panics
+    ///
+    /// If no key was given for the value.
+    #[must_use]
+    pub fn value_with(self, fmt: impl FnOnce(Formatter<'_>)) -> Self {
+        self.value(&WithFn::new(fmt))
+    }
+
     /// Adds the entry to the map output.
     #[must_use]
     pub fn entry(self, key: &dyn DebugPls, value: &dyn DebugPls) -> Self {
         self.key(key).value(value)
     }
 
+    /// Adds the entry to the map output, followed by a comment on the rest
+    /// of its line, like [`DebugStruct::field_commented`](crate::DebugStruct::field_commented).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Limits {
+    ///     timeout: u64,
+    /// }
+    ///
+    /// impl DebugPls for Limits {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_map()
+    ///             .entry_commented(&"timeout", &self.timeout, "in ms")
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Limits { timeout: 500 })),
+    ///     "\
+    /// {
+    ///     [\"timeout\"] = 500; // in ms
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn entry_commented(self, key: &dyn DebugPls, value: &dyn DebugPls, comment: &str) -> Self {
+        let len = self.formatter.tree.children(self.entries).len();
+        let map = self.entry(key, value);
+        let tree = &mut *map.formatter.tree;
+        // unless it was left out past the truncation
+        if tree.children(map.entries).len() == len + 2 {
+            tree.comment_last(map.entries, comment);
+        }
+        map
+    }
+
     /// Adds all the entries to the map output.
     #[must_use]
-    pub fn entries<K, V, I>(self, entries: I) -> Self
+    pub fn entries<K, V, I>(mut self, entries: I) -> Self
     where
         K: DebugPls,
         V: DebugPls,
         I: IntoIterator<Item = (K, V)>,
     {
-        entries
-            .into_iter()
-            .fold(self, |f, (key, value)| f.entry(&key, &value))
+        let mut entries = entries.into_iter();
+        while let Some((key, value)) = entries.next() {
+            if self.formatter.tree.elides() {
+                self.formatter.tree.elide(&mut self.entries);
+                break;
+            }
+            self = self.entry(&key, &value);
+            if self.full() {
+                self.truncate.skip_all(entries);
+                break;
+            }
+        }
+        self
+    }
+
+    /// Adds all the entries of a slice of pairs to the map output.
+    ///
+    /// Long slices are formatted in parallel with rayon, as
+    /// [`DebugList::par_entries`](crate::DebugList::par_entries) does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Index(Vec<(u32, u64)>);
+    ///
+    /// impl DebugPls for Index {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_map().par_entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let index = Index((0..100_000).map(|i| (i, u64::from(i) * 2)).collect());
+    /// let map: std::collections::BTreeMap<_, _> = index.0.iter().copied().collect();
+    /// assert_eq!(format!("{}", pretty(&index)), format!("{}", pretty(&map)));
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    #[must_use]
+    pub fn par_entries<K, V>(mut self, entries: &[(K, V)]) -> Self
+    where
+        K: DebugPls + Sync,
+        V: DebugPls + Sync,
+    {
+        let tree = &mut *self.formatter.tree;
+        let room = self.truncate.room(tree.children(self.entries).len() / 2);
+        let (entries, rest) = entries.split_at(room.min(entries.len()));
+        tree.append_pairs(&mut self.entries, entries);
+        self.truncate.skip_all(rest.iter());
+        self
+    }
+
+    /// Whether the map has all the entries it keeps.
+    fn full(&self) -> bool {
+        let entries = self.formatter.tree.children(self.entries).len();
+        self.truncate.full(entries / 2)
     }
 
     /// Closes off the map.
-    pub fn finish(self) {
-        self.formatter.write_expr(syn::ExprBlock {
-            attrs: vec![],
-            label: None,
-            block: self.set,
-        });
+    ///
+    /// # Panics
+    ///
+    /// If the last key was not followed by a value.
+    ///
+    /// ```rust,should_panic
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Headers;
+    ///
+    /// impl DebugPls for Headers {
+    ///     fn fmt(&self, f: Formatter) {
+    ///         f.debug_map().key(&"host").finish()
+    ///     }
+    /// }
+    ///
+    /// // attempted to finish a map with a key that has no value
+    /// pretty(&Headers).to_string();
+    /// ```
+    pub fn finish(mut self) {
+        assert!(
+            self.key.is_none() && !self.skipped,
+            "attempted to finish a map with a key that has no value"
+        );
+        let tree = &mut *self.formatter.tree;
+        tree.truncated(&mut self.entries, self.truncate);
+        let entries = match self.sort {
+            Some(sort) => sort_entries(tree, self.entries, sort),
+            None => self.entries,
+        };
+        if !tree.style.constructors {
+            self.formatter.write(Node::Map(entries));
+            return;
+        }
+        let entries: Vec<_> = tree.children(entries).collect();
+        let mut elems = Children::default();
+        for entry in entries.chunks(2) {
+            let elem = match *entry {
+                [key, value] => {
+                    let mut pair = Children::default();
+                    tree.link(&mut pair, key);
+                    tree.link(&mut pair, value);
+                    tree.push(Node::Tuple(pair))
+                }
+                // the lone key that marks the rest as elided
+                _ => entry[0],
+            };
+            tree.link(&mut elems, elem);
+        }
+        let node = match self.from {
+            Some(from) => tree.constructor(from, elems),
+            // the pairs, which any map can be collected from
+            None => Node::List(elems),
+        };
+        self.formatter.write(node);
+    }
+}
+
+/// Sorts the entries of a map by their keys, leaving the lone key that
+/// marks the rest as elided at the end.
+fn sort_entries(tree: &mut Tree, entries: Children, sort: Sort) -> Children {
+    let entries: Vec<_> = tree.children(entries).collect();
+    let (pairs, rest) = entries.split_at(entries.len() & !1);
+    let mut pairs: Vec<_> = pairs.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+    match sort {
+        Sort::Value => pairs.sort_by(|a, b| tree.cmp(a.0, b.0)),
+        #[cfg(feature = "pretty")]
+        Sort::Repr => pairs.sort_by_cached_key(|&(key, _)| crate::layout::node_string(tree, key)),
+    }
+    let mut sorted = Children::default();
+    for (key, value) in pairs {
+        tree.link(&mut sorted, key);
+        tree.link(&mut sorted, value);
+    }
+    for &key in rest {
+        tree.link(&mut sorted, key);
+    }
+    sorted
+}
+
+#[cfg(all(test, feature = "pretty"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{pretty, DebugPls, Formatter};
+
+    #[test]
+    fn sorted_maps() {
+        struct Sorted<'a>(&'a HashMap<(i32, &'static str), u8>, bool);
+
+        impl DebugPls for Sorted<'_> {
+            fn fmt(&self, f: Formatter<'_>) {
+                let map = f.debug_map();
+                let map = if self.1 {
+                    map.sorted_by_key_repr()
+                } else {
+                    map.sorted()
+                };
+                map.entries(self.0).finish();
+            }
+        }
+
+        let map = HashMap::from([((10, "a"), 1), ((-5, "b"), 2), ((2, "z"), 3), ((2, "a"), 4)]);
+        assert_eq!(
+            pretty(&Sorted(&map, false)).constructors().to_string(),
+            r#"[((-5, "b"), 2), ((2, "a"), 4), ((2, "z"), 3), ((10, "a"), 1)]"#,
+        );
+        assert_eq!(
+            pretty(&Sorted(&map, true)).constructors().to_string(),
+            r#"[((-5, "b"), 2), ((10, "a"), 1), ((2, "a"), 4), ((2, "z"), 3)]"#,
+        );
+    }
+
+    #[test]
+    fn split_map_entries() {
+        struct Columns<'a> {
+            names: &'a [&'a str],
+            widths: &'a [usize],
+        }
+
+        impl DebugPls for Columns<'_> {
+            fn fmt(&self, f: Formatter<'_>) {
+                let mut map = f.debug_map();
+                for name in self.names {
+                    map = map.key(name);
+                }
+                map.finish();
+            }
+        }
+
+        struct Zipped<'a>(Columns<'a>);
+
+        impl DebugPls for Zipped<'_> {
+            fn fmt(&self, f: Formatter<'_>) {
+                let mut map = f.debug_map();
+                for (name, width) in self.0.names.iter().zip(self.0.widths) {
+                    map = map
+                        .key_with(|f| f.debug_ident(&name.to_uppercase()))
+                        .value(width);
+                }
+                map.finish();
+            }
+        }
+
+        let columns = Columns {
+            names: &["id", "name"],
+            widths: &[4, 16],
+        };
+        assert_eq!(
+            pretty(&Zipped(columns)).to_string(),
+            "{\n    [ID] = 4;\n    [NAME] = 16;\n}",
+        );
+
+        let columns = Columns {
+            names: &["id", "name"],
+            widths: &[],
+        };
+        let panic = std::panic::catch_unwind(|| pretty(&columns).to_string()).unwrap_err();
+        assert_eq!(
+            panic.downcast_ref::<&str>(),
+            Some(&"attempted to begin a new map entry without completing the previous one"),
+        );
     }
 }
@@ -1,4 +1,10 @@
-use crate::{DebugPls, Formatter};
+use std::cmp::Ordering;
+
+use crate::{
+    node::{Children, Hint, Node, Text, Truncate},
+    with_fn::WithFn,
+    DebugPls, Formatter,
+};
 
 /// A helper designed to assist with creation of
 /// [`DebugPls`] implementations for sets.
@@ -30,51 +36,236 @@ use crate::{DebugPls, Formatter};
 /// ```
 pub struct DebugSet<'a> {
     formatter: Formatter<'a>,
-    set: syn::Block,
+    entries: Children,
+    /// The path to the constructor, when the set is written as one
+    from: Option<Text>,
+    truncate: Truncate,
 }
 
 impl<'a> DebugSet<'a> {
     pub(crate) fn new(formatter: Formatter<'a>) -> Self {
+        let truncate = formatter.tree.truncation(formatter.id);
         DebugSet {
             formatter,
-            set: syn::Block {
-                brace_token: syn::token::Brace::default(),
-                stmts: vec![],
-            },
+            entries: Children::default(),
+            from: None,
+            truncate,
+        }
+    }
+
+    /// Names the type of the set, such as `HashSet`, so that it can be
+    /// written as the constructor that builds it when asked to by
+    /// [`Pretty::constructors`](crate::Pretty::constructors). Sets that are
+    /// not named are written as an array of their entries then, `[1, 2]`.
+    #[must_use]
+    pub fn type_name(mut self, name: &str) -> Self {
+        if self.formatter.tree.style.constructors {
+            self.from = Some(self.formatter.tree.constructor_path(name));
         }
+        self
+    }
+
+    /// Breaks the set output over lines, an entry to each, however short
+    /// it is, like [`DebugStruct::expanded`](crate::DebugStruct::expanded).
+    #[must_use]
+    pub fn expanded(self) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.hint_as(id, Hint::Expanded);
+        self
     }
 
-    /// Adds the entry to the set output.
+    /// Keeps the set output on one line, `{ 1; 2 }`, however long it is,
+    /// like [`DebugStruct::compact`](crate::DebugStruct::compact).
+    #[must_use]
+    pub fn compact(self) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.hint_as(id, Hint::Compact);
+        self
+    }
+
+    /// Keeps the set output to its first `max` entries, with the number of
+    /// those left out after them, as [`DebugList::truncate`](crate::DebugList::truncate)
+    /// does.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::collections::BTreeSet;
+    ///
+    /// struct Seen(BTreeSet<u32>);
+    ///
+    /// impl DebugPls for Seen {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_set().truncate(1).entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let seen = Seen((1..=100).collect());
+    /// assert_eq!(
+    ///     format!("{}", pretty(&seen)),
+    /// "{
+    ///     1;
+    ///     .. /* 99 more */
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn truncate(mut self, max: usize) -> Self {
+        self.truncate.max = Some(max);
+        self
+    }
+
+    /// Adds the entry to the set output, for sets of entries of different
+    /// types, or that are not kept in a collection.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Permissions {
+    ///     read: bool,
+    ///     write: bool,
+    ///     groups: &'static [&'static str],
+    /// }
+    ///
+    /// impl DebugPls for Permissions {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         let mut set = f.debug_set();
+    ///         if self.read {
+    ///             set = set.entry(&"read");
+    ///         }
+    ///         if self.write {
+    ///             set = set.entry(&"write");
+    ///         }
+    ///         set.entry(&self.groups.len()).finish()
+    ///     }
+    /// }
+    ///
+    /// let permissions = Permissions { read: true, write: false, groups: &["staff"] };
+    /// assert_eq!(
+    ///     format!("{}", pretty(&permissions)),
+    /// "{
+    ///     \"read\";
+    ///     1
+    /// }",
+    /// );
+    /// ```
     #[must_use]
     pub fn entry(mut self, value: &dyn DebugPls) -> Self {
-        let expr = Formatter::process(value);
-        self.set
-            .stmts
-            .push(syn::Stmt::Semi(expr, syn::token::Semi::default()));
+        let tree = &mut *self.formatter.tree;
+        if self.truncate.full(tree.children(self.entries).len()) {
+            self.truncate.skip();
+        } else {
+            tree.append(&mut self.entries, value);
+        }
         self
     }
 
+    /// Adds a new entry to the set output, formatted by `fmt`.
+    #[must_use]
+    pub fn entry_with(self, fmt: impl FnOnce(Formatter<'_>)) -> Self {
+        self.entry(&WithFn::new(fmt))
+    }
+
     /// Adds all the entries to the set output.
     #[must_use]
-    pub fn entries<V, I>(self, entries: I) -> Self
+    pub fn entries<V, I>(mut self, entries: I) -> Self
+    where
+        V: DebugPls,
+        I: IntoIterator<Item = V>,
+    {
+        self.extend(entries);
+        self
+    }
+
+    /// Adds all the entries of a slice to the set output.
+    ///
+    /// Long slices are formatted in parallel with rayon, as
+    /// [`DebugList::par_entries`](crate::DebugList::par_entries) does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Seen(Vec<u64>);
+    ///
+    /// impl DebugPls for Seen {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_set().par_entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let seen = Seen((0..100_000).collect());
+    /// let set: std::collections::BTreeSet<_> = seen.0.iter().copied().collect();
+    /// assert_eq!(format!("{}", pretty(&seen)), format!("{}", pretty(&set)));
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    #[must_use]
+    pub fn par_entries<V: DebugPls + Sync>(mut self, entries: &[V]) -> Self {
+        let tree = &mut *self.formatter.tree;
+        let room = self.truncate.room(tree.children(self.entries).len());
+        let (entries, rest) = entries.split_at(room.min(entries.len()));
+        tree.append_slice(&mut self.entries, entries);
+        self.truncate.skip_all(rest.iter());
+        self
+    }
+
+    /// Adds all the entries to the set output in the order `cmp` sorts them
+    /// in, like [`DebugMap::entries_sorted_by`](crate::DebugMap::entries_sorted_by).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::collections::HashSet;
+    ///
+    /// struct Versions(HashSet<&'static str>);
+    ///
+    /// impl DebugPls for Versions {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         let minor = |v: &str| v.split('.').nth(1).and_then(|m| m.parse::<u32>().ok());
+    ///         f.debug_set()
+    ///             .entries_sorted_by(&self.0, |a, b| minor(a).cmp(&minor(b)))
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let versions = Versions(HashSet::from(["1.10", "1.9", "1.2"]));
+    /// assert_eq!(
+    ///     format!("{}", pretty(&versions)),
+    /// "{
+    ///     \"1.2\";
+    ///     \"1.9\";
+    ///     \"1.10\"
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn entries_sorted_by<V, I>(self, entries: I, cmp: impl FnMut(&V, &V) -> Ordering) -> Self
     where
         V: DebugPls,
         I: IntoIterator<Item = V>,
     {
-        entries.into_iter().fold(self, |f, entry| f.entry(&entry))
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by(cmp);
+        self.entries(entries)
     }
 
     /// Closes off the set.
     pub fn finish(mut self) {
-        // remove the last semicolon
-        if let Some(syn::Stmt::Semi(entry, _)) = self.set.stmts.pop() {
-            self.set.stmts.push(syn::Stmt::Expr(entry));
-        }
+        let tree = &mut *self.formatter.tree;
+        tree.truncated(&mut self.entries, self.truncate);
+        let node = match self.from {
+            Some(from) => self.formatter.tree.constructor(from, self.entries),
+            None if self.formatter.tree.style.constructors => Node::List(self.entries),
+            None => Node::Set(self.entries),
+        };
+        self.formatter.write(node);
+    }
+}
 
-        self.formatter.write_expr(syn::ExprBlock {
-            attrs: vec![],
-            label: None,
-            block: self.set,
-        });
+impl<V: DebugPls> Extend<V> for DebugSet<'_> {
+    fn extend<T: IntoIterator<Item = V>>(&mut self, iter: T) {
+        self.formatter
+            .tree
+            .append_truncated(&mut self.entries, &mut self.truncate, iter);
     }
 }
@@ -1,6 +1,8 @@
-use syn::__private::Span;
-
-use crate::{DebugPls, Formatter};
+use crate::{
+    node::{Children, Hint, Node, Text},
+    with_fn::WithFn,
+    DebugPls, Formatter,
+};
 
 /// A helper designed to assist with creation of
 /// [`DebugPls`] implementations for structs.
@@ -34,44 +36,456 @@ use crate::{DebugPls, Formatter};
 /// ```
 pub struct DebugStruct<'a> {
     formatter: Formatter<'a>,
-    expr: syn::ExprStruct,
+    name: Text,
+    fields: Children,
 }
 
 impl<'a> DebugStruct<'a> {
     pub(crate) fn new(formatter: Formatter<'a>, name: &str) -> Self {
+        let name = formatter.tree.name(name);
         DebugStruct {
             formatter,
-            expr: syn::ExprStruct {
-                attrs: vec![],
-                path: syn::Ident::new(name, Span::call_site()).into(),
-                brace_token: syn::token::Brace::default(),
-                fields: syn::punctuated::Punctuated::new(),
-                dot2_token: None,
-                rest: None,
-            },
+            name,
+            fields: Children::default(),
         }
     }
 
-    /// Adds the field to the struct output.
+    /// Writes the generic arguments of the struct after its name, as in
+    /// `Matrix::<3, 3> { .. }`, which tells apart the instantiations of a
+    /// generic type that show up in the same output. The arguments are
+    /// written as they are given.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Matrix<const R: usize, const C: usize>([[i32; C]; R]);
+    ///
+    /// impl<const R: usize, const C: usize> DebugPls for Matrix<R, C> {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Matrix")
+    ///             .generics(&[&R.to_string(), &C.to_string()])
+    ///             .field("rows", &self.0)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Matrix([[1, 0], [0, 1]]))),
+    ///     "\
+    /// Matrix::<2, 2> {
+    ///     rows: [[1, 0], [0, 1]],
+    /// }",
+    /// );
+    /// ```
+    ///
+    /// Derived implementations write them with the `#[dbg_pls(generics)]`
+    /// arg on the type.
+    #[must_use]
+    pub fn generics(mut self, args: &[&str]) -> Self {
+        self.name = self.formatter.tree.generic_name(self.name, args);
+        self
+    }
+
+    /// Breaks the struct output over lines, a field to each, however short
+    /// it is, for structs that read better as a column.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Config {
+    ///     port: u16,
+    ///     debug: bool,
+    /// }
+    ///
+    /// impl DebugPls for Config {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Config")
+    ///             .expanded()
+    ///             .field("port", &self.port)
+    ///             .field("debug", &self.debug)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let config = Config { port: 80, debug: false };
+    /// assert_eq!(
+    ///     format!("{}", pretty(&config)),
+    ///     "\
+    /// Config {
+    ///     port: 80,
+    ///     debug: false,
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn expanded(self) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.hint_as(id, Hint::Expanded);
+        self
+    }
+
+    /// Keeps the struct output on one line, with its fields, however long
+    /// it is, for structs that are only read at a glance.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Point {
+    ///     x: f64,
+    ///     y: f64,
+    ///     z: f64,
+    /// }
+    ///
+    /// impl DebugPls for Point {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Point")
+    ///             .compact()
+    ///             .field("x", &self.x)
+    ///             .field("y", &self.y)
+    ///             .field("z", &self.z)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let point = Point { x: 0.25, y: 1.5, z: -3.75 };
+    /// assert_eq!(
+    ///     format!("{}", pretty(&point)),
+    ///     "Point { x: 0.25, y: 1.5, z: -3.75 }",
+    /// );
+    /// ```
+    ///
+    /// With the `syn-backend` feature, a value that holds such a struct, or
+    /// one that is [`expanded`](Self::expanded), is laid out by the
+    /// built-in layout.
+    #[must_use]
+    pub fn compact(self) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.hint_as(id, Hint::Compact);
+        self
+    }
+
+    /// Chooses whether the struct output, when it is broken over lines,
+    /// ends in a comma after its last field, as rustfmt's `trailing_comma`
+    /// does, rather than following
+    /// [`Pretty::trailing_commas`](crate::Pretty::trailing_commas).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Config {
+    ///     port: u16,
+    ///     debug: bool,
+    /// }
+    ///
+    /// impl DebugPls for Config {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Config")
+    ///             .expanded()
+    ///             .trailing_comma(false)
+    ///             .field("port", &self.port)
+    ///             .field("debug", &self.debug)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let config = Config { port: 80, debug: false };
+    /// assert_eq!(
+    ///     format!("{}", pretty(&config)),
+    ///     "\
+    /// Config {
+    ///     port: 80,
+    ///     debug: false
+    /// }",
+    /// );
+    /// ```
+    ///
+    /// With the `syn-backend` feature, a value that holds a struct without
+    /// a trailing comma is laid out by the built-in layout.
+    #[must_use]
+    pub fn trailing_comma(self, trailing: bool) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.trailing_comma_as(id, trailing);
+        self
+    }
+
+    /// Adds the field to the struct output. A `name` that is a keyword, such
+    /// as `type`, is written as a raw identifier: `r#type`.
     #[must_use]
     pub fn field(mut self, name: &str, value: &dyn DebugPls) -> Self {
-        self.expr.fields.push(syn::FieldValue {
-            expr: Formatter::process(value),
-            attrs: vec![],
-            member: syn::Member::Named(syn::Ident::new(name, Span::call_site())),
-            colon_token: Some(syn::token::Colon::default()),
-        });
+        self.formatter
+            .tree
+            .append_field(&mut self.fields, name, value);
+        self
+    }
+
+    /// Adds the fields of `value` to the struct output in place of the field
+    /// `name`, when it is written as a struct with named fields, which keeps
+    /// the output of deeply nested structs, such as configurations, flat.
+    /// Other values are added as the field `name`.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// #[derive(DebugPls)]
+    /// struct Retry {
+    ///     attempts: u32,
+    ///     backoff_ms: u64,
+    /// }
+    ///
+    /// struct Client {
+    ///     url: &'static str,
+    ///     retry: Retry,
+    /// }
+    ///
+    /// impl DebugPls for Client {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Client")
+    ///             .field("url", &self.url)
+    ///             .flatten("retry", &self.retry)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let client = Client {
+    ///     url: "a.io",
+    ///     retry: Retry { attempts: 3, backoff_ms: 100 },
+    /// };
+    /// assert_eq!(
+    ///     format!("{}", pretty(&client)),
+    ///     "\
+    /// Client {
+    ///     url: \"a.io\",
+    ///     attempts: 3,
+    ///     backoff_ms: 100,
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn flatten(mut self, name: &str, value: &dyn DebugPls) -> Self {
+        self.formatter
+            .tree
+            .append_flattened(&mut self.fields, name, value);
+        self
+    }
+
+    /// Adds the field to the struct output, formatted by `fmt`, for a value
+    /// that has no [`DebugPls`] impl of its own.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Span {
+    ///     start: usize,
+    ///     len: usize,
+    /// }
+    ///
+    /// impl DebugPls for Span {
+    ///     fn fmt(&self, f: Formatter) {
+    ///         f.debug_struct("Span")
+    ///             .field_with("range", |f| {
+    ///                 f.debug_tuple()
+    ///                     .field(&self.start)
+    ///                     .field(&(self.start + self.len))
+    ///                     .finish()
+    ///             })
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let span = Span { start: 4, len: 2 };
+    /// assert_eq!(format!("{}", pretty(&span)), "Span { range: (4, 6) }");
+    /// ```
+    #[must_use]
+    pub fn field_with(self, name: &str, fmt: impl FnOnce(Formatter<'_>)) -> Self {
+        self.field(name, &WithFn::new(fmt))
+    }
+
+    /// Adds the field to the struct output, followed by a comment on the
+    /// rest of its line, for the units of a value, or what a raw value
+    /// stands for. A struct with a comment is always broken over lines.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Inode {
+    ///     mode: u32,
+    ///     size: u64,
+    /// }
+    ///
+    /// impl DebugPls for Inode {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Inode")
+    ///             .field_commented("mode", &self.mode, "rwxr-xr-x")
+    ///             .field("size", &self.size)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let inode = Inode { mode: 0o755, size: 4096 };
+    /// assert_eq!(
+    ///     format!("{}", pretty(&inode)),
+    ///     "\
+    /// Inode {
+    ///     mode: 493, // rwxr-xr-x
+    ///     size: 4096,
+    /// }",
+    /// );
+    /// ```
+    ///
+    /// [`color`](crate::color()) highlights the comment as a comment. Where
+    /// the struct is kept on one line, by [`compact`](Self::compact), it is
+    /// written as a block comment, `/* rwxr-xr-x */`. With the
+    /// `syn-backend` feature, a value that holds a comment is laid out by
+    /// the built-in layout, which keeps it.
+    #[must_use]
+    pub fn field_commented(mut self, name: &str, value: &dyn DebugPls, comment: &str) -> Self {
+        let tree = &mut *self.formatter.tree;
+        tree.append_field(&mut self.fields, name, value);
+        tree.comment_last(self.fields, comment);
+        self
+    }
+
+    /// Adds the field to the struct output unless it is `None`, for sparse
+    /// structs, such as configurations, that would otherwise be pages of
+    /// `None`. The field is written as the `Option` it is, `Some(30)`, and
+    /// [`finish_non_exhaustive`](Self::finish_non_exhaustive) marks that
+    /// fields were left out.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Config {
+    ///     name: &'static str,
+    ///     timeout: Option<u64>,
+    ///     proxy: Option<&'static str>,
+    /// }
+    ///
+    /// impl DebugPls for Config {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Config")
+    ///             .field("name", &self.name)
+    ///             .field_if_some("timeout", &self.timeout)
+    ///             .field_if_some("proxy", &self.proxy)
+    ///             .finish_non_exhaustive()
+    ///     }
+    /// }
+    ///
+    /// let config = Config { name: "app", timeout: Some(30), proxy: None };
+    /// assert_eq!(
+    ///     format!("{}", pretty(&config)),
+    ///     "\
+    /// Config {
+    ///     name: \"app\",
+    ///     timeout: Some(30),
+    ///     ..
+    /// }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn field_if_some<T: DebugPls>(self, name: &str, value: &Option<T>) -> Self {
+        self.field_if(name, value.is_some(), value)
+    }
+
+    /// Adds the field to the struct output if `cond` holds, like
+    /// [`field_if_some`](Self::field_if_some).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Buffer {
+    ///     data: Vec<u8>,
+    /// }
+    ///
+    /// impl DebugPls for Buffer {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Buffer")
+    ///             .field("len", &self.data.len())
+    ///             .field_if("data", self.data.len() <= 4, &self.data)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let short = Buffer { data: vec![1, 2] };
+    /// assert_eq!(format!("{}", pretty(&short)), "Buffer { len: 2, data: [1, 2] }");
+    /// let long = Buffer { data: vec![0; 1024] };
+    /// assert_eq!(format!("{}", pretty(&long)), "Buffer { len: 1024 }");
+    /// ```
+    #[must_use]
+    pub fn field_if(self, name: &str, cond: bool, value: &dyn DebugPls) -> Self {
+        if cond {
+            self.field(name, value)
+        } else {
+            self
+        }
+    }
+
+    /// Adds all the fields to the struct output, for records whose fields
+    /// are only known at runtime.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// /// A row from a database, with the names of its columns
+    /// struct Row {
+    ///     columns: Vec<&'static str>,
+    ///     values: Vec<i64>,
+    /// }
+    ///
+    /// impl DebugPls for Row {
+    ///     fn fmt(&self, f: Formatter) {
+    ///         let values = self.values.iter().map(|value| value as &dyn DebugPls);
+    ///         f.debug_struct("Row")
+    ///             .fields(self.columns.iter().copied().zip(values))
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let row = Row {
+    ///     columns: vec!["id", "age"],
+    ///     values: vec![1, 42],
+    /// };
+    /// assert_eq!(format!("{}", pretty(&row)), "Row { id: 1, age: 42 }");
+    /// ```
+    #[must_use]
+    pub fn fields<'b>(
+        mut self,
+        fields: impl IntoIterator<Item = (&'b str, &'b dyn DebugPls)>,
+    ) -> Self {
+        for (name, value) in fields {
+            self = self.field(name, value);
+        }
         self
     }
 
     /// Closes off the struct.
     pub fn finish(self) {
-        self.formatter.write_expr(self.expr);
+        self.formatter.write(Node::Struct(self.name, self.fields));
     }
 
-    /// Closes off the struct with `..`.
+    /// Closes off the struct with `..`, for a struct that has more fields
+    /// than are shown.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Conn {
+    ///     addr: &'static str,
+    ///     socket: u32,
+    /// }
+    ///
+    /// impl DebugPls for Conn {
+    ///     fn fmt(&self, f: Formatter) {
+    ///         f.debug_struct("Conn")
+    ///             .field("addr", &self.addr)
+    ///             .finish_non_exhaustive()
+    ///     }
+    /// }
+    ///
+    /// let conn = Conn { addr: "::1", socket: 3 };
+    /// assert_eq!(format!("{}", pretty(&conn)), "Conn { addr: \"::1\", .. }");
+    /// ```
     pub fn finish_non_exhaustive(mut self) {
-        self.expr.dot2_token = Some(syn::token::Dot2::default());
+        self.formatter.tree.elide(&mut self.fields);
         self.finish();
     }
 }
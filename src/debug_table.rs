@@ -0,0 +1,85 @@
+use crate::{
+    node::{Children, Node},
+    DebugPls, Formatter,
+};
+
+/// A helper designed to assist with creation of
+/// [`DebugPls`] implementations for lists of rows, structs that all have
+/// the same fields.
+///
+/// The fields of the rows are laid out in aligned columns, each headed by
+/// the name of the field in every row. Rows that are not all structs of
+/// the same name and fields, or that don't fit on a line, are laid out as
+/// a list instead. With the `syn-backend` feature, a table is always laid
+/// out as a list.
+///
+/// # Examples
+///
+/// ```rust
+/// use dbg_pls::{pretty, DebugPls, Formatter};
+///
+/// #[derive(DebugPls)]
+/// struct User {
+///     id: u32,
+///     name: &'static str,
+///     admin: bool,
+/// }
+///
+/// struct Users(Vec<User>);
+///
+/// impl DebugPls for Users {
+///     fn fmt(&self, f: Formatter<'_>) {
+///         f.debug_table().entries(&self.0).finish()
+///     }
+/// }
+///
+/// let users = Users(vec![
+///     User { id: 1, name: "root", admin: true },
+///     User { id: 1000, name: "alice", admin: false },
+/// ]);
+/// # #[cfg(not(feature = "syn-backend"))]
+/// assert_eq!(
+///     format!("{}", pretty(&users)),
+///     "\
+/// [
+///     User { id: 1,    name: \"root\",  admin: true  },
+///     User { id: 1000, name: \"alice\", admin: false },
+/// ]",
+/// );
+/// ```
+pub struct DebugTable<'a> {
+    formatter: Formatter<'a>,
+    rows: Children,
+}
+
+impl<'a> DebugTable<'a> {
+    pub(crate) fn new(formatter: Formatter<'a>) -> Self {
+        DebugTable {
+            formatter,
+            rows: Children::default(),
+        }
+    }
+
+    /// Adds a new row to the table output.
+    #[must_use]
+    pub fn entry(mut self, row: &dyn DebugPls) -> Self {
+        self.formatter.tree.append(&mut self.rows, row);
+        self
+    }
+
+    /// Adds all the rows to the table output.
+    #[must_use]
+    pub fn entries<D, I>(mut self, rows: I) -> Self
+    where
+        D: DebugPls,
+        I: IntoIterator<Item = D>,
+    {
+        self.formatter.tree.append_all(&mut self.rows, rows);
+        self
+    }
+
+    /// Closes off the table.
+    pub fn finish(self) {
+        self.formatter.write(Node::Table(self.rows));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    node::{Children, Node, Text},
+    with_fn::WithFn,
+    DebugPls, Formatter,
+};
+
+/// A helper designed to assist with creation of
+/// [`DebugPls`] implementations for hierarchical data, such as syntax
+/// trees, file trees or scene graphs.
+///
+/// The label is written as it is, with every child below it on lines of
+/// its own, joined to it by box-drawing guides. A child that is written
+/// with a `DebugTree` of its own is drawn as a branch of the tree.
+///
+/// # Examples
+///
+/// ```rust
+/// use dbg_pls::{pretty, DebugPls, Formatter};
+///
+/// enum Entry {
+///     File(&'static str, u64),
+///     Dir(&'static str, Vec<Entry>),
+/// }
+///
+/// impl DebugPls for Entry {
+///     fn fmt(&self, f: Formatter<'_>) {
+///         match self {
+///             Entry::File(name, len) => f.debug_tree(name).child(len).finish(),
+///             Entry::Dir(name, entries) => f.debug_tree(name).children(entries).finish(),
+///         }
+///     }
+/// }
+///
+/// let src = Entry::Dir("src", vec![
+///     Entry::Dir("layout", vec![Entry::File("node.rs", 6403)]),
+///     Entry::File("lib.rs", 20931),
+/// ]);
+/// # #[cfg(not(feature = "syn-backend"))]
+/// assert_eq!(
+///     format!("{}", pretty(&src)),
+///     "\
+/// src
+/// ├── layout
+/// │   └── node.rs
+/// │       └── 6403
+/// └── lib.rs
+///     └── 20931",
+/// );
+/// ```
+///
+/// [`color`](crate::color()) highlights the labels and children as Rust,
+/// and leaves the guides in the plain text color. The guides are not Rust,
+/// so with the `syn-backend` feature a value that holds a tree is laid out
+/// by the built-in layout instead.
+pub struct DebugTree<'a> {
+    formatter: Formatter<'a>,
+    label: Text,
+    children: Children,
+}
+
+impl<'a> DebugTree<'a> {
+    pub(crate) fn new(formatter: Formatter<'a>, label: &str) -> Self {
+        let label = formatter.tree.text(label);
+        DebugTree {
+            formatter,
+            label,
+            children: Children::default(),
+        }
+    }
+
+    /// Adds a new child to the tree output.
+    #[must_use]
+    pub fn child(mut self, child: &dyn DebugPls) -> Self {
+        self.formatter.tree.append(&mut self.children, child);
+        self
+    }
+
+    /// Adds a new child to the tree output, formatted by `fmt`.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Add(i32, i32);
+    ///
+    /// impl DebugPls for Add {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_tree("Add")
+    ///             .child_with(|f| f.debug_tree("Lit").child(&self.0).finish())
+    ///             .child_with(|f| f.debug_tree("Lit").child(&self.1).finish())
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// # #[cfg(not(feature = "syn-backend"))]
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Add(1, 2))),
+    ///     "Add\n├── Lit\n│   └── 1\n└── Lit\n    └── 2",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn child_with(self, fmt: impl FnOnce(Formatter<'_>)) -> Self {
+        self.child(&WithFn::new(fmt))
+    }
+
+    /// Adds all the children to the tree output.
+    #[must_use]
+    pub fn children<D, I>(mut self, children: I) -> Self
+    where
+        D: DebugPls,
+        I: IntoIterator<Item = D>,
+    {
+        self.formatter.tree.append_all(&mut self.children, children);
+        self
+    }
+
+    /// Closes off the tree
+    pub fn finish(self) {
+        self.formatter
+            .write(Node::Branch(self.label, self.children));
+    }
+}
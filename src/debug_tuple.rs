@@ -1,4 +1,8 @@
-use crate::{DebugPls, Formatter};
+use crate::{
+    node::{Children, Node},
+    with_fn::WithFn,
+    DebugPls, Formatter,
+};
 
 /// A helper designed to assist with creation of
 /// [`DebugPls`] implementations for tuples.
@@ -24,30 +28,43 @@ use crate::{DebugPls, Formatter};
 /// ```
 pub struct DebugTuple<'a> {
     formatter: Formatter<'a>,
-    expr: syn::ExprTuple,
+    elems: Children,
 }
 
 impl<'a> DebugTuple<'a> {
     pub(crate) fn new(formatter: Formatter<'a>) -> Self {
         DebugTuple {
             formatter,
-            expr: syn::ExprTuple {
-                attrs: vec![],
-                paren_token: syn::token::Paren::default(),
-                elems: syn::punctuated::Punctuated::new(),
-            },
+            elems: Children::default(),
         }
     }
 
+    /// Chooses whether the tuple output, when it is broken over lines,
+    /// ends in a comma after its last field, like
+    /// [`DebugStruct::trailing_comma`](crate::DebugStruct::trailing_comma).
+    /// A tuple of one keeps its comma, which makes it a tuple.
+    #[must_use]
+    pub fn trailing_comma(self, trailing: bool) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.trailing_comma_as(id, trailing);
+        self
+    }
+
     /// Adds the field to the tuple output.
     #[must_use]
     pub fn field(mut self, value: &dyn DebugPls) -> Self {
-        self.expr.elems.push(Formatter::process(value));
+        self.formatter.tree.append(&mut self.elems, value);
         self
     }
 
+    /// Adds the field to the tuple output, formatted by `fmt`.
+    #[must_use]
+    pub fn field_with(self, fmt: impl FnOnce(Formatter<'_>)) -> Self {
+        self.field(&WithFn::new(fmt))
+    }
+
     /// Closes off the tuple.
     pub fn finish(self) {
-        self.formatter.write_expr(self.expr);
+        self.formatter.write(Node::Tuple(self.elems));
     }
 }
@@ -1,6 +1,8 @@
-use syn::__private::Span;
-
-use crate::{DebugPls, Formatter};
+use crate::{
+    node::{Children, Node, Text},
+    with_fn::WithFn,
+    DebugPls, Formatter,
+};
 
 /// A helper designed to assist with creation of
 /// [`DebugPls`] implementations for tuple structs.
@@ -26,35 +28,99 @@ use crate::{DebugPls, Formatter};
 /// ```
 pub struct DebugTupleStruct<'a> {
     formatter: Formatter<'a>,
-    expr: syn::ExprCall,
+    name: Text,
+    fields: Children,
 }
 
 impl<'a> DebugTupleStruct<'a> {
     pub(crate) fn new(formatter: Formatter<'a>, name: &str) -> Self {
+        let name = formatter.tree.name(name);
         DebugTupleStruct {
             formatter,
-            expr: syn::ExprCall {
-                attrs: vec![],
-                func: Box::new(syn::Expr::Path(syn::ExprPath {
-                    attrs: vec![],
-                    qself: None,
-                    path: syn::Ident::new(name, Span::call_site()).into(),
-                })),
-                paren_token: syn::token::Paren::default(),
-                args: syn::punctuated::Punctuated::new(),
-            },
+            name,
+            fields: Children::default(),
         }
     }
 
+    /// Writes the generic arguments of the tuple struct after its name, as
+    /// in `Wrapper::<u32>(5)`, like
+    /// [`DebugStruct::generics`](crate::DebugStruct::generics).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Wrapper<T>(T);
+    ///
+    /// impl<T: DebugPls> DebugPls for Wrapper<T> {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_tuple_struct("Wrapper")
+    ///             .generics(&[std::any::type_name::<T>()])
+    ///             .field(&self.0)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{}", pretty(&(Wrapper(5_u32), Wrapper(true)))),
+    ///     "(Wrapper::<u32>(5), Wrapper::<bool>(true))",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn generics(mut self, args: &[&str]) -> Self {
+        self.name = self.formatter.tree.generic_name(self.name, args);
+        self
+    }
+
+    /// Chooses whether the tuple struct output, when it is broken over
+    /// lines, ends in a comma after its last field, like
+    /// [`DebugStruct::trailing_comma`](crate::DebugStruct::trailing_comma).
+    #[must_use]
+    pub fn trailing_comma(self, trailing: bool) -> Self {
+        let id = self.formatter.id;
+        self.formatter.tree.trailing_comma_as(id, trailing);
+        self
+    }
+
     /// Adds the field to the tuple struct output.
     #[must_use]
     pub fn field(mut self, value: &dyn DebugPls) -> Self {
-        self.expr.args.push(Formatter::process(value));
+        self.formatter.tree.append(&mut self.fields, value);
         self
     }
 
+    /// Adds the field to the tuple struct output, formatted by `fmt`.
+    #[must_use]
+    pub fn field_with(self, fmt: impl FnOnce(Formatter<'_>)) -> Self {
+        self.field(&WithFn::new(fmt))
+    }
+
     /// Closes off the tuple struct.
     pub fn finish(self) {
-        self.formatter.write_expr(self.expr);
+        self.formatter
+            .write(Node::TupleStruct(self.name, self.fields));
+    }
+
+    /// Closes off the tuple struct with `..`, for a tuple struct that has
+    /// more fields than are shown, like
+    /// [`DebugStruct::finish_non_exhaustive`](crate::DebugStruct::finish_non_exhaustive).
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Conn(&'static str, u32);
+    ///
+    /// impl DebugPls for Conn {
+    ///     fn fmt(&self, f: Formatter) {
+    ///         f.debug_tuple_struct("Conn")
+    ///             .field(&self.0)
+    ///             .finish_non_exhaustive()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Conn("::1", 3))), "Conn(\"::1\", ..)");
+    /// ```
+    pub fn finish_non_exhaustive(mut self) {
+        self.formatter.tree.elide(&mut self.fields);
+        self.finish();
     }
 }
@@ -0,0 +1,119 @@
+//! Printing captured values from a background thread.
+
+use std::{
+    sync::{
+        mpsc::{self, Sender},
+        Mutex, PoisonError,
+    },
+    thread,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{pretty::Str, Captured};
+
+enum Message {
+    Print {
+        file: &'static str,
+        line: u32,
+        expr: &'static str,
+        // boxed, as a captured value is far larger than a flush
+        value: Box<Captured>,
+    },
+    Flush(Sender<()>),
+}
+
+/// The printing thread, which is started on first use and lives for the
+/// rest of the process.
+fn printer() -> &'static Mutex<Sender<Message>> {
+    static PRINTER: Lazy<Mutex<Sender<Message>>> = Lazy::new(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("dbg-pls".to_owned())
+            .spawn(move || {
+                for message in receiver {
+                    match message {
+                        Message::Print {
+                            file,
+                            line,
+                            expr,
+                            value,
+                        } => crate::output::print(file, line, expr, &Str(expr), &value),
+                        // the receiver only waits for the messages before this one
+                        Message::Flush(done) => drop(done.send(())),
+                    }
+                }
+            })
+            .expect("failed to spawn the dbg-pls printing thread");
+        Mutex::new(sender)
+    });
+    &PRINTER
+}
+
+fn send(message: Message) {
+    let sender = printer().lock().unwrap_or_else(PoisonError::into_inner);
+    // the printing thread only stops if it panicked, in which case there is
+    // nowhere left to print to
+    let _ = sender.send(message);
+}
+
+/// Implementation detail for the `dbg_pls_async!` macro
+pub fn print_later(file: &'static str, line: u32, expr: &'static str, value: Captured) {
+    send(Message::Print {
+        file,
+        line,
+        expr,
+        value: Box::new(value),
+    });
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// Blocks until every value passed to [`dbg_pls_async!`](crate::dbg_pls_async) so far has been printed.
+///
+/// The printing thread does not hold up the process when it exits, so call
+/// this before exiting to not lose the last values.
+pub fn flush() {
+    let (done, wait) = mpsc::channel();
+    send(Message::Flush(done));
+    let _ = wait.recv();
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+#[macro_export]
+/// Same as [`pretty!`](crate::pretty!), except that only capturing the value happens on the
+/// calling thread. Laying it out and printing it are left to a background
+/// thread.
+///
+/// ```rust
+/// # use dbg_pls::dbg_pls_async;
+/// let a = 2;
+/// let b = dbg_pls_async!(a * 2) + 1;
+/// //      ^-- prints, a little later: [src/main.rs:2] a * 2 => 4
+/// assert_eq!(b, 5);
+/// dbg_pls::flush();
+/// ```
+///
+/// Values are printed in the order they were captured in, each written at
+/// once as with [`pretty!`](crate::pretty!). Call [`flush`]
+/// to wait for them to be printed, or some are lost when the process exits.
+macro_rules! dbg_pls_async {
+    () => {
+        ::std::eprintln!("[{}:{}]", ::std::file!(), ::std::line!())
+    };
+    ($val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                $crate::__private::print_later(
+                    ::std::file!(),
+                    ::std::line!(),
+                    ::std::stringify!($val),
+                    $crate::pretty(&tmp).capture(),
+                );
+                tmp
+            }
+        }
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::dbg_pls_async!($val)),+,)
+    };
+}
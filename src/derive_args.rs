@@ -0,0 +1,430 @@
+//! The arguments that [`DebugPls`](crate::DebugPls) takes in `#[dbg_pls(...)]`.
+//!
+//! # Fields
+//!
+//! `opaque` writes the field as its type name, so its type need not
+//! implement `DebugPls`:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! struct Job {
+//!     #[dbg_pls(opaque)]
+//!     started: std::time::Instant,
+//! }
+//!
+//! let job = Job { started: std::time::Instant::now() };
+//! assert_eq!(format!("{}", pretty(&job)), r#"Job { started: "<Instant>" }"#);
+//! ```
+//!
+//! `len_only` writes the field as its type and length, as
+//! [`Formatter::debug_len`](crate::Formatter::debug_len) does.
+//! `len_only = N` only does so once it holds more than `N` elements:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! struct Decoder {
+//!     #[dbg_pls(len_only)]
+//!     frames: Vec<u32>,
+//!     #[dbg_pls(len_only = 4)]
+//!     pending: Vec<u32>,
+//! }
+//!
+//! let decoder = Decoder { frames: vec![0; 1024], pending: vec![1, 2] };
+//! assert_eq!(
+//!     format!("{}", pretty(&decoder)),
+//!     "Decoder {
+//!     frames: Vec::<u32>(len = 1024),
+//!     pending: [1, 2],
+//! }",
+//! );
+//! ```
+//!
+//! `truncate = N` writes the first `N` entries of the field, or chars of a
+//! string, followed by how many more it holds:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! struct Response {
+//!     #[dbg_pls(truncate = 3)]
+//!     body: Vec<u8>,
+//! }
+//!
+//! let response = Response { body: vec![7; 1000] };
+//! assert_eq!(
+//!     format!("{}", pretty(&response)),
+//!     "Response {
+//!     body: [7, 7, 7, .. /* 997 more */],
+//! }",
+//! );
+//! ```
+//!
+//! `bytes` follows a size in bytes with the size in a readable unit:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! struct Stats {
+//!     #[dbg_pls(bytes)]
+//!     resident: usize,
+//! }
+//!
+//! assert_eq!(
+//!     format!("{}", pretty(&Stats { resident: 3 << 30 })),
+//!     "Stats {
+//!     resident: 3221225472 /* 3 GiB */,
+//! }",
+//! );
+//! ```
+//!
+//! `vec`, `raw`, `hex` and `binary` write the field as
+//! [`Pretty::vec_macros`](crate::Pretty::vec_macros),
+//! [`Pretty::raw_strings`](crate::Pretty::raw_strings),
+//! [`Formatter::debug_int_hex`](crate::Formatter::debug_int_hex) and
+//! [`Formatter::debug_int_bin`](crate::Formatter::debug_int_bin) would:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! struct Regs {
+//!     #[dbg_pls(vec)]
+//!     stack: Vec<u8>,
+//!     #[dbg_pls(raw)]
+//!     pattern: &'static str,
+//!     #[dbg_pls(hex)]
+//!     pc: u32,
+//!     #[dbg_pls(binary)]
+//!     flags: u8,
+//! }
+//!
+//! let regs = Regs { stack: vec![1], pattern: r"\d", pc: 0x8000_04A0, flags: 0b101 };
+//! assert_eq!(
+//!     format!("{}", pretty(&regs)),
+//!     r#"Regs {
+//!     stack: vec![1],
+//!     pattern: r"\d",
+//!     pc: 0x8000_04A0,
+//!     flags: 0b101,
+//! }"#,
+//! );
+//! ```
+//!
+//! `skip` leaves the field out, and `skip_if = "path"` leaves it out
+//! whenever the function at the path returns `true` for it:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! struct Socket;
+//!
+//! #[derive(DebugPls)]
+//! struct Client {
+//!     #[dbg_pls(skip)]
+//!     socket: Socket,
+//!     #[dbg_pls(skip_if = "Option::is_none")]
+//!     timeout: Option<u32>,
+//! }
+//!
+//! let client = Client { socket: Socket, timeout: None };
+//! assert_eq!(format!("{}", pretty(&client)), "Client {}");
+//! ```
+//!
+//! `PhantomData` fields are skipped without being marked. `show` writes one
+//! anyway, with the type it stands for:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! # use std::marker::PhantomData;
+//! #[derive(DebugPls)]
+//! struct Key<T> {
+//!     #[dbg_pls(show)]
+//!     kind: PhantomData<T>,
+//! }
+//!
+//! let key: Key<u8> = Key { kind: PhantomData };
+//! assert_eq!(format!("{}", pretty(&key)), "Key { kind: PhantomData::<u8> }");
+//! ```
+//!
+//! Other fields are written anyway, so they can't be marked `show`:
+//!
+//! ```compile_fail
+//! # use dbg_pls::DebugPls;
+//! #[derive(DebugPls)]
+//! struct Id {
+//!     #[dbg_pls(show)]
+//!     raw: u64,
+//! }
+//! ```
+//!
+//! `with = "path"` formats the field by the function at the path, a
+//! `fn(&T, Formatter<'_>)`:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls, Formatter};
+//! struct Rgb(u8, u8, u8);
+//!
+//! fn hex(rgb: &Rgb, f: Formatter<'_>) {
+//!     f.debug_lit_str(&format!("#{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2));
+//! }
+//!
+//! #[derive(DebugPls)]
+//! struct Theme {
+//!     #[dbg_pls(with = "hex")]
+//!     accent: Rgb,
+//! }
+//!
+//! let theme = Theme { accent: Rgb(255, 128, 0) };
+//! assert_eq!(format!("{}", pretty(&theme)), r##"Theme { accent: "#ff8000" }"##);
+//! ```
+//!
+//! `display` and `debug` write the field by its `Display` or `Debug` impl,
+//! as [`Formatter::write_display`](crate::Formatter::write_display) and
+//! [`Formatter::write_debug`](crate::Formatter::write_debug) do. With the
+//! `syn` feature, what `Debug` writes is laid out like any other value:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(Debug)]
+//! struct Metric(u8);
+//!
+//! #[derive(DebugPls)]
+//! struct Route {
+//!     #[dbg_pls(display)]
+//!     gateway: std::net::Ipv4Addr,
+//!     #[dbg_pls(debug)]
+//!     metric: Metric,
+//! }
+//!
+//! let route = Route { gateway: [10, 0, 0, 1].into(), metric: Metric(3) };
+//! assert_eq!(
+//!     format!("{}", pretty(&route)),
+//!     r#"Route {
+//!     gateway: "10.0.0.1",
+//!     metric: Metric(3),
+//! }"#,
+//! );
+//! ```
+//!
+//! `as = "Type"` writes a clone of the field converted into the type by
+//! [`Into`]:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! enum Signal {
+//!     Kill,
+//!     Other(u8),
+//! }
+//!
+//! impl From<u8> for Signal {
+//!     fn from(raw: u8) -> Self {
+//!         if raw == 9 { Signal::Kill } else { Signal::Other(raw) }
+//!     }
+//! }
+//!
+//! #[derive(DebugPls)]
+//! struct Exit {
+//!     #[dbg_pls(as = "Signal")]
+//!     signal: u8,
+//! }
+//!
+//! assert_eq!(format!("{}", pretty(&Exit { signal: 9 })), "Exit { signal: Kill }");
+//! ```
+//!
+//! `redact` writes `"[REDACTED]"` in place of the field, and
+//! `redact = "..."` the given placeholder:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! struct Login {
+//!     #[dbg_pls(redact)]
+//!     password: String,
+//!     #[dbg_pls(redact = "***")]
+//!     otp: u32,
+//! }
+//!
+//! let login = Login { password: "hunter2".to_string(), otp: 123456 };
+//! assert_eq!(
+//!     format!("{}", pretty(&login)),
+//!     r#"Login {
+//!     password: "[REDACTED]",
+//!     otp: "***",
+//! }"#,
+//! );
+//! ```
+//!
+//! `flatten` writes the fields of a named field in its place, as
+//! [`DebugStruct::flatten`](crate::DebugStruct::flatten) does:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! struct Limits {
+//!     max_conns: u32,
+//! }
+//!
+//! #[derive(DebugPls)]
+//! struct Server {
+//!     port: u16,
+//!     #[dbg_pls(flatten)]
+//!     limits: Limits,
+//! }
+//!
+//! let server = Server { port: 80, limits: Limits { max_conns: 8 } };
+//! assert_eq!(format!("{}", pretty(&server)), "Server { port: 80, max_conns: 8 }");
+//! ```
+//!
+//! # Names
+//!
+//! `rename = "..."` writes a type, variant or field with the given name,
+//! and `rename_all = "..."` writes the fields of a struct, or the variants
+//! of an enum, in the given case, as serde's attributes of the same names
+//! do:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! #[dbg_pls(rename = "Heartbeat", rename_all = "camelCase")]
+//! struct Ping {
+//!     seq_no: u32,
+//! }
+//!
+//! assert_eq!(format!("{}", pretty(&Ping { seq_no: 1 })), "Heartbeat { seqNo: 1 }");
+//! ```
+//!
+//! serde's kebab cases are not valid Rust names, so they are not taken:
+//!
+//! ```compile_fail
+//! # use dbg_pls::DebugPls;
+//! #[derive(DebugPls)]
+//! #[dbg_pls(rename_all = "kebab-case")]
+//! struct Ping {
+//!     seq_no: u32,
+//! }
+//! ```
+//!
+//! `qualify_variants` writes the variants of an enum after its name, as
+//! [`Formatter::debug_enum`](crate::Formatter::debug_enum) does, and
+//! `generics` writes a type with its generic arguments, as
+//! [`DebugStruct::generics`](crate::DebugStruct::generics) does:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! #[dbg_pls(generics, qualify_variants)]
+//! enum Slot<T, const N: usize> {
+//!     Full([T; N]),
+//!     Empty,
+//! }
+//!
+//! assert_eq!(
+//!     format!("{}", pretty(&[Slot::Full([1_u8, 2]), Slot::Empty])),
+//!     "[Slot::<u8, 2>::Full([1, 2]), Slot::<u8, 2>::Empty]",
+//! );
+//! ```
+//!
+//! `module_path` writes a type after the path to its module:
+//!
+//! ```
+//! mod net {
+//!     #[derive(dbg_pls::DebugPls)]
+//!     #[dbg_pls(module_path)]
+//!     pub struct Port(pub u16);
+//! }
+//!
+//! assert_eq!(
+//!     format!("{}", dbg_pls::pretty(&net::Port(80))),
+//!     format!("{}::net::Port(80)", module_path!()),
+//! );
+//! ```
+//!
+//! # Shape
+//!
+//! `non_exhaustive` writes `..` after the fields of a type, and
+//! `sort_fields` writes its named fields in the order of their names:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! #[dbg_pls(non_exhaustive, sort_fields)]
+//! struct Pool {
+//!     size: usize,
+//!     idle: usize,
+//! }
+//!
+//! let pool = Pool { size: 4, idle: 1 };
+//! assert_eq!(format!("{}", pretty(&pool)), "Pool { idle: 1, size: 4, .. }");
+//! ```
+//!
+//! `transparent` writes a struct or variant as its one field. Variants can
+//! be marked `skip` too, to be written as their name alone:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! #[derive(DebugPls)]
+//! enum Frame {
+//!     #[dbg_pls(skip)]
+//!     Data(Vec<u8>),
+//!     #[dbg_pls(transparent)]
+//!     Error(&'static str),
+//! }
+//!
+//! let frames = [Frame::Data(vec![1]), Frame::Error("eof")];
+//! assert_eq!(format!("{}", pretty(&frames)), r#"[Data(..), "eof"]"#);
+//! ```
+//!
+//! The field of a transparent struct is always written, so it can't be
+//! marked `skip_if`:
+//!
+//! ```compile_fail
+//! # use dbg_pls::DebugPls;
+//! #[derive(DebugPls)]
+//! #[dbg_pls(transparent)]
+//! struct Timeout {
+//!     #[dbg_pls(skip_if = "Option::is_none")]
+//!     secs: Option<u64>,
+//! }
+//! ```
+//!
+//! # Impls
+//!
+//! The impl bounds the type of each written field that mentions a type
+//! parameter by `DebugPls`, such as `Vec<T>: DebugPls`. `bound = "..."`
+//! gives the where clause instead, as mutually recursive types need on one
+//! of them:
+//!
+//! ```
+//! # use dbg_pls::{pretty, DebugPls};
+//! struct Fnv;
+//!
+//! #[derive(DebugPls)]
+//! #[dbg_pls(bound = "K: DebugPls")]
+//! struct Cache<K, S> {
+//!     keys: Vec<K>,
+//!     #[dbg_pls(skip)]
+//!     hasher: S,
+//! }
+//!
+//! let cache = Cache { keys: vec![1, 2], hasher: Fnv };
+//! assert_eq!(format!("{}", pretty(&cache)), "Cache { keys: [1, 2] }");
+//! ```
+//!
+//! `std_debug` and `impl_display` implement `Debug` and `Display` too,
+//! through the `pretty` feature. `{:?}` is written on one line, and `{:#?}`
+//! and `{}` are pretty printed:
+//!
+//! ```
+//! # use dbg_pls::DebugPls;
+//! #[derive(DebugPls)]
+//! #[dbg_pls(std_debug, impl_display)]
+//! struct Span {
+//!     file: &'static str,
+//!     lines: std::ops::Range<u32>,
+//! }
+//!
+//! let span = Span { file: "main.rs", lines: 10..12 };
+//! assert_eq!(format!("{:?}", span), r#"Span { file: "main.rs", lines: 10..12 }"#);
+//! assert_eq!(span.to_string(), format!("{:#?}", span));
+//! ```
@@ -0,0 +1,75 @@
+//! Byte buffers written as byte strings, or as hex dumps once they are long.
+
+use crate::{push_fmt, DebugPls, Formatter};
+
+/// The longest buffer that is written as a byte string.
+pub(crate) const MAX_BYTE_STR: usize = 32;
+
+/// Bytes in a row of a hex dump, which keeps a row within the width of
+/// the layout.
+const ROW: usize = 8;
+
+/// Writes `bytes` as a byte string literal, `b"GET /\r\n"`.
+pub(crate) fn byte_str(bytes: &[u8], buf: &mut String) {
+    buf.push_str("b\"");
+    for &byte in bytes {
+        // `'` needs no escape in a string
+        if byte == b'\'' {
+            buf.push('\'');
+        } else {
+            buf.extend(std::ascii::escape_default(byte).map(char::from));
+        }
+    }
+    buf.push('"');
+}
+
+/// The rows of a hex dump of `bytes`.
+pub(crate) fn rows(bytes: &[u8]) -> impl ExactSizeIterator<Item = Row<'_>> {
+    // enough digits for the offset of the last row
+    let last = bytes.len().saturating_sub(1);
+    let digits = (usize::BITS - last.leading_zeros()).div_ceil(4).max(4) as usize;
+    bytes.chunks(ROW).enumerate().map(move |(i, bytes)| Row {
+        offset: i * ROW,
+        digits,
+        bytes,
+    })
+}
+
+/// A row of a hex dump, its offset and characters in a comment ahead of
+/// its bytes: `/* 0008 TP/1.1.. */ 0x54, 0x50, 0x2f, 0x31, 0x2e, 0x31, 0x0d, 0x0a`.
+pub(crate) struct Row<'a> {
+    offset: usize,
+    digits: usize,
+    bytes: &'a [u8],
+}
+
+impl DebugPls for Row<'_> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_verbatim_with(|buf| {
+            push_fmt(buf, format_args!("/* {:01$x} ", self.offset, self.digits));
+            let mut prev = ' ';
+            for &byte in self.bytes {
+                let mut ch = if byte == b' ' || byte.is_ascii_graphic() {
+                    char::from(byte)
+                } else {
+                    '.'
+                };
+                // which would end the comment, or open another one within it
+                if (prev, ch) == ('*', '/') || (prev, ch) == ('/', '*') {
+                    ch = '.';
+                }
+                buf.push(ch);
+                prev = ch;
+            }
+            // the bytes of a short last row still line up with the rest
+            buf.extend(std::iter::repeat_n(' ', ROW - self.bytes.len()));
+            buf.push_str(" */ ");
+            for (i, &byte) in self.bytes.iter().enumerate() {
+                if i > 0 {
+                    buf.push_str(", ");
+                }
+                push_fmt(buf, format_args!("0x{byte:02x}"));
+            }
+        });
+    }
+}
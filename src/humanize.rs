@@ -0,0 +1,96 @@
+//! Numbers written out the way people read them, for the comments that
+//! humanized output adds.
+
+use std::time::Duration;
+
+/// Writes `value` in `unit`s, rounded to a tenth, `3.4s`.
+pub(crate) fn tenths(value: u128, unit: u128, suffix: &str, buf: &mut String) {
+    let tenths = rounded(value, unit);
+    buf.push_str(itoa::Buffer::new().format(tenths / 10));
+    if !tenths.is_multiple_of(10) {
+        buf.push('.');
+        buf.push_str(itoa::Buffer::new().format(tenths % 10));
+    }
+    buf.push_str(suffix);
+}
+
+/// Writes the duration in the units it is best read in, `2m 3.4s` or
+/// `12.5ms`, to a tenth of the smallest one.
+pub(crate) fn duration(duration: Duration, buf: &mut String) {
+    let nanos = duration.as_nanos();
+    // rounded first, so that `999.96µs` is `1ms` and `59.96s` is `1m`
+    for (unit, suffix) in [(1, "ns"), (1_000, "µs"), (1_000_000, "ms")] {
+        if rounded(nanos, unit) < 10_000 {
+            return tenths(nanos, unit, suffix, buf);
+        }
+    }
+    let deciseconds = rounded(nanos, 1_000_000_000);
+    let parts = [
+        (deciseconds / 864_000, "d"),
+        (deciseconds / 36_000 % 24, "h"),
+        (deciseconds / 600 % 60, "m"),
+    ];
+    for (count, unit) in parts {
+        if count > 0 {
+            buf.push_str(itoa::Buffer::new().format(count));
+            buf.push_str(unit);
+            buf.push(' ');
+        }
+    }
+    let rest = deciseconds % 600;
+    if rest > 0 || deciseconds < 600 {
+        tenths(rest, 10, "s", buf);
+    }
+    if buf.ends_with(' ') {
+        buf.pop();
+    }
+}
+
+/// Writes a size in bytes in the largest binary unit it makes one of once
+/// rounded, `1.5 MiB`.
+pub(crate) fn bytes(bytes: u64, buf: &mut String) {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if bytes < 1024 {
+        buf.push_str(itoa::Buffer::new().format(bytes));
+        buf.push_str(" B");
+        return;
+    }
+    // rounding can make 1024 of a unit, `1023.96 KiB`, which is one of the
+    // next
+    let mut unit = 1024;
+    let mut i = 0;
+    while i + 1 < UNITS.len() && rounded(u128::from(bytes), unit) >= 10_240 {
+        unit *= 1024;
+        i += 1;
+    }
+    tenths(u128::from(bytes), unit, "", buf);
+    buf.push(' ');
+    buf.push_str(UNITS[i]);
+}
+
+/// `value` in tenths of `unit`, rounded to the nearest.
+pub(crate) fn rounded(value: u128, unit: u128) -> u128 {
+    (value * 10 + unit / 2) / unit
+}
+
+#[cfg(test)]
+mod tests {
+    fn bytes(bytes: u64) -> String {
+        let mut buf = String::new();
+        super::bytes(bytes, &mut buf);
+        buf
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(bytes(0), "0 B");
+        assert_eq!(bytes(1023), "1023 B");
+        assert_eq!(bytes(1024), "1 KiB");
+        assert_eq!(bytes(1536), "1.5 KiB");
+        assert_eq!(bytes(1_048_524), "1023.9 KiB");
+        assert_eq!(bytes(1_048_525), "1 MiB");
+        assert_eq!(bytes(1_048_575), "1 MiB");
+        assert_eq!(bytes(1_048_576), "1 MiB");
+        assert_eq!(bytes(u64::MAX), "16 EiB");
+    }
+}
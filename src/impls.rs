@@ -1,3 +1,7 @@
+#[cfg(feature = "proc-macro2")]
 mod proc_macro2;
 mod std;
+#[cfg(feature = "syn")]
 mod syn;
+#[cfg(feature = "time")]
+mod time;
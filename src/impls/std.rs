@@ -1,8 +1,11 @@
 mod collections;
 mod fnptr;
+mod path;
+mod time;
 mod tuple;
 
 use std::{
+    marker::PhantomData,
     ops,
     ops::ControlFlow,
     rc::Rc,
@@ -10,9 +13,11 @@ use std::{
     task::Poll,
 };
 
-use crate::{DebugPls, Formatter};
-use syn::RangeLimits;
-use syn::__private::Span;
+use crate::{
+    node::{Children, Node, Radix},
+    opaque::generic_name,
+    push_fmt, DebugPls, Formatter,
+};
 
 impl<T: ?Sized + DebugPls> DebugPls for Box<T> {
     fn fmt(&self, f: Formatter<'_>) {
@@ -20,9 +25,18 @@ impl<T: ?Sized + DebugPls> DebugPls for Box<T> {
     }
 }
 
+/// Written as the expression for it, with the type it stands for,
+/// `PhantomData::<u32>`.
+impl<T: ?Sized> DebugPls for PhantomData<T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        let name = generic_name("PhantomData", &[&std::any::type_name::<T>()]);
+        f.debug_ident(&name);
+    }
+}
+
 impl<D: DebugPls + ?Sized> DebugPls for *mut D {
     fn fmt(&self, f: Formatter<'_>) {
-        <*const D>::fmt(&(*self as *const D), f);
+        <*const D>::fmt(&(*self).cast_const(), f);
     }
 }
 
@@ -31,10 +45,8 @@ impl<D: DebugPls + ?Sized> DebugPls for *const D {
         /// Since the formatting will be identical for all pointer types, use a non-monomorphized
         /// implementation for the actual formatting to reduce the amount of codegen work needed
         fn inner(ptr: *const (), f: Formatter<'_>) {
-            let output = format!("{:#x?}", ptr as usize);
-            f.write_expr(syn::ExprLit {
-                attrs: vec![],
-                lit: syn::LitFloat::new(&output, Span::call_site()).into(),
+            f.write_lit_with(|buf| {
+                push_fmt(buf, format_args!("{:#x?}", ptr as usize));
             });
         }
 
@@ -42,13 +54,13 @@ impl<D: DebugPls + ?Sized> DebugPls for *const D {
     }
 }
 
-impl<'a, D: DebugPls + ?Sized> DebugPls for &'a D {
+impl<D: DebugPls + ?Sized> DebugPls for &D {
     fn fmt(&self, f: Formatter<'_>) {
         D::fmt(self, f);
     }
 }
 
-impl<'a, D: DebugPls + ?Sized> DebugPls for &'a mut D {
+impl<D: DebugPls + ?Sized> DebugPls for &mut D {
     fn fmt(&self, f: Formatter<'_>) {
         D::fmt(self, f);
     }
@@ -56,13 +68,13 @@ impl<'a, D: DebugPls + ?Sized> DebugPls for &'a mut D {
 
 impl<T: ?Sized + DebugPls> DebugPls for Rc<T> {
     fn fmt(&self, f: Formatter<'_>) {
-        DebugPls::fmt(&**self, f);
+        f.write_shared(Rc::as_ptr(self), &**self);
     }
 }
 
 impl<T: ?Sized + DebugPls> DebugPls for Arc<T> {
     fn fmt(&self, f: Formatter<'_>) {
-        DebugPls::fmt(&**self, f);
+        f.write_shared(Arc::as_ptr(self), &**self);
     }
 }
 
@@ -88,12 +100,28 @@ impl<T: ?Sized + DebugPls> DebugPls for Mutex<T> {
 macro_rules! debug_integers {
     ($($T:ident)*) => {$(
         impl DebugPls for $T {
+            // the sign and casts only mean something for some of the types
+            #[allow(
+                unused_comparisons,
+                clippy::cast_lossless,
+                clippy::cast_sign_loss,
+                clippy::cast_possible_wrap
+            )]
             fn fmt(&self, f: Formatter<'_>) {
-                let mut buf = itoa::Buffer::new();
-                f.write_expr(syn::ExprLit {
-                    attrs: vec![],
-                    lit: syn::LitInt::new(buf.format(*self), Span::call_site()).into(),
-                });
+                if f.tree.style.radix == Radix::Decimal {
+                    let mut buf = itoa::Buffer::new();
+                    return f.write_int("", buf.format(*self), 3, stringify!($T));
+                }
+                // the magnitude, which a negative value is written with a
+                // sign ahead of, as a literal of a signed type can't hold
+                // its two's complement
+                let negative = *self < 0;
+                let magnitude = if negative {
+                    (*self as i128).unsigned_abs()
+                } else {
+                    *self as u128
+                };
+                f.write_int_in(negative, magnitude, stringify!($T));
             }
         }
     )*};
@@ -108,11 +136,18 @@ macro_rules! debug_floats {
     ($ty:ident) => {
         impl DebugPls for $ty {
             fn fmt(&self, f: Formatter<'_>) {
-                let mut buf = ryu::Buffer::new();
-                f.write_expr(syn::ExprLit {
-                    attrs: vec![],
-                    lit: syn::LitFloat::new(buf.format(*self), Span::call_site()).into(),
-                });
+                // written as the constants, since no literal has these values
+                if self.is_nan() {
+                    f.debug_ident(concat!(stringify!($ty), "::NAN"));
+                } else if *self == $ty::INFINITY {
+                    f.debug_ident(concat!(stringify!($ty), "::INFINITY"));
+                } else if *self == $ty::NEG_INFINITY {
+                    f.debug_ident(concat!(stringify!($ty), "::NEG_INFINITY"));
+                } else {
+                    // keeps the sign of `-0.0`
+                    let digits = f.tree.style.precision;
+                    f.write_float(*self, digits, stringify!($ty));
+                }
             }
         }
     };
@@ -132,34 +167,134 @@ impl DebugPls for bool {
 
 impl<D: DebugPls> DebugPls for [D] {
     fn fmt(&self, f: Formatter<'_>) {
-        f.debug_list().entries(self).finish();
+        f.write_vec(self);
     }
 }
 
 impl<D: DebugPls, const N: usize> DebugPls for [D; N] {
     fn fmt(&self, f: Formatter<'_>) {
-        f.debug_list().entries(self).finish();
+        f.write_list(self);
     }
 }
 
 impl DebugPls for char {
     fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(syn::ExprLit {
-            attrs: vec![],
-            lit: syn::LitChar::new(*self, Span::call_site()).into(),
+        f.write_lit_with(|buf| {
+            buf.push('\'');
+            // `escape_debug` would turn this into '\"', which is unnecessary
+            if *self == '"' {
+                buf.push('"');
+            } else {
+                buf.extend(self.escape_debug());
+            }
+            buf.push('\'');
         });
     }
 }
 
 impl DebugPls for str {
     fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(syn::ExprLit {
-            attrs: vec![],
-            lit: syn::LitStr::new(self, Span::call_site()).into(),
+        if let Some(max) = f.tree.truncation(f.id).max {
+            if let Some((end, _)) = self.char_indices().nth(max) {
+                let more = self[end..].chars().count();
+                return f.write_verbatim_with(|buf| {
+                    buf.push('"');
+                    escape_str(&self[..end], buf);
+                    buf.push_str("\" /* ");
+                    buf.push_str(itoa::Buffer::new().format(more));
+                    buf.push_str(" more chars */");
+                });
+            }
+        }
+        // a newline at the very end leaves the string on one line
+        let lines = matches!(self.find('\n'), Some(i) if i + 1 < self.len());
+        if lines && f.tree.style.multiline_strings {
+            let mut lines = Children::default();
+            f.tree.append_all(&mut lines, self.split_inclusive('\n'));
+            return f.write(Node::Concat(lines));
+        }
+        let raw = if f.tree.style.raw_strings {
+            raw_hashes(self)
+        } else {
+            None
+        };
+        if let Some(hashes) = raw {
+            return f.write_lit_with(|buf| {
+                buf.push('r');
+                buf.extend(std::iter::repeat_n('#', hashes));
+                buf.push('"');
+                buf.push_str(self);
+                buf.push('"');
+                buf.extend(std::iter::repeat_n('#', hashes));
+            });
+        }
+        f.write_lit_with(|buf| {
+            buf.push('"');
+            escape_str(self, buf);
+            buf.push('"');
         });
     }
 }
 
+/// Escapes the contents of a string literal, copying all the runs that need
+/// no escaping as they are.
+fn escape_str(s: &str, buf: &mut String) {
+    let mut run = 0;
+    let mut chars = s.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            // printable ASCII, including `'`, which `escape_debug` would
+            // needlessly turn into "\'"
+            ' '..='~' if ch != '"' && ch != '\\' => continue,
+            '\0'..='\x7f' => {}
+            ch if !needs_escape(ch) => continue,
+            _ => {}
+        }
+        buf.push_str(&s[run..i]);
+        run = i + ch.len_utf8();
+        match ch {
+            // `\0` followed by a digit reads like an octal escape
+            '\0' if chars.as_str().starts_with(|c| ('0'..='7').contains(&c)) => {
+                buf.push_str("\\x00");
+            }
+            '\0' => buf.push_str("\\0"),
+            ch => buf.extend(ch.escape_debug()),
+        }
+    }
+    buf.push_str(&s[run..]);
+}
+
+/// The number of `#`s that a raw string literal of `s` needs, or `None` if
+/// it would not save any escapes, or cannot be written as one for holding
+/// characters that have to be escaped.
+fn raw_hashes(s: &str) -> Option<usize> {
+    let mut escapes = false;
+    let mut hashes = 0;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            // the literal ends at a quote followed by as many `#`s
+            '"' => {
+                let run = s[i + 1..].bytes().take_while(|&b| b == b'#').count();
+                hashes = hashes.max(run + 1);
+                escapes = true;
+            }
+            '\\' => escapes = true,
+            ' '..='~' => {}
+            '\0'..='\x7f' => return None,
+            ch if needs_escape(ch) => return None,
+            _ => {}
+        }
+    }
+    escapes.then_some(hashes)
+}
+
+/// Whether a non-ASCII character is escaped: when it is not printable, or
+/// combines with the one before.
+fn needs_escape(ch: char) -> bool {
+    let mut escaped = ch.escape_debug();
+    !(escaped.next() == Some(ch) && escaped.next().is_none())
+}
+
 impl DebugPls for String {
     fn fmt(&self, f: Formatter<'_>) {
         DebugPls::fmt(self.as_str(), f);
@@ -169,8 +304,8 @@ impl DebugPls for String {
 impl<T: DebugPls, E: DebugPls> DebugPls for Result<T, E> {
     fn fmt(&self, f: Formatter<'_>) {
         match self {
-            Ok(t) => f.debug_tuple_struct("Ok").field(t).finish(),
-            Err(e) => f.debug_tuple_struct("Err").field(e).finish(),
+            Ok(t) => f.debug_ok(t),
+            Err(e) => f.debug_err(e),
         }
     }
 }
@@ -187,8 +322,8 @@ impl<B: DebugPls, C: DebugPls> DebugPls for ControlFlow<B, C> {
 impl<T: DebugPls> DebugPls for Option<T> {
     fn fmt(&self, f: Formatter<'_>) {
         match self {
-            Some(t) => f.debug_tuple_struct("Some").field(t).finish(),
-            None => f.debug_ident("None"),
+            Some(t) => f.debug_some(t),
+            None => f.debug_none(),
         }
     }
 }
@@ -204,66 +339,204 @@ impl<T: DebugPls> DebugPls for Poll<T> {
 
 impl<T: DebugPls> DebugPls for ops::Range<T> {
     fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(syn::ExprRange {
-            attrs: vec![],
-            from: Some(Box::new(Formatter::process(&self.start))),
-            limits: RangeLimits::HalfOpen(syn::token::Dot2::default()),
-            to: Some(Box::new(Formatter::process(&self.end))),
-        });
+        f.write_range(Some(&self.start), Some(&self.end), false);
     }
 }
 
 impl<T: DebugPls> DebugPls for ops::RangeFrom<T> {
     fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(syn::ExprRange {
-            attrs: vec![],
-            from: Some(Box::new(Formatter::process(&self.start))),
-            limits: RangeLimits::HalfOpen(syn::token::Dot2::default()),
-            to: None,
-        });
+        f.write_range(Some(&self.start), None, false);
     }
 }
 
 impl<T: DebugPls> DebugPls for ops::RangeTo<T> {
     fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(syn::ExprRange {
-            attrs: vec![],
-            from: None,
-            limits: RangeLimits::HalfOpen(syn::token::Dot2::default()),
-            to: Some(Box::new(Formatter::process(&self.end))),
-        });
+        f.write_range(None, Some(&self.end), false);
     }
 }
 
 impl DebugPls for ops::RangeFull {
     fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(syn::ExprRange {
-            attrs: vec![],
-            from: None,
-            limits: RangeLimits::HalfOpen(syn::token::Dot2::default()),
-            to: None,
-        });
+        f.write_range(None, None, false);
     }
 }
 
 impl<T: DebugPls> DebugPls for ops::RangeInclusive<T> {
     fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(syn::ExprRange {
-            attrs: vec![],
-            from: Some(Box::new(Formatter::process(&self.start()))),
-            limits: RangeLimits::Closed(syn::token::DotDotEq::default()),
-            to: Some(Box::new(Formatter::process(&self.end()))),
-        });
+        f.write_range(Some(&self.start()), Some(&self.end()), true);
     }
 }
 
 impl<T: DebugPls> DebugPls for ops::RangeToInclusive<T> {
     fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(syn::ExprRange {
-            attrs: vec![],
-            from: None,
-            limits: RangeLimits::Closed(syn::token::DotDotEq::default()),
-            to: Some(Box::new(Formatter::process(&self.end))),
-        });
+        f.write_range(None, Some(&self.end), true);
+    }
+}
+
+#[cfg(all(test, feature = "proc-macro2"))]
+mod tests {
+    use proc_macro2::Literal;
+
+    use crate::{
+        node::{Node, Tree},
+        DebugPls,
+    };
+
+    fn lit(value: &dyn DebugPls) -> String {
+        let tree = Tree::capture(value);
+        match tree.get(Tree::ROOT) {
+            Node::Lit(text) => tree.str(text).to_owned(),
+            node => panic!("expected a literal, got {:?}", node),
+        }
+    }
+
+    #[test]
+    fn escapes_like_proc_macro2() {
+        let strs = [
+            "",
+            "hello",
+            "\"quoted\"",
+            "it's",
+            "tab\tnew\nline\r",
+            "\\",
+            "\0",
+            "\x001",
+            "\08",
+            "\u{301}",
+            "a\u{301}",
+            "é",
+            "\u{7f}",
+            "\u{200b}",
+            "🦀",
+            "naïve \"café\"\n\u{200b}end\0",
+        ];
+        for s in strs {
+            assert_eq!(lit(&s), Literal::string(s).to_string());
+        }
+        for c in ['a', '\'', '"', '\\', '\0', '\n', '\u{301}', '\u{7f}', '🦀'] {
+            assert_eq!(lit(&c), Literal::character(c).to_string());
+        }
+    }
+
+    #[test]
+    fn escapes_every_control_character() {
+        let controls = ('\0'..='\x1f').chain('\x7f'..='\u{9f}');
+        for c in controls {
+            let s = format!("a{c}b{c}0");
+            for lit in [lit(&c), lit(&s)] {
+                assert_eq!(lit.find(char::is_control), None, "{lit}");
+            }
+            assert_eq!(lit(&c), Literal::character(c).to_string());
+            assert_eq!(lit(&s), Literal::string(&s).to_string());
+        }
+    }
+
+    #[test]
+    fn escapes_every_char_like_proc_macro2() {
+        for c in (0..=u32::from(char::MAX)).filter_map(char::from_u32) {
+            assert_eq!(lit(&c), Literal::character(c).to_string());
+        }
+    }
+
+    #[test]
+    fn integers_like_proc_macro2() {
+        assert_eq!(
+            lit(&u128::MAX),
+            Literal::u128_unsuffixed(u128::MAX).to_string()
+        );
+        assert_eq!(
+            lit(&i128::MIN),
+            Literal::i128_unsuffixed(i128::MIN).to_string()
+        );
+        assert_eq!(lit(&i8::MIN), Literal::i8_unsuffixed(i8::MIN).to_string());
+        assert_eq!(lit(&0_usize), Literal::usize_unsuffixed(0).to_string());
+    }
+
+    #[test]
+    fn floats_are_valid_rust() {
+        fn text(value: &dyn DebugPls) -> String {
+            let tree = Tree::capture(value);
+            match tree.get(Tree::ROOT) {
+                Node::Lit(text) | Node::Path(text) => tree.str(text).to_owned(),
+                node => panic!("expected a literal or path, got {:?}", node),
+            }
+        }
+
+        assert_eq!(text(&f64::NAN), "f64::NAN");
+        assert_eq!(text(&-f32::NAN), "f32::NAN");
+        assert_eq!(text(&f64::INFINITY), "f64::INFINITY");
+        assert_eq!(text(&f32::NEG_INFINITY), "f32::NEG_INFINITY");
+        assert_eq!(text(&-0.0_f64), "-0.0");
+        for x in [0.0, -0.0, 1.5, -1e300, f64::MIN_POSITIVE, f64::MAX] {
+            let text = text(&x);
+            let parsed: f64 = text.parse().unwrap();
+            assert_eq!(parsed.to_bits(), x.to_bits(), "{text}");
+        }
+    }
+
+    #[test]
+    fn floats_round_trip() {
+        fn significant_digits(lit: &str) -> usize {
+            let mantissa = lit.split('e').next().unwrap();
+            let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+            digits.trim_matches('0').len()
+        }
+
+        // xorshift, to cover the bit patterns evenly without a dependency
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..100_000 {
+            let bits = next();
+            let x = f64::from_bits(bits);
+            if x.is_finite() {
+                let lit = lit(&x);
+                assert_eq!(lit.parse::<f64>().unwrap().to_bits(), bits, "{lit}");
+                // the shortest that round trips, as std's own output is
+                assert_eq!(
+                    significant_digits(&lit),
+                    significant_digits(&format!("{x:?}"))
+                );
+            }
+            #[allow(clippy::cast_possible_truncation)]
+            let bits = (bits >> 32) as u32;
+            let x = f32::from_bits(bits);
+            if x.is_finite() {
+                let lit = lit(&x);
+                assert_eq!(lit.parse::<f32>().unwrap().to_bits(), bits, "{lit}");
+                assert_eq!(
+                    significant_digits(&lit),
+                    significant_digits(&format!("{x:?}"))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn summarizes_huge_lists() {
+        use crate::node::MAX_LEN;
+
+        fn verbatim(value: &dyn DebugPls) -> String {
+            let tree = Tree::capture(value);
+            match tree.get(Tree::ROOT) {
+                Node::Verbatim(text) => tree.str(text).to_owned(),
+                node => panic!("expected a summary, got {:?}", node),
+            }
+        }
+
+        assert_eq!(
+            verbatim(&vec![(); usize::MAX]),
+            format!("[..; {}]", usize::MAX)
+        );
+        assert_eq!(
+            verbatim(&vec![0_u8; MAX_LEN + 1].into_boxed_slice()),
+            format!("[..; {}]", MAX_LEN + 1)
+        );
+        let tree = Tree::capture(&vec![(); MAX_LEN]);
+        assert!(matches!(tree.get(Tree::ROOT), Node::List(_)));
     }
 }
@@ -0,0 +1,130 @@
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use crate::{push_fmt, DebugPls, Formatter};
+
+impl DebugPls for OsStr {
+    fn fmt(&self, f: Formatter<'_>) {
+        match self.to_str() {
+            Some(s) => DebugPls::fmt(s, f),
+            None => not_unicode(self, f),
+        }
+    }
+}
+
+impl DebugPls for OsString {
+    fn fmt(&self, f: Formatter<'_>) {
+        DebugPls::fmt(self.as_os_str(), f);
+    }
+}
+
+impl DebugPls for Path {
+    fn fmt(&self, f: Formatter<'_>) {
+        DebugPls::fmt(self.as_os_str(), f);
+    }
+}
+
+impl DebugPls for PathBuf {
+    fn fmt(&self, f: Formatter<'_>) {
+        DebugPls::fmt(self.as_os_str(), f);
+    }
+}
+
+/// Writes the bytes as a byte string literal, `b"caf\xC3\xA9\xFF"`, since
+/// they are not valid UTF-8.
+#[cfg(unix)]
+fn not_unicode(s: &OsStr, f: Formatter<'_>) {
+    use std::os::unix::ffi::OsStrExt;
+
+    f.write_lit_with(|buf| {
+        buf.push_str("b\"");
+        let mut bytes = s.as_bytes().iter();
+        while let Some(&b) = bytes.next() {
+            match b {
+                // `\0` followed by a digit reads like an octal escape
+                b'\0' if matches!(bytes.as_slice().first(), Some(b'0'..=b'7')) => {
+                    buf.push_str("\\x00");
+                }
+                b'\0' => buf.push_str("\\0"),
+                b'\t' => buf.push_str("\\t"),
+                b'\n' => buf.push_str("\\n"),
+                b'\r' => buf.push_str("\\r"),
+                b'"' => buf.push_str("\\\""),
+                b'\\' => buf.push_str("\\\\"),
+                b' '..=b'~' => buf.push(char::from(b)),
+                _ => {
+                    push_fmt(buf, format_args!("\\x{b:02X}"));
+                }
+            }
+        }
+        buf.push('"');
+    });
+}
+
+/// Writes the string with its unpaired surrogates escaped, `"caf\u{d800}"`,
+/// since they cannot be written any other way.
+#[cfg(windows)]
+fn not_unicode(s: &OsStr, f: Formatter<'_>) {
+    use std::os::windows::ffi::OsStrExt;
+
+    f.write_lit_with(|buf| {
+        buf.push('"');
+        let mut run = String::new();
+        for ch in char::decode_utf16(s.encode_wide()) {
+            match ch {
+                Ok(ch) => run.push(ch),
+                Err(err) => {
+                    super::escape_str(&run, buf);
+                    run.clear();
+                    push_fmt(buf, format_args!("\\u{{{:x}}}", err.unpaired_surrogate()));
+                }
+            }
+        }
+        super::escape_str(&run, buf);
+        buf.push('"');
+    });
+}
+
+/// Falls back to the standard `Debug` output, which is as faithful to the
+/// platform's encoding as it gets.
+#[cfg(not(any(unix, windows)))]
+fn not_unicode(s: &OsStr, f: Formatter<'_>) {
+    f.write_lit_with(|buf| {
+        push_fmt(buf, format_args!("{s:?}"));
+    });
+}
+
+#[cfg(all(test, unix, feature = "proc-macro2"))]
+mod tests {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+    use proc_macro2::Literal;
+
+    use crate::node::{Node, Tree};
+
+    fn lit(path: &Path) -> String {
+        let tree = Tree::capture(&path);
+        match tree.get(Tree::ROOT) {
+            Node::Lit(text) => tree.str(text).to_owned(),
+            node => panic!("expected a literal, got {:?}", node),
+        }
+    }
+
+    #[test]
+    fn keeps_every_byte() {
+        assert_eq!(lit(Path::new("/tmp/café")), "\"/tmp/café\"");
+        let paths: [&[u8]; 5] = [
+            b"\xff",
+            b"/tmp/caf\xc3\xa9\xff",
+            b"\"quoted\"\\\xfe",
+            b"tab\tnew\nline\r\0\xff",
+            b"\x001\x7f\x80",
+        ];
+        for bytes in paths {
+            let path = Path::new(OsStr::from_bytes(bytes));
+            assert_eq!(lit(path), Literal::byte_string(bytes).to_string());
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::{
+    convert::TryFrom,
+    time::{Duration, SystemTime},
+};
+
+use crate::{humanize, push_fmt, DebugPls, Formatter};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Written as the constructor that makes it, in the largest unit that
+/// keeps it exact, `Duration::from_millis(1500)`.
+impl DebugPls for Duration {
+    fn fmt(&self, f: Formatter<'_>) {
+        if !f.tree.style.humanized {
+            let (constructor, secs, nanos) = constructor(*self);
+            let f = f.debug_tuple_struct(constructor).field(&secs);
+            return match nanos {
+                Some(nanos) => f.field(&nanos).finish(),
+                None => f.finish(),
+            };
+        }
+        let suffixes = f.tree.style.suffixes;
+        f.write_verbatim_with(|buf| {
+            write_constructor(*self, suffixes, buf);
+            buf.push_str(" /* ");
+            humanize::duration(*self, buf);
+            buf.push_str(" */");
+        });
+    }
+}
+
+/// Written as its distance from the Unix epoch,
+/// `SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000)`.
+impl DebugPls for SystemTime {
+    fn fmt(&self, f: Formatter<'_>) {
+        let (sign, since) = match self.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(since) => ('+', since),
+            Err(err) => ('-', err.duration()),
+        };
+        let style = f.tree.style;
+        f.write_verbatim_with(|buf| {
+            buf.push_str("SystemTime::UNIX_EPOCH ");
+            buf.push(sign);
+            buf.push(' ');
+            write_constructor(since, style.suffixes, buf);
+            if style.humanized {
+                buf.push_str(" /* ");
+                timestamp(sign, since, buf);
+                buf.push_str(" */");
+            }
+        });
+    }
+}
+
+/// The constructor for the duration and its arguments, the nanoseconds
+/// only being given to `Duration::new`.
+fn constructor(duration: Duration) -> (&'static str, u64, Option<u32>) {
+    let nanos = duration.as_nanos();
+    let units = [
+        ("Duration::from_secs", NANOS_PER_SEC),
+        ("Duration::from_millis", 1_000_000),
+        ("Duration::from_micros", 1_000),
+        ("Duration::from_nanos", 1),
+    ];
+    for (constructor, unit) in units {
+        if nanos.is_multiple_of(unit) {
+            if let Ok(count) = u64::try_from(nanos / unit) {
+                return (constructor, count, None);
+            }
+        }
+    }
+    (
+        "Duration::new",
+        duration.as_secs(),
+        Some(duration.subsec_nanos()),
+    )
+}
+
+fn write_constructor(duration: Duration, suffixes: bool, buf: &mut String) {
+    let (constructor, secs, nanos) = constructor(duration);
+    buf.push_str(constructor);
+    buf.push('(');
+    buf.push_str(itoa::Buffer::new().format(secs));
+    if suffixes {
+        buf.push_str("u64");
+    }
+    if let Some(nanos) = nanos {
+        buf.push_str(", ");
+        buf.push_str(itoa::Buffer::new().format(nanos));
+        if suffixes {
+            buf.push_str("u32");
+        }
+    }
+    buf.push(')');
+}
+
+/// Writes the time `since` the epoch as an RFC 3339 timestamp in UTC,
+/// `2023-11-14T22:13:20.5Z`.
+fn timestamp(sign: char, since: Duration, buf: &mut String) {
+    let (mut secs, mut nanos) = match i64::try_from(since.as_secs()) {
+        Ok(secs) => (secs, since.subsec_nanos()),
+        Err(_) => return buf.push_str("out of range"),
+    };
+    if sign == '-' {
+        secs = -secs;
+        if nanos > 0 {
+            secs -= 1;
+            nanos = 1_000_000_000 - nanos;
+        }
+    }
+    let (year, month, day) = civil(secs.div_euclid(86_400));
+    let time = secs.rem_euclid(86_400);
+    push_fmt(
+        buf,
+        format_args!(
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+            time / 3_600,
+            time / 60 % 60,
+            time % 60
+        ),
+    );
+    if nanos > 0 {
+        push_fmt(buf, format_args!(".{nanos:09}"));
+        while buf.ends_with('0') {
+            buf.pop();
+        }
+    }
+    buf.push('Z');
+}
+
+/// The year, month and day `days` after 1970-01-01 in the proleptic
+/// Gregorian calendar, after Howard Hinnant's `civil_from_days`.
+fn civil(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(all(test, feature = "pretty"))]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use crate::pretty;
+
+    fn humanized(duration: Duration) -> String {
+        let output = pretty(&duration).humanized().to_string();
+        let start = output.find("/* ").unwrap() + 3;
+        output[start..output.len() - 3].to_owned()
+    }
+
+    #[test]
+    fn durations() {
+        let cases = [
+            (Duration::from_secs(90), "Duration::from_secs(90)"),
+            (Duration::from_millis(1500), "Duration::from_millis(1500)"),
+            (Duration::from_nanos(1), "Duration::from_nanos(1)"),
+            (
+                Duration::MAX,
+                "Duration::new(18446744073709551615, 999999999)",
+            ),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(pretty(&duration).to_string(), expected);
+        }
+        assert_eq!(
+            pretty(&Duration::from_micros(5)).suffixes().to_string(),
+            "Duration::from_micros(5u64)",
+        );
+
+        assert_eq!(humanized(Duration::ZERO), "0ns");
+        assert_eq!(humanized(Duration::from_nanos(12_345)), "12.3µs");
+        assert_eq!(humanized(Duration::from_micros(12_500)), "12.5ms");
+        assert_eq!(humanized(Duration::from_millis(3_400)), "3.4s");
+        assert_eq!(humanized(Duration::from_millis(123_400)), "2m 3.4s");
+        assert_eq!(humanized(Duration::from_secs(90_061)), "1d 1h 1m 1s");
+
+        assert_eq!(humanized(Duration::from_nanos(999)), "999ns");
+        assert_eq!(humanized(Duration::from_nanos(999_940)), "999.9µs");
+        assert_eq!(humanized(Duration::from_nanos(999_960)), "1ms");
+        assert_eq!(humanized(Duration::from_micros(999_960)), "1s");
+        assert_eq!(humanized(Duration::from_millis(59_940)), "59.9s");
+        assert_eq!(humanized(Duration::from_millis(59_999)), "1m");
+        assert_eq!(humanized(Duration::from_millis(119_960)), "2m");
+        assert_eq!(humanized(Duration::from_millis(3_599_960)), "1h");
+        assert_eq!(humanized(Duration::from_millis(86_399_960)), "1d");
+        assert_eq!(humanized(Duration::MAX), "213503982334601d 7h 16s");
+    }
+
+    #[test]
+    fn timestamps() {
+        let time = |secs: i64, nanos: u32| {
+            let since = Duration::new(secs.unsigned_abs(), nanos);
+            let time = if secs < 0 {
+                SystemTime::UNIX_EPOCH - since
+            } else {
+                SystemTime::UNIX_EPOCH + since
+            };
+            pretty(&time).humanized().to_string()
+        };
+        assert_eq!(
+            time(0, 0),
+            "SystemTime::UNIX_EPOCH + Duration::from_secs(0) /* 1970-01-01T00:00:00Z */",
+        );
+        assert_eq!(
+            time(951_782_400, 500_000_000),
+            "SystemTime::UNIX_EPOCH + Duration::from_millis(951782400500) /* 2000-02-29T00:00:00.5Z */",
+        );
+        assert_eq!(
+            time(-1, 250_000_000),
+            "SystemTime::UNIX_EPOCH - Duration::from_millis(1250) /* 1969-12-31T23:59:58.75Z */",
+        );
+    }
+}
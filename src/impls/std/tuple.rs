@@ -11,11 +11,7 @@ macro_rules! tuple {
             #[allow(non_snake_case, unused_assignments)]
             fn fmt(&self, f: Formatter<'_>) {
                 let ($(ref $name,)+) = *self;
-                f.debug_tuple()
-                $(
-                    .field(&$name)
-                )+
-                    .finish();
+                f.debug_tuple_fields_finish(&[$(&$name),+]);
             }
         }
         peel! { $($name,)+ }
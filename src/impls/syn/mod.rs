@@ -4,13 +4,6 @@ mod gen;
 
 use crate::{DebugPls, Formatter};
 
-struct DebugSelf<T>(T);
-impl<T: Into<syn::Expr> + Clone> DebugPls for DebugSelf<T> {
-    fn fmt(&self, f: Formatter<'_>) {
-        f.write_expr(self.0.clone());
-    }
-}
-
 struct DebugLit<T>(T);
 impl<T: Into<syn::Lit> + Clone> DebugPls for DebugLit<T> {
     fn fmt(&self, f: Formatter<'_>) {
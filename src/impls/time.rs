@@ -0,0 +1,254 @@
+use std::convert::TryFrom;
+
+use ::time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday};
+
+use crate::{humanize, push_fmt, DebugPls, Formatter};
+
+/// Written as the `date!` that makes it, `date!(2023-11-14)`.
+impl DebugPls for Date {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_verbatim_with(|buf| {
+            buf.push_str("date!(");
+            write_date(*self, buf);
+            buf.push(')');
+        });
+    }
+}
+
+/// Written as the `time!` that makes it, `time!(22:13:20.5)`.
+impl DebugPls for Time {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_verbatim_with(|buf| {
+            buf.push_str("time!(");
+            write_time(*self, buf);
+            buf.push(')');
+        });
+    }
+}
+
+/// Written as the `datetime!` that makes it,
+/// `datetime!(2023-11-14 22:13:20)`.
+impl DebugPls for PrimitiveDateTime {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_verbatim_with(|buf| {
+            buf.push_str("datetime!(");
+            write_date(self.date(), buf);
+            buf.push(' ');
+            write_time(self.time(), buf);
+            buf.push(')');
+        });
+    }
+}
+
+/// Written as the `datetime!` that makes it, with its offset,
+/// `datetime!(2023-11-14 22:13:20 UTC)`.
+impl DebugPls for OffsetDateTime {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_verbatim_with(|buf| {
+            buf.push_str("datetime!(");
+            write_date(self.date(), buf);
+            buf.push(' ');
+            write_time(self.time(), buf);
+            buf.push(' ');
+            write_offset(self.offset(), buf);
+            buf.push(')');
+        });
+    }
+}
+
+/// Written as the `offset!` that makes it, `offset!(+01:00)`.
+impl DebugPls for UtcOffset {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_verbatim_with(|buf| {
+            buf.push_str("offset!(");
+            write_offset(*self, buf);
+            buf.push(')');
+        });
+    }
+}
+
+/// Written as the constructor that makes it, in the largest unit that
+/// keeps it exact, `Duration::milliseconds(1500)`. With
+/// [`Pretty::humanized`](crate::Pretty::humanized), it is followed by a
+/// comment that reads it out, as std's durations are.
+impl DebugPls for Duration {
+    fn fmt(&self, f: Formatter<'_>) {
+        let style = f.tree.style;
+        let (constructor, secs, nanos) = constructor(*self);
+        f.write_verbatim_with(|buf| {
+            push_fmt(buf, format_args!("{constructor}({secs}"));
+            if style.suffixes {
+                buf.push_str("i64");
+            }
+            if let Some(nanos) = nanos {
+                push_fmt(buf, format_args!(", {nanos}"));
+                if style.suffixes {
+                    buf.push_str("i32");
+                }
+            }
+            buf.push(')');
+            if style.humanized {
+                buf.push_str(" /* ");
+                if self.is_negative() {
+                    buf.push('-');
+                }
+                humanize::duration(self.unsigned_abs(), buf);
+                buf.push_str(" */");
+            }
+        });
+    }
+}
+
+/// The constructor for the duration and its arguments, the nanoseconds
+/// only being given to `Duration::new`.
+fn constructor(duration: Duration) -> (&'static str, i64, Option<i32>) {
+    let nanos = duration.whole_nanoseconds();
+    let units = [
+        ("Duration::seconds", 1_000_000_000),
+        ("Duration::milliseconds", 1_000_000),
+        ("Duration::microseconds", 1_000),
+        ("Duration::nanoseconds", 1),
+    ];
+    for (constructor, unit) in units {
+        if nanos % unit == 0 {
+            if let Ok(count) = i64::try_from(nanos / unit) {
+                return (constructor, count, None);
+            }
+        }
+    }
+    (
+        "Duration::new",
+        duration.whole_seconds(),
+        Some(duration.subsec_nanoseconds()),
+    )
+}
+
+/// Written as the variant it is, `Month::November`.
+impl DebugPls for Month {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_ident(&format!("Month::{self:?}"));
+    }
+}
+
+/// Written as the variant it is, `Weekday::Tuesday`.
+impl DebugPls for Weekday {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_ident(&format!("Weekday::{self:?}"));
+    }
+}
+
+/// `2023-11-14`, with a sign for years of more than four digits, as the
+/// `date!` macro takes them.
+fn write_date(date: Date, buf: &mut String) {
+    let year = date.year();
+    let sign = match year {
+        ..=-1 => "-",
+        10_000.. => "+",
+        _ => "",
+    };
+    push_fmt(
+        buf,
+        format_args!(
+            "{sign}{:04}-{:02}-{:02}",
+            year.unsigned_abs(),
+            u8::from(date.month()),
+            date.day()
+        ),
+    );
+}
+
+/// `22:13:20.5`, with only as many digits of the fraction as it needs.
+fn write_time(time: Time, buf: &mut String) {
+    let (hour, minute, second, nanos) = time.as_hms_nano();
+    push_fmt(buf, format_args!("{hour:02}:{minute:02}:{second:02}"));
+    if nanos > 0 {
+        push_fmt(buf, format_args!(".{nanos:09}"));
+        while buf.ends_with('0') {
+            buf.pop();
+        }
+    }
+}
+
+/// `UTC`, `+01:00` or `-00:30:15`, the seconds only being written when
+/// there are some.
+fn write_offset(offset: UtcOffset, buf: &mut String) {
+    if offset.is_utc() {
+        return buf.push_str("UTC");
+    }
+    let (hours, minutes, seconds) = offset.as_hms();
+    buf.push(if offset.is_negative() { '-' } else { '+' });
+    push_fmt(
+        buf,
+        format_args!("{:02}:{:02}", hours.unsigned_abs(), minutes.unsigned_abs()),
+    );
+    if seconds != 0 {
+        push_fmt(buf, format_args!(":{:02}", seconds.unsigned_abs()));
+    }
+}
+
+#[cfg(all(test, feature = "pretty"))]
+mod tests {
+    use ::time::{
+        macros::{date, datetime, offset, time},
+        Duration, Month,
+    };
+
+    use crate::pretty;
+
+    #[test]
+    fn dates_and_times() {
+        assert_eq!(
+            pretty(&date!(2023 - 11 - 14)).to_string(),
+            "date!(2023-11-14)"
+        );
+        assert_eq!(
+            pretty(&date!(-0044 - 03 - 15)).to_string(),
+            "date!(-0044-03-15)"
+        );
+        assert_eq!(pretty(&time!(0:00)).to_string(), "time!(00:00:00)");
+        assert_eq!(pretty(&time!(22:13:20.5)).to_string(), "time!(22:13:20.5)");
+        assert_eq!(
+            pretty(&datetime!(2023-11-14 22:13:20.000_001)).to_string(),
+            "datetime!(2023-11-14 22:13:20.000001)",
+        );
+        assert_eq!(
+            pretty(&datetime!(2023-11-14 22:13:20 UTC)).to_string(),
+            "datetime!(2023-11-14 22:13:20 UTC)",
+        );
+        assert_eq!(
+            pretty(&datetime!(2023-11-14 23:13:20 +1)).to_string(),
+            "datetime!(2023-11-14 23:13:20 +01:00)",
+        );
+        assert_eq!(pretty(&offset!(-0:30:15)).to_string(), "offset!(-00:30:15)");
+        assert_eq!(pretty(&Month::November).to_string(), "Month::November");
+    }
+
+    #[test]
+    fn durations() {
+        let cases = [
+            (Duration::seconds(90), "Duration::seconds(90)"),
+            (
+                Duration::milliseconds(-1500),
+                "Duration::milliseconds(-1500)",
+            ),
+            (Duration::ZERO, "Duration::seconds(0)"),
+            (
+                Duration::MAX,
+                "Duration::new(9223372036854775807, 999999999)",
+            ),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(pretty(&duration).to_string(), expected);
+        }
+        assert_eq!(
+            pretty(&Duration::microseconds(5)).suffixes().to_string(),
+            "Duration::microseconds(5i64)",
+        );
+        assert_eq!(
+            pretty(&Duration::milliseconds(-123_400))
+                .humanized()
+                .to_string(),
+            "Duration::milliseconds(-123400) /* -2m 3.4s */",
+        );
+    }
+}
@@ -0,0 +1,586 @@
+//! The built-in layout engine.
+//!
+//! Output is described as a document made up of text, breaks and boxes.
+//! A box either fits on the remainder of the current line, or it is broken:
+//! consistent boxes then break at every one of their breaks, inconsistent
+//! boxes only at the breaks where the following chunk does not fit.
+//!
+//! The engine follows the box model of Oppen's pretty printer, which is the
+//! same model `prettyplease` (and rustc before it) uses, so the layout
+//! decisions match what the `syn-backend` would produce for the same
+//! [`Tree`]s.
+
+use std::{borrow::Cow, collections::VecDeque};
+
+use crate::node::{NodeId, Tree};
+
+mod branch;
+mod node;
+mod table;
+
+const MARGIN: isize = 89;
+const INDENT: isize = 4;
+const MIN_SPACE: isize = 60;
+const SIZE_INFINITY: isize = 0xffff;
+
+/// Width taken up by the `const _: () = ` prefix that the `syn-backend`
+/// lays the value out behind. Reserving it keeps both backends identical.
+const LEAD: isize = 14;
+
+/// How much of a line is left to the value, in characters.
+pub(crate) const WIDTH: usize = (MARGIN - LEAD - 1) as usize;
+
+pub(crate) fn pretty_string(tree: &Tree) -> String {
+    layout(Printer::new(tree), Tree::ROOT, LEAD)
+}
+
+/// Lays out the node `id` as if it were the whole value.
+pub(crate) fn node_string(tree: &Tree, id: NodeId) -> String {
+    layout(Printer::new(tree), id, LEAD)
+}
+
+/// Lays out the tree like [`pretty_string`], with the text of the node
+/// `id` written between `start` and `end`, which take up no width.
+pub(crate) fn pretty_string_marked(
+    tree: &Tree,
+    id: NodeId,
+    start: &'static str,
+    end: &'static str,
+) -> String {
+    let mut p = Printer::new(tree);
+    p.mark = Some(Mark { id, start, end });
+    layout(p, Tree::ROOT, LEAD)
+}
+
+/// The text of [`Node::More`](crate::node::Node::More), `.. /* 4032 more */`.
+fn more(more: usize) -> String {
+    format!(".. /* {more} more */")
+}
+
+/// Lays out the node `id` on its own, with its first line starting after
+/// `lead` characters.
+fn layout(mut p: Printer<'_>, id: NodeId, lead: isize) -> String {
+    p.cbox(0);
+    p.cbox(0);
+    p.reserve(lead);
+    p.neverbreak();
+    p.node(id);
+    // the trailing `;` takes part in the fitting decisions of the last line
+    p.reserve(1);
+    p.end();
+    p.hardbreak();
+    p.end();
+    let mut output = p.eof();
+    output.pop();
+    output
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Breaks {
+    Consistent,
+    Inconsistent,
+}
+
+#[derive(Clone, Copy, Default)]
+struct BreakToken {
+    offset: isize,
+    blank_space: usize,
+    pre_break: Option<char>,
+    if_nonempty: bool,
+    never_break: bool,
+}
+
+#[derive(Clone, Copy)]
+struct BeginToken {
+    offset: isize,
+    breaks: Breaks,
+}
+
+enum Token<'a> {
+    /// Text that is written out as is
+    Text(Cow<'a, str>),
+    /// Width that takes part in the layout but is never written out
+    Reserved(isize),
+    Break(BreakToken),
+    Begin(BeginToken),
+    End,
+    /// Text that is written out, but takes up no width, such as an escape
+    /// code
+    Mark(&'static str),
+}
+
+/// A node whose text is written between two [`Token::Mark`]s.
+#[derive(Clone, Copy)]
+struct Mark {
+    id: NodeId,
+    start: &'static str,
+    end: &'static str,
+}
+
+struct Entry<'a> {
+    token: Token<'a>,
+    size: isize,
+}
+
+#[derive(Clone, Copy)]
+enum Frame {
+    Fits,
+    Broken(usize, Breaks),
+}
+
+/// A queue of tokens with stable indices, so that the scan stack can refer
+/// to entries that have not been printed yet.
+struct Queue<'a> {
+    data: VecDeque<Entry<'a>>,
+    offset: usize,
+}
+
+impl<'a> Queue<'a> {
+    fn push(&mut self, entry: Entry<'a>) -> usize {
+        let index = self.offset + self.data.len();
+        self.data.push_back(entry);
+        index
+    }
+
+    fn pop_first(&mut self) -> Entry<'a> {
+        self.offset += 1;
+        self.data.pop_front().unwrap()
+    }
+
+    fn get(&mut self, index: usize) -> &mut Entry<'a> {
+        &mut self.data[index - self.offset]
+    }
+}
+
+/// Text is borrowed from the laid out [`Tree`] where possible, so that
+/// printing a large value does not copy every leaf.
+pub(crate) struct Printer<'a> {
+    tree: &'a Tree,
+    out: String,
+    /// Number of columns left on the current line
+    space: isize,
+    buf: Queue<'a>,
+    /// Total size of the tokens already printed
+    left_total: isize,
+    /// Total size of the tokens enqueued so far
+    right_total: isize,
+    /// Indices of the `Begin`s and `Break`s whose size is not yet known
+    scan_stack: VecDeque<usize>,
+    print_stack: Vec<Frame>,
+    indent: usize,
+    /// Indentation is only written out once text follows, to avoid
+    /// trailing whitespace
+    pending_indentation: usize,
+    mark: Option<Mark>,
+    /// How many of the nodes being laid out are kept on one line
+    compact: usize,
+    /// Whether the node being laid out ends in a comma when it is broken
+    /// over lines
+    trailing_commas: bool,
+}
+
+impl<'a> Printer<'a> {
+    fn new(tree: &'a Tree) -> Self {
+        Printer {
+            tree,
+            out: String::new(),
+            space: MARGIN,
+            buf: Queue {
+                data: VecDeque::new(),
+                offset: 0,
+            },
+            left_total: 0,
+            right_total: 0,
+            scan_stack: VecDeque::new(),
+            print_stack: Vec::new(),
+            indent: 0,
+            pending_indentation: 0,
+            mark: None,
+            compact: 0,
+            trailing_commas: !tree.style.no_trailing_commas,
+        }
+    }
+
+    fn eof(mut self) -> String {
+        if !self.scan_stack.is_empty() {
+            self.check_stack(0);
+            self.advance_left();
+        }
+        self.out
+    }
+
+    /// Opens a box whose breaks are decided one by one.
+    fn ibox(&mut self, indent: isize) {
+        self.scan_begin(BeginToken {
+            offset: indent,
+            breaks: Breaks::Inconsistent,
+        });
+    }
+
+    /// Opens a box that breaks at all of its breaks if it does not fit.
+    fn cbox(&mut self, indent: isize) {
+        self.scan_begin(BeginToken {
+            offset: indent,
+            breaks: Breaks::Consistent,
+        });
+    }
+
+    fn end(&mut self) {
+        self.scan_end();
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn word(&mut self, word: impl Into<Cow<'a, str>>) {
+        let word = word.into();
+        let size = word.len() as isize;
+        self.scan_text(Token::Text(word), size);
+    }
+
+    fn reserve(&mut self, width: isize) {
+        self.scan_text(Token::Reserved(width), width);
+    }
+
+    fn marker(&mut self, text: &'static str) {
+        self.scan_text(Token::Mark(text), 0);
+    }
+
+    fn zerobreak(&mut self) {
+        self.scan_break(BreakToken::default());
+    }
+
+    fn space(&mut self) {
+        self.scan_break(BreakToken {
+            blank_space: 1,
+            ..BreakToken::default()
+        });
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn hardbreak(&mut self) {
+        self.scan_break(BreakToken {
+            blank_space: SIZE_INFINITY as usize,
+            ..BreakToken::default()
+        });
+    }
+
+    fn space_if_nonempty(&mut self) {
+        self.scan_break(BreakToken {
+            blank_space: 1,
+            if_nonempty: true,
+            ..BreakToken::default()
+        });
+    }
+
+    fn neverbreak(&mut self) {
+        self.scan_break(BreakToken {
+            never_break: true,
+            ..BreakToken::default()
+        });
+    }
+
+    /// A separator that turns into a trailing comma when the box is broken.
+    fn trailing_comma(&mut self, is_last: bool) {
+        if is_last {
+            self.scan_break(BreakToken {
+                pre_break: self.trailing_commas.then_some(','),
+                ..BreakToken::default()
+            });
+        } else {
+            self.word(",");
+            self.space();
+        }
+    }
+
+    fn trailing_comma_or_space(&mut self, is_last: bool) {
+        if is_last {
+            self.scan_break(BreakToken {
+                blank_space: 1,
+                pre_break: self.trailing_commas.then_some(','),
+                ..BreakToken::default()
+            });
+        } else {
+            self.word(",");
+            self.space();
+        }
+    }
+
+    /// Writes the comment of a child after it, up to the end of the line,
+    /// or as a block comment where the line is not broken.
+    fn comment(&mut self, comment: &'a str) {
+        if self.compact > 0 {
+            self.word(" /* ");
+            self.word(comment.replace("*/", "* /"));
+            self.word(" */");
+        } else {
+            self.word(" // ");
+            self.word(comment);
+        }
+    }
+
+    /// Adjusts the indentation of the most recent break.
+    fn offset(&mut self, offset: isize) {
+        if let Some(Entry {
+            token: Token::Break(token),
+            ..
+        }) = self.buf.data.back_mut()
+        {
+            token.offset += offset;
+        }
+    }
+
+    /// Forces the current box to break, as if its contents were too wide
+    /// for the line.
+    fn force_break(&mut self) {
+        self.scan_text(Token::Reserved(0), SIZE_INFINITY);
+    }
+
+    /// Closes the current box, forcing it to break if its contents
+    /// are wider than `max`.
+    fn end_with_max_width(&mut self, max: isize) {
+        if self.compact > 0 {
+            return self.scan_end();
+        }
+        let mut depth = 1;
+        for &index in self.scan_stack.iter().rev() {
+            let entry = &self.buf.data[index - self.buf.offset];
+            match entry.token {
+                Token::Begin(_) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        if entry.size < 0 && entry.size + self.right_total > max {
+                            self.buf.push(Entry {
+                                token: Token::Reserved(0),
+                                size: SIZE_INFINITY,
+                            });
+                            self.right_total += SIZE_INFINITY;
+                        }
+                        break;
+                    }
+                }
+                Token::End => depth += 1,
+                _ => {}
+            }
+        }
+        self.scan_end();
+    }
+
+    fn scan_begin(&mut self, token: BeginToken) {
+        if self.scan_stack.is_empty() {
+            self.left_total = 1;
+            self.right_total = 1;
+            self.buf.data.clear();
+        }
+        let right = self.buf.push(Entry {
+            token: Token::Begin(token),
+            size: -self.right_total,
+        });
+        self.scan_stack.push_back(right);
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn scan_end(&mut self) {
+        if self.scan_stack.is_empty() {
+            self.print_end();
+            return;
+        }
+        let len = self.buf.data.len();
+        if let Some(Entry {
+            token: Token::Break(token),
+            ..
+        }) = self.buf.data.back()
+        {
+            let token = *token;
+            // an empty box leaves no trace
+            if len >= 2 {
+                if let Token::Begin(_) = self.buf.data[len - 2].token {
+                    self.buf.data.pop_back();
+                    self.buf.data.pop_back();
+                    self.scan_stack.pop_back();
+                    self.scan_stack.pop_back();
+                    self.right_total -= token.blank_space as isize;
+                    return;
+                }
+            }
+            if token.if_nonempty {
+                self.buf.data.pop_back();
+                self.scan_stack.pop_back();
+                self.right_total -= token.blank_space as isize;
+            }
+        }
+        let right = self.buf.push(Entry {
+            token: Token::End,
+            size: -1,
+        });
+        self.scan_stack.push_back(right);
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn scan_break(&mut self, mut token: BreakToken) {
+        if self.compact > 0 {
+            // hard breaks stand for a space on one line
+            token.never_break = true;
+            token.blank_space = token.blank_space.min(1);
+        }
+        if self.scan_stack.is_empty() {
+            self.left_total = 1;
+            self.right_total = 1;
+            self.buf.data.clear();
+        } else {
+            self.check_stack(0);
+        }
+        let right = self.buf.push(Entry {
+            token: Token::Break(token),
+            size: -self.right_total,
+        });
+        self.scan_stack.push_back(right);
+        self.right_total += token.blank_space as isize;
+    }
+
+    fn scan_text(&mut self, token: Token<'a>, size: isize) {
+        if self.scan_stack.is_empty() {
+            self.print_text(token);
+        } else {
+            self.buf.push(Entry { token, size });
+            self.right_total += size;
+            self.check_stream();
+        }
+    }
+
+    fn check_stream(&mut self) {
+        while self.right_total - self.left_total > self.space {
+            if *self.scan_stack.front().unwrap() == self.buf.offset {
+                self.scan_stack.pop_front().unwrap();
+                self.buf.data[0].size = SIZE_INFINITY;
+            }
+
+            self.advance_left();
+
+            if self.buf.data.is_empty() {
+                break;
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn advance_left(&mut self) {
+        while self.buf.data[0].size >= 0 {
+            let left = self.buf.pop_first();
+
+            match left.token {
+                Token::Break(token) => {
+                    self.left_total += token.blank_space as isize;
+                    self.print_break(token, left.size);
+                }
+                Token::Begin(token) => self.print_begin(token, left.size),
+                Token::End => self.print_end(),
+                token => {
+                    self.left_total += left.size;
+                    self.print_text(token);
+                }
+            }
+
+            if self.buf.data.is_empty() {
+                break;
+            }
+        }
+    }
+
+    fn check_stack(&mut self, mut depth: usize) {
+        while let Some(&index) = self.scan_stack.back() {
+            let right_total = self.right_total;
+            let entry = self.buf.get(index);
+            match entry.token {
+                Token::Begin(_) => {
+                    if depth == 0 {
+                        break;
+                    }
+                    self.scan_stack.pop_back().unwrap();
+                    entry.size += right_total;
+                    depth -= 1;
+                }
+                Token::End => {
+                    self.scan_stack.pop_back().unwrap();
+                    entry.size = 1;
+                    depth += 1;
+                }
+                Token::Break(_) => {
+                    self.scan_stack.pop_back().unwrap();
+                    entry.size += right_total;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                Token::Text(_) | Token::Reserved(_) | Token::Mark(_) => unreachable!(),
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    fn print_begin(&mut self, token: BeginToken, size: isize) {
+        if size > self.space {
+            self.print_stack
+                .push(Frame::Broken(self.indent, token.breaks));
+            self.indent = (self.indent as isize + token.offset) as usize;
+        } else {
+            self.print_stack.push(Frame::Fits);
+        }
+    }
+
+    fn print_end(&mut self) {
+        if let Some(Frame::Broken(indent, _)) = self.print_stack.pop() {
+            self.indent = indent;
+        }
+    }
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    fn print_break(&mut self, token: BreakToken, size: isize) {
+        let top = self
+            .print_stack
+            .last()
+            .copied()
+            .unwrap_or(Frame::Broken(0, Breaks::Inconsistent));
+        let fits = token.never_break
+            || match top {
+                Frame::Fits => true,
+                Frame::Broken(.., Breaks::Consistent) => false,
+                Frame::Broken(.., Breaks::Inconsistent) => size <= self.space,
+            };
+        if fits {
+            self.pending_indentation += token.blank_space;
+            self.space -= token.blank_space as isize;
+        } else {
+            if let Some(pre_break) = token.pre_break {
+                self.print_indent();
+                self.out.push(pre_break);
+            }
+            self.out.push('\n');
+            let indent = self.indent as isize + token.offset;
+            self.pending_indentation = indent as usize;
+            self.space = std::cmp::max(MARGIN - indent, MIN_SPACE);
+        }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn print_text(&mut self, token: Token<'a>) {
+        match token {
+            Token::Text(text) => {
+                self.print_indent();
+                self.out.push_str(&text);
+                self.space -= text.len() as isize;
+            }
+            Token::Reserved(width) => self.space -= width,
+            Token::Mark(text) => {
+                self.print_indent();
+                self.out.push_str(text);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn print_indent(&mut self) {
+        for _ in 0..self.pending_indentation {
+            self.out.push(' ');
+        }
+        self.pending_indentation = 0;
+    }
+}
@@ -0,0 +1,73 @@
+use super::{layout, Printer, Token, LEAD};
+use crate::node::{Children, Node, NodeId};
+
+/// The guides ahead of a child and ahead of the lines below it, for every
+/// child but the last, then for the last.
+const GUIDES: [(&str, &str); 2] = [("├── ", "│   "), ("└── ", "    ")];
+
+/// Columns taken up by a guide.
+const GUIDE: isize = 4;
+
+impl<'a> Printer<'a> {
+    /// Writes the label, with every child on lines of its own below it.
+    pub(super) fn branch(&mut self, label: &'a str, children: Children) {
+        self.word(label);
+        self.twigs(children, &mut Vec::new());
+    }
+
+    /// Writes the children of a branch, each line of them starting with
+    /// `guides`, one for every branch they are nested in.
+    fn twigs(&mut self, children: Children, guides: &mut Vec<&'static str>) {
+        let tree = self.tree;
+        let len = tree.children(children).len();
+        for (i, child) in tree.children(children).enumerate() {
+            let (first, rest) = GUIDES[usize::from(i + 1 == len)];
+            self.hardbreak();
+            self.guides(guides);
+            self.guide(first);
+            guides.push(rest);
+            self.twig(child, guides);
+            guides.pop();
+        }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn twig(&mut self, id: NodeId, guides: &mut Vec<&'static str>) {
+        let tree = self.tree;
+        if let Node::Branch(label, children) = tree.get(id) {
+            let mark = self.mark.filter(|mark| mark.id == id);
+            if let Some(mark) = mark {
+                self.marker(mark.start);
+            }
+            self.word(tree.str(label));
+            self.twigs(children, guides);
+            if let Some(mark) = mark {
+                self.marker(mark.end);
+            }
+            return;
+        }
+        // laid out on its own, so that the guides can go ahead of every
+        // one of its lines
+        let mut p = Printer::new(tree);
+        p.mark = self.mark;
+        let text = layout(p, id, LEAD + GUIDE * guides.len() as isize);
+        for (i, line) in text.lines().enumerate() {
+            if i > 0 {
+                self.hardbreak();
+                self.guides(guides);
+            }
+            self.word(line.to_owned());
+        }
+    }
+
+    fn guides(&mut self, guides: &[&'static str]) {
+        for guide in guides {
+            self.guide(guide);
+        }
+    }
+
+    fn guide(&mut self, guide: &'static str) {
+        // a box-drawing character takes up one column, but three bytes
+        self.scan_text(Token::Text(guide.into()), GUIDE);
+    }
+}
@@ -0,0 +1,303 @@
+use super::{Printer, INDENT};
+use crate::node::{Children, Hint, Node, NodeId, Range};
+
+impl<'a> Printer<'a> {
+    pub(super) fn node(&mut self, id: NodeId) {
+        self.node_as(id, self.tree.hint(id));
+    }
+
+    /// Lays out the node, broken over lines as `hint` asks for.
+    fn node_as(&mut self, id: NodeId, hint: Hint) {
+        let tree = self.tree;
+        let mark = self.mark.filter(|mark| mark.id == id);
+        if let Some(mark) = mark {
+            self.marker(mark.start);
+        }
+        let expanded = hint == Hint::Expanded;
+        if hint == Hint::Compact {
+            self.compact += 1;
+        }
+        let trailing_commas = tree
+            .trailing_comma(id)
+            .unwrap_or(!tree.style.no_trailing_commas);
+        let outer_commas = std::mem::replace(&mut self.trailing_commas, trailing_commas);
+        match tree.get(id) {
+            Node::Lit(text) | Node::Path(text) | Node::Verbatim(text) => self.word(tree.str(text)),
+            Node::Elided => self.word(".."),
+            Node::More(more) => self.word(super::more(more)),
+            Node::Struct(name, fields) => self.struct_(tree.str(name), fields, expanded),
+            Node::TupleStruct(name, fields) => {
+                self.word(tree.str(name));
+                self.word("(");
+                match only(tree.children(fields)) {
+                    // a constructor of a map or set is expanded with it
+                    Some(field) if is_blocklike(tree.get(field)) => {
+                        let hint = if expanded { hint } else { tree.hint(field) };
+                        self.node_as(field, hint);
+                    }
+                    _ => {
+                        self.cbox(INDENT);
+                        self.zerobreak();
+                        self.delimited(fields);
+                        self.offset(-INDENT);
+                        self.expand_if(expanded, fields);
+                        self.end();
+                    }
+                }
+                self.word(")");
+            }
+            Node::Tuple(elems) => {
+                self.word("(");
+                self.cbox(INDENT);
+                self.zerobreak();
+                if let Some(elem) = only(tree.children(elems)) {
+                    self.node(elem);
+                    self.word(",");
+                    self.zerobreak();
+                } else {
+                    self.delimited(elems);
+                }
+                self.offset(-INDENT);
+                self.end();
+                self.word(")");
+            }
+            Node::List(elems) => self.list(elems, expanded),
+            Node::Vec(elems) => {
+                self.word("vec!");
+                self.list(elems, expanded);
+            }
+            Node::Concat(lines) => {
+                // every line on a line of its own, however short
+                self.word("concat!(");
+                self.cbox(INDENT);
+                self.zerobreak();
+                let len = tree.children(lines).len();
+                for (i, line) in tree.children(lines).enumerate() {
+                    self.node(line);
+                    if i + 1 < len || self.trailing_commas {
+                        self.word(",");
+                    }
+                    self.hardbreak();
+                }
+                self.offset(-INDENT);
+                self.end();
+                self.word(")");
+            }
+            Node::Table(rows) => self.table(rows),
+            Node::Branch(label, children) => self.branch(tree.str(label), children),
+            Node::Set(entries) => self.set(entries, expanded),
+            Node::Map(entries) => self.map(entries),
+            Node::Range(Range {
+                start,
+                end,
+                inclusive,
+            }) => {
+                if let Some(start) = start {
+                    self.bound(start);
+                }
+                self.word(if inclusive { "..=" } else { ".." });
+                if let Some(end) = end {
+                    self.bound(end);
+                }
+            }
+        }
+        if hint == Hint::Compact {
+            self.compact -= 1;
+        }
+        self.trailing_commas = outer_commas;
+        if let Some(mark) = mark {
+            self.marker(mark.end);
+        }
+    }
+
+    /// Forces the current box to break if the node is expanded and has
+    /// children to break between.
+    fn expand_if(&mut self, expanded: bool, children: Children) {
+        if expanded && self.tree.children(children).len() > 0 {
+            self.force_break();
+        }
+    }
+
+    /// Ranges do not chain, so a range within a range is parenthesized.
+    fn bound(&mut self, bound: NodeId) {
+        if let Node::Range(_) = self.tree.get(bound) {
+            self.word("(");
+            self.node(bound);
+            self.word(")");
+        } else {
+            self.node(bound);
+        }
+    }
+
+    fn struct_(&mut self, name: &'a str, fields: Children, expanded: bool) {
+        let tree = self.tree;
+        self.cbox(INDENT);
+        self.ibox(-INDENT);
+        self.word(name);
+        self.end();
+        self.word(" {");
+        self.space_if_nonempty();
+        let len = tree.children(fields).len();
+        for (i, field) in tree.children(fields).enumerate() {
+            if tree.get(field) == Node::Elided {
+                // the rest of the fields, as in `Foo { bar: 1, .. }`
+                self.word("..");
+                self.space();
+                break;
+            }
+            self.word(tree.label(field));
+            self.word(": ");
+            self.ibox(0);
+            self.node(field);
+            self.end();
+            self.separator(field, i + 1 == len, true);
+        }
+        self.offset(-INDENT);
+        self.expand_if(expanded, fields);
+        self.end_with_max_width(34);
+        self.word("}");
+    }
+
+    pub(super) fn list(&mut self, elems: Children, expanded: bool) {
+        self.word("[");
+        self.cbox(INDENT);
+        self.zerobreak();
+        self.delimited(elems);
+        self.offset(-INDENT);
+        self.expand_if(expanded, elems);
+        self.end();
+        self.word("]");
+    }
+
+    fn set(&mut self, entries: Children, expanded: bool) {
+        let tree = self.tree;
+        self.cbox(INDENT);
+        self.word("{");
+        let mut entries = tree.children(entries);
+        match entries.len() {
+            0 => {}
+            1 => {
+                self.space();
+                self.ibox(0);
+                self.node(entries.next().unwrap());
+                self.end();
+                self.space();
+                self.offset(-INDENT);
+                if expanded {
+                    self.force_break();
+                }
+            }
+            len => {
+                self.space();
+                for (i, entry) in entries.enumerate() {
+                    self.ibox(0);
+                    self.node(entry);
+                    if i + 1 < len {
+                        self.word(";");
+                    }
+                    self.end();
+                    self.hardbreak();
+                }
+                self.offset(-INDENT);
+            }
+        }
+        self.word("}");
+        self.end();
+    }
+
+    fn map(&mut self, entries: Children) {
+        let tree = self.tree;
+        self.cbox(INDENT);
+        self.word("{");
+        let mut entries = tree.children(entries);
+        let len = entries.len();
+        if len > 0 {
+            self.space();
+            while let Some(key) = entries.next() {
+                let Some(value) = entries.next() else {
+                    // the rest of the entries were left out, which leaves
+                    // a lone `..` as the key
+                    self.ibox(0);
+                    self.node(key);
+                    self.end();
+                    if len == 1 {
+                        self.space();
+                    } else {
+                        self.hardbreak();
+                    }
+                    break;
+                };
+                self.ibox(0);
+                self.ibox(0);
+                self.word("[");
+                self.cbox(INDENT);
+                self.zerobreak();
+                self.node(key);
+                self.trailing_comma(true);
+                self.offset(-INDENT);
+                self.end();
+                self.word("]");
+                self.word(" = ");
+                self.node(value);
+                self.end();
+                self.word(";");
+                if let Some(comment) = tree.comment(value) {
+                    self.comment(comment);
+                }
+                self.end();
+                self.hardbreak();
+            }
+            self.offset(-INDENT);
+        }
+        self.word("}");
+        self.end();
+    }
+
+    fn delimited(&mut self, nodes: Children) {
+        let tree = self.tree;
+        let len = tree.children(nodes).len();
+        for (i, node) in tree.children(nodes).enumerate() {
+            self.node(node);
+            self.separator(node, i + 1 == len, false);
+        }
+    }
+
+    /// The comma after a field or element, which is followed by its
+    /// comment, if it has one, and then by a line break.
+    fn separator(&mut self, child: NodeId, is_last: bool, space: bool) {
+        match self.tree.comment(child) {
+            Some(comment) => {
+                if !is_last || self.trailing_commas {
+                    self.word(",");
+                }
+                self.comment(comment);
+                self.hardbreak();
+            }
+            None if space => self.trailing_comma_or_space(is_last),
+            None => self.trailing_comma(is_last),
+        }
+    }
+}
+
+fn only(mut children: impl Iterator<Item = NodeId>) -> Option<NodeId> {
+    match (children.next(), children.next()) {
+        (Some(child), None) => Some(child),
+        _ => None,
+    }
+}
+
+/// Whether the node is delimited by brackets of its own, in which case a
+/// tuple struct wrapping only this node hugs it: `Some(Foo {..})`.
+fn is_blocklike(node: Node) -> bool {
+    matches!(
+        node,
+        Node::Struct(..)
+            | Node::Tuple(_)
+            | Node::List(_)
+            | Node::Vec(_)
+            | Node::Concat(_)
+            | Node::Table(_)
+            | Node::Set(_)
+            | Node::Map(_)
+    )
+}
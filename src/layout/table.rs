@@ -0,0 +1,231 @@
+use super::{Printer, INDENT, WIDTH};
+use crate::node::{Children, Node, NodeId, Range, Tree};
+
+/// The rows of a table, each written out on one line with its fields
+/// padded to the width of their column.
+struct Table {
+    rows: Vec<String>,
+    /// The rest of the rows were elided
+    elided: bool,
+}
+
+impl Printer<'_> {
+    /// Lays out the rows in aligned columns, or as a list if they are not
+    /// structs of the same shape that each fit on a line.
+    pub(super) fn table(&mut self, rows: Children) {
+        // the highlighted node could be hidden within a row
+        let table = match self.mark {
+            Some(_) => None,
+            None => table(self.tree, rows),
+        };
+        let Some(table) = table else {
+            return self.list(rows, false);
+        };
+        self.word("[");
+        self.cbox(INDENT);
+        self.zerobreak();
+        let len = table.rows.len();
+        for (i, row) in table.rows.into_iter().enumerate() {
+            self.word(row);
+            if i + 1 < len || table.elided || self.trailing_commas {
+                self.word(",");
+            }
+            self.hardbreak();
+        }
+        if table.elided {
+            self.word("..");
+            if self.trailing_commas {
+                self.word(",");
+            }
+            self.hardbreak();
+        }
+        self.offset(-INDENT);
+        self.end();
+        self.word("]");
+    }
+}
+
+fn table(tree: &Tree, rows: Children) -> Option<Table> {
+    let mut name = None;
+    let mut labels = Vec::new();
+    let mut cells = Vec::new();
+    let mut elided = false;
+    for row in tree.children(rows) {
+        if tree.comment(row).is_some() {
+            return None;
+        }
+        let Node::Struct(row_name, fields) = tree.get(row) else {
+            // only the last row can stand for the rest
+            elided = tree.get(row) == Node::Elided;
+            break;
+        };
+        let row_name = tree.str(row_name);
+        if *name.get_or_insert(row_name) != row_name {
+            return None;
+        }
+        let fields: Vec<_> = tree.children(fields).collect();
+        if cells.is_empty() {
+            labels = fields.iter().map(|&field| tree.label(field)).collect();
+        } else if !fields
+            .iter()
+            .map(|&field| tree.label(field))
+            .eq(labels.iter().copied())
+        {
+            return None;
+        }
+        let mut row = Vec::with_capacity(fields.len());
+        for field in fields {
+            if tree.get(field) == Node::Elided {
+                return None;
+            }
+            let mut cell = String::new();
+            flat(tree, field, &mut cell)?;
+            row.push(cell);
+        }
+        cells.push(row);
+    }
+    if cells.len() + usize::from(elided) != tree.children(rows).len() || labels.is_empty() {
+        return None;
+    }
+
+    let mut widths: Vec<_> = labels.iter().map(|_| 0).collect();
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = cell.chars().count().max(*width);
+        }
+    }
+    let name = name?;
+    let rows = cells
+        .iter()
+        .map(|row| {
+            let mut line = format!("{name} {{ ");
+            for (i, cell) in row.iter().enumerate() {
+                line.push_str(labels[i]);
+                line.push_str(": ");
+                line.push_str(cell);
+                let pad = widths[i] - cell.chars().count();
+                if i + 1 < row.len() {
+                    line.push(',');
+                    line.extend(std::iter::repeat_n(' ', pad + 1));
+                } else {
+                    line.extend(std::iter::repeat_n(' ', pad));
+                    line.push_str(" }");
+                }
+            }
+            line
+        })
+        .collect::<Vec<_>>();
+    // a row and its comma, within the indentation of the list
+    let fits = |row: &String| row.chars().count() < WIDTH - INDENT as usize;
+    rows.iter().all(fits).then_some(Table { rows, elided })
+}
+
+/// Writes the node as the layout writes it when it fits on one line, or
+/// returns `None` for a node that is always broken over several lines.
+fn flat(tree: &Tree, id: NodeId, out: &mut String) -> Option<()> {
+    // a comment runs to the end of the line
+    if tree.comment(id).is_some() {
+        return None;
+    }
+    let list = |out: &mut String, elems: Children| {
+        for (i, elem) in tree.children(elems).enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            flat(tree, elem, out)?;
+        }
+        Some(())
+    };
+    match tree.get(id) {
+        Node::Lit(text) | Node::Path(text) | Node::Verbatim(text) => {
+            let text = tree.str(text);
+            if text.contains('\n') {
+                return None;
+            }
+            out.push_str(text);
+        }
+        Node::Elided => out.push_str(".."),
+        Node::More(more) => out.push_str(&super::more(more)),
+        Node::Branch(label, children) if tree.children(children).len() == 0 => {
+            out.push_str(tree.str(label));
+        }
+        Node::Branch(..) | Node::Concat(_) => return None,
+        Node::Struct(name, fields) => {
+            out.push_str(tree.str(name));
+            if tree.children(fields).len() == 0 {
+                out.push_str(" {}");
+                return Some(());
+            }
+            out.push_str(" { ");
+            for (i, field) in tree.children(fields).enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                if tree.get(field) == Node::Elided {
+                    out.push_str("..");
+                    break;
+                }
+                out.push_str(tree.label(field));
+                out.push_str(": ");
+                flat(tree, field, out)?;
+            }
+            out.push_str(" }");
+        }
+        Node::TupleStruct(name, fields) => {
+            out.push_str(tree.str(name));
+            out.push('(');
+            list(out, fields)?;
+            out.push(')');
+        }
+        Node::Tuple(elems) => {
+            out.push('(');
+            list(out, elems)?;
+            if tree.children(elems).len() == 1 {
+                out.push(',');
+            }
+            out.push(')');
+        }
+        Node::List(elems) | Node::Vec(elems) | Node::Table(elems) => {
+            if let Node::Vec(_) = tree.get(id) {
+                out.push_str("vec!");
+            }
+            out.push('[');
+            list(out, elems)?;
+            out.push(']');
+        }
+        Node::Set(entries) | Node::Map(entries) => match tree.children(entries).len() {
+            0 => out.push_str("{}"),
+            // a set of one, or a map whose entries were all left out
+            1 => {
+                out.push_str("{ ");
+                flat(tree, tree.children(entries).next()?, out)?;
+                out.push_str(" }");
+            }
+            _ => return None,
+        },
+        Node::Range(range) => flat_range(tree, range, out)?,
+    }
+    Some(())
+}
+
+fn flat_range(tree: &Tree, range: Range, out: &mut String) -> Option<()> {
+    let bound = |out: &mut String, bound: NodeId| {
+        let nested = matches!(tree.get(bound), Node::Range(_));
+        if nested {
+            out.push('(');
+        }
+        flat(tree, bound, out)?;
+        if nested {
+            out.push(')');
+        }
+        Some(())
+    };
+    if let Some(start) = range.start {
+        bound(out, start)?;
+    }
+    out.push_str(if range.inclusive { "..=" } else { ".." });
+    if let Some(end) = range.end {
+        bound(out, end)?;
+    }
+    Some(())
+}
@@ -3,7 +3,7 @@
 #![forbid(unsafe_code)]
 //! Syntax aware debug printing.
 //!
-//! Makes use of `syn` and `prettyplease` in order to provide the most
+//! Makes use of `syn` and a built-in layout engine in order to provide the most
 //! canonincal rust debug lines as possible, quickly.
 //!
 //! # Example usage
@@ -162,37 +162,96 @@
 //!
 //! # How it works
 //!
-//! All [`DebugPls`] implementations are forced to output only valid
-//! [`syn::Expr`] values. These are then formatted using [`prettyplease::unparse`].
+//! All [`DebugPls`] implementations are forced to output only valid rust
+//! expressions, built up through the [`Formatter`] builders (or any
+//! [`syn::Expr`] via [`Formatter::write_expr`]) into a compact tree.
+//! This tree is then laid out by a small built-in layout engine
+//! that makes the same line breaking decisions as `prettyplease`
+//! (the `syn-backend` feature switches to `prettyplease::unparse` itself).
 //! Finally, it uses [`syntect`] to provide syntax highlighting, with theme provided by
 //! <https://github.com/jonschlinkert/sublime-monokai-extended>
+//!
+//! # Output stability
+//!
+//! The built-in layout engine is part of this crate, so its output does not
+//! change with the versions of any dependency. Layout changes are only made in
+//! releases that bump the minor version, and the golden tests in
+//! `tests/layout.rs` pin the current output. This makes it safe to snapshot.
+//!
+//! The `syn-backend` feature gives no such guarantee: its output is whatever
+//! the `prettyplease` version in use produces.
 
-use syn::__private::{Span, TokenStream2};
-
+mod hexdump;
+mod humanize;
 mod impls;
+// only read back by the `pretty` printers
+#[cfg_attr(not(feature = "pretty"), allow(dead_code))]
+mod node;
 
+use node::{Node, NodeId, Tree};
+
+mod debug_enum;
+mod debug_iter;
 mod debug_list;
 mod debug_map;
 mod debug_set;
 mod debug_struct;
+mod debug_table;
+mod debug_tree;
 mod debug_tuple;
 mod debug_tuple_struct;
+mod opaque;
+mod with_fn;
+pub use debug_enum::DebugEnum;
+pub use debug_iter::{debug_iter, DebugIter};
 pub use debug_list::DebugList;
 pub use debug_map::DebugMap;
 pub use debug_set::DebugSet;
 pub use debug_struct::DebugStruct;
+pub use debug_table::DebugTable;
+pub use debug_tree::DebugTree;
 pub use debug_tuple::DebugTuple;
 pub use debug_tuple_struct::DebugTupleStruct;
+pub use with_fn::{from_fn, FromFn};
 
+#[cfg(feature = "pretty")]
+mod layout;
 #[cfg(feature = "pretty")]
 mod pretty;
 #[cfg(feature = "pretty")]
-pub use pretty::pretty;
+pub use pretty::{pretty, Budget, Captured, Pretty, Rendered};
+#[cfg(feature = "pretty")]
+mod deferred;
+#[cfg(feature = "pretty")]
+pub use deferred::flush;
+#[cfg(feature = "pretty")]
+mod output;
+#[cfg(feature = "pretty")]
+pub use output::{lock_output, set_sink, Capture, Dump, Sink, Stderr};
+#[cfg(feature = "pretty")]
+mod markdown;
+#[cfg(feature = "pretty")]
+pub use markdown::{markdown, Markdown};
+#[cfg(feature = "pretty")]
+mod comparison;
+#[cfg(feature = "pretty")]
+pub use comparison::Comparison;
+#[cfg(feature = "pretty")]
+mod matches;
+
+#[cfg(feature = "yaml")]
+mod yaml;
+#[cfg(feature = "yaml")]
+pub use yaml::to_yaml;
 
 #[cfg(feature = "colors")]
 mod colors;
 #[cfg(feature = "colors")]
-pub use colors::color;
+pub use colors::{color, Color};
+
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub mod derive_args;
 
 #[cfg(feature = "derive")]
 #[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
@@ -215,14 +274,53 @@ pub use colors::color;
 ///     "The origin is: Point { x: 0, y: 0 }",
 /// );
 /// ```
+///
+/// It takes arguments in `#[dbg_pls(...)]`, listed in [`derive_args`].
 pub use dbg_pls_derive::DebugPls;
 
+/// Implementation detail for the `std_debug` and `impl_display` derive args,
+/// whose impls are only written with the `pretty` feature.
+#[cfg(feature = "pretty")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pretty_only {
+    ($arg:literal, $($item:item)*) => {
+        $($item)*
+    };
+}
+
+/// Implementation detail for the `std_debug` and `impl_display` derive args,
+/// whose impls are only written with the `pretty` feature.
+#[cfg(not(feature = "pretty"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pretty_only {
+    ($arg:literal, $($item:item)*) => {
+        ::core::compile_error!(::core::concat!(
+            "`",
+            $arg,
+            "` requires the `pretty` feature of dbg-pls"
+        ));
+    };
+}
+
 #[doc(hidden)]
 pub mod __private {
+    pub use crate::__pretty_only as pretty_only;
     #[cfg(feature = "colors")]
     pub use crate::colors::ColorStr;
     #[cfg(feature = "pretty")]
-    pub use crate::pretty::Str as PrettyStr;
+    pub use crate::deferred::print_later;
+    #[cfg(feature = "pretty")]
+    pub use crate::matches::Mismatch;
+    pub use crate::opaque::{
+        generic_name, AsDebug, AsDisplay, Binary, Bytes, FormatWith, Hex, LenOnly, LenOnlyAbove,
+        Opaque, RawStrings, Truncated, VecMacro,
+    };
+    #[cfg(feature = "pretty")]
+    pub use crate::output::print;
+    #[cfg(feature = "pretty")]
+    pub use crate::pretty::{std_debug, Str as PrettyStr};
 }
 
 /// Syntax aware pretty-printed debug formatting.
@@ -299,22 +397,368 @@ pub trait DebugPls {
 }
 
 /// Tool for formatting, used within [`DebugPls`] implementations
-#[repr(transparent)]
 pub struct Formatter<'a> {
-    expr: &'a mut syn::Expr,
+    tree: &'a mut Tree,
+    id: NodeId,
 }
 
 impl<'a> Formatter<'a> {
-    pub(crate) fn process(value: &dyn DebugPls) -> syn::Expr {
-        let mut expr = syn::Expr::Verbatim(TokenStream2::new());
-        value.fmt(Formatter { expr: &mut expr });
-        expr
+    pub(crate) fn write(self, node: Node) {
+        self.tree.set(self.id, node);
+    }
+
+    pub(crate) fn write_lit(self, lit: &str) {
+        let text = self.tree.text(lit);
+        self.write(Node::Lit(text));
+    }
+
+    /// Writes a number literal, with its type as a suffix if asked to.
+    pub(crate) fn write_number(self, lit: &str, ty: &str) {
+        let suffix = if self.tree.style.suffixes { ty } else { "" };
+        self.write_lit_with(|buf| {
+            buf.push_str(lit);
+            buf.push_str(suffix);
+        });
+    }
+
+    /// Writes an integer literal of `digits` after a radix `prefix`, with
+    /// the digits in groups of `group` if asked to, and its type as a
+    /// suffix if asked to.
+    pub(crate) fn write_int(self, prefix: &str, digits: &str, group: usize, ty: &str) {
+        let style = self.tree.style;
+        let suffix = if style.suffixes { ty } else { "" };
+        self.write_lit_with(|buf| {
+            let (sign, digits) = match digits.strip_prefix('-') {
+                Some(digits) => ("-", digits),
+                None => ("", digits),
+            };
+            buf.push_str(sign);
+            buf.push_str(prefix);
+            for (i, digit) in digits.chars().enumerate() {
+                if style.digit_separators && i > 0 && (digits.len() - i) % group == 0 {
+                    buf.push('_');
+                }
+                buf.push(digit);
+            }
+            buf.push_str(suffix);
+        });
+    }
+
+    /// Writes an integer literal in the radix of the style, given its sign
+    /// and its magnitude.
+    pub(crate) fn write_int_in(self, negative: bool, magnitude: u128, ty: &str) {
+        let sign = if negative { "-" } else { "" };
+        match self.tree.style.radix {
+            node::Radix::Decimal => self.write_int("", &format!("{sign}{magnitude}"), 3, ty),
+            node::Radix::Hex => self.write_int("0x", &format!("{sign}{magnitude:X}"), 4, ty),
+            node::Radix::Binary => self.write_int("0b", &format!("{sign}{magnitude:b}"), 4, ty),
+        }
+    }
+
+    /// Writes a finite float literal, rounded to `digits` digits after the
+    /// point if given, as the shortest literal of the rounded value.
+    pub(crate) fn write_float<T>(self, value: T, digits: Option<usize>, ty: &str)
+    where
+        T: ryu::Float + std::fmt::Display + std::str::FromStr,
+    {
+        let value = match digits {
+            Some(digits) => format!("{value:.digits$}").parse().unwrap_or(value),
+            None => value,
+        };
+        self.write_number(ryu::Buffer::new().format_finite(value), ty);
+    }
+
+    pub(crate) fn write_lit_with(self, write: impl FnOnce(&mut String)) {
+        let text = self.tree.text_with(write);
+        self.write(Node::Lit(text));
+    }
+
+    pub(crate) fn write_verbatim_with(self, write: impl FnOnce(&mut String)) {
+        let text = self.tree.text_with(write);
+        self.write(Node::Verbatim(text));
+    }
+
+    /// Writes a list of the elements, unless there are more than
+    /// [`node::MAX_LEN`] of them, in which case only their number is
+    /// written, `[..; 10000000]`.
+    pub(crate) fn write_list<I>(self, elems: I)
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: DebugPls,
+    {
+        self.write_list_as(elems, false);
+    }
+
+    /// Writes `value` in the style that `style` makes of the current one.
+    pub(crate) fn write_styled(
+        self,
+        style: impl FnOnce(node::Style) -> node::Style,
+        value: &dyn DebugPls,
+    ) {
+        let Formatter { tree, id } = self;
+        let outer = tree.style;
+        tree.style = style(outer);
+        value.fmt(Formatter {
+            tree: &mut *tree,
+            id,
+        });
+        tree.style = outer;
+    }
+
+    /// Writes `value` with the entries of its collection, or the chars of
+    /// its string, cut short to `max`.
+    pub(crate) fn write_truncated(self, max: usize, value: &dyn DebugPls) {
+        let Formatter { tree, id } = self;
+        let outer = tree.truncation.replace((id, max));
+        value.fmt(Formatter {
+            tree: &mut *tree,
+            id,
+        });
+        tree.truncation = outer;
+    }
+
+    /// Writes a `Vec`, `VecDeque` or slice, as a `vec!` if asked to.
+    pub(crate) fn write_vec<I>(self, elems: I)
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: DebugPls,
+    {
+        let vec = self.tree.style.vec_macros;
+        self.write_list_as(elems, vec);
+    }
+
+    fn write_list_as<I>(self, elems: I, vec: bool)
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: DebugPls,
+    {
+        let elems = elems.into_iter();
+        // a truncated list only looks at the elements it keeps
+        if elems.len() <= node::MAX_LEN || self.tree.truncation(self.id).max.is_some() {
+            let list = self.debug_list();
+            let list = if vec { list.vec_macro() } else { list };
+            return list.entries(elems).finish();
+        }
+        self.write_verbatim_with(|buf| {
+            if vec {
+                buf.push_str("vec!");
+            }
+            buf.push_str("[..; ");
+            buf.push_str(itoa::Buffer::new().format(elems.len()));
+            buf.push(']');
+        });
+    }
+
+    pub(crate) fn write_range(
+        self,
+        start: Option<&dyn DebugPls>,
+        end: Option<&dyn DebugPls>,
+        inclusive: bool,
+    ) {
+        let start = start.map(|start| self.tree.process(start));
+        let end = end.map(|end| self.tree.process(end));
+        let elided = |bound| matches!(bound, Some(id) if self.tree.get(id) == Node::Elided);
+        if elided(start) || elided(end) {
+            return self.write(Node::Elided);
+        }
+        self.write(Node::Range(node::Range {
+            start,
+            end,
+            inclusive,
+        }));
+    }
+
+    /// Formats the value behind a shared pointer, unless that value is
+    /// already being formatted further up, as then formatting would never end.
+    pub(crate) fn write_shared<T: ?Sized + DebugPls>(self, ptr: *const T, value: &T) {
+        let Formatter { tree, id } = self;
+        let addr = ptr.cast::<()>() as usize;
+        if tree.shared.contains(&addr) {
+            return Formatter { tree, id }.write_lit("\"<cycle>\"");
+        }
+        tree.shared.push(addr);
+        value.fmt(Formatter { tree, id });
+        tree.shared.pop();
+    }
+
+    /// Borrows the formatter for a while, such as for a helper that writes
+    /// the value only in some cases. Whatever is written last is kept, so
+    /// the formatter can still be used once the helper returns without
+    /// writing, or to write something else in its place.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// /// Writes the name as is if it is a plain identifier.
+    /// fn ident(name: &str, f: Formatter<'_>) -> bool {
+    ///     let plain = name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    ///     if plain {
+    ///         f.debug_ident(name);
+    ///     }
+    ///     plain
+    /// }
+    ///
+    /// struct Name(&'static str);
+    ///
+    /// impl DebugPls for Name {
+    ///     fn fmt(&self, mut f: Formatter<'_>) {
+    ///         if ident(self.0, f.reborrow()) {
+    ///             return;
+    ///         }
+    ///         f.debug_tuple_struct("Name").field(&self.0).finish();
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{}", pretty(&[Name("id"), Name("full name")])),
+    ///     "[id, Name(\"full name\")]",
+    /// );
+    /// ```
+    pub fn reborrow(&mut self) -> Formatter<'_> {
+        Formatter {
+            tree: &mut *self.tree,
+            id: self.id,
+        }
+    }
+
+    /// Formats the value with `fmt`, which can fail, such as when a lock
+    /// is poisoned or a lazily loaded field can't be read. A failure is
+    /// written as a placeholder in its place, `"<error: ..>"`, and the rest
+    /// of the output is still written.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::cell::RefCell;
+    ///
+    /// struct Shared(RefCell<Vec<u8>>);
+    ///
+    /// impl DebugPls for Shared {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.try_fmt(|f| {
+    ///             let bytes = self.0.try_borrow().map_err(|_| "being written to")?;
+    ///             f.debug_tuple_struct("Shared").field(&*bytes).finish();
+    ///             Ok::<_, &str>(())
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let shared = Shared(RefCell::new(vec![1, 2]));
+    /// assert_eq!(format!("{}", pretty(&shared)), "Shared([1, 2])");
+    ///
+    /// let _writer = shared.0.borrow_mut();
+    /// assert_eq!(
+    ///     format!("{}", pretty(&(1, &shared))),
+    ///     "(1, \"<error: being written to>\")",
+    /// );
+    /// ```
+    ///
+    /// The placeholder is a string, which keeps the output valid Rust.
+    pub fn try_fmt<E: std::fmt::Display>(self, fmt: impl FnOnce(Formatter<'_>) -> Result<(), E>) {
+        let Formatter { tree, id } = self;
+        if let Err(err) = fmt(Formatter {
+            tree: &mut *tree,
+            id,
+        }) {
+            // in place of whatever was written before the failure
+            format!("<error: {err}>")
+                .as_str()
+                .fmt(Formatter { tree, id });
+        }
+    }
+
+    /// Writes the [`Display`](std::fmt::Display) form of the value as a
+    /// string literal, for types from other crates that have no
+    /// [`DebugPls`] impl.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::net::{Ipv4Addr, SocketAddr};
+    ///
+    /// struct Peer(SocketAddr);
+    ///
+    /// impl DebugPls for Peer {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_tuple_struct("Peer")
+    ///             .field_with(|f| f.write_display(&self.0))
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let peer = Peer(SocketAddr::from((Ipv4Addr::LOCALHOST, 80)));
+    /// assert_eq!(format!("{}", pretty(&peer)), r#"Peer("127.0.0.1:80")"#);
+    /// ```
+    pub fn write_display(self, value: &dyn std::fmt::Display) {
+        value.to_string().as_str().fmt(self);
+    }
+
+    /// Writes the [`Debug`](std::fmt::Debug) form of the value as it is,
+    /// for types from other crates that have no [`DebugPls`] impl. Most
+    /// `Debug` impls write Rust, but where one doesn't, the output won't be
+    /// either, and the `syn-backend` falls back to the built-in layout.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    /// use std::cmp::Ordering;
+    ///
+    /// struct Order(Ordering);
+    ///
+    /// impl DebugPls for Order {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_tuple_struct("Order")
+    ///             .field_with(|f| f.write_debug(&self.0))
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Order(Ordering::Less))), "Order(Less)");
+    /// ```
+    pub fn write_debug(self, value: &dyn std::fmt::Debug) {
+        self.write_verbatim_with(|buf| {
+            push_fmt(buf, format_args!("{value:?}"));
+        });
+    }
+
+    /// Captures `value` as an expression, for impls that write their
+    /// values with [`write_expr`](Self::write_expr) and have others to
+    /// embed in them. Parts of it that are not Rust, such as the guides of
+    /// a [`DebugTree`] or the text of [`write_debug`](Self::write_debug)
+    /// that does not lex, are written as a tuple of their parts or as a
+    /// string.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// /// A value that is shared, written as the expression that shares it
+    /// struct Shared<T>(std::rc::Rc<T>);
+    ///
+    /// impl<T: DebugPls> DebugPls for Shared<T> {
+    ///     fn fmt(&self, mut f: Formatter<'_>) {
+    ///         let value = f.value(&*self.0);
+    ///         let expr: syn::Expr = syn::parse_quote!(Rc::new(#value));
+    ///         f.write_expr(expr);
+    ///     }
+    /// }
+    ///
+    /// let shared = Shared(std::rc::Rc::new((1, "a")));
+    /// assert_eq!(format!("{}", pretty(&shared)), r#"Rc::new((1, "a"))"#);
+    /// ```
+    #[cfg(feature = "syn")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "syn")))]
+    pub fn value(&mut self, value: &dyn DebugPls) -> syn::Expr {
+        let id = self.tree.process(value);
+        self.tree.expr(id)
     }
 
     /// Writes a wrap expression into the formatter.
     /// This is typically reserved for more advanced uses
+    #[cfg(feature = "syn")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "syn")))]
     pub fn write_expr(self, expr: impl Into<syn::Expr>) {
-        *self.expr = expr.into();
+        let node = self.tree.capture_expr(&expr.into());
+        self.write(node);
     }
 
     /// Creates a [`DebugStruct`] builder designed to assist with creation of
@@ -347,6 +791,12 @@ impl<'a> Formatter<'a> {
     ///     "Foo { bar: 10, baz: \"Hello World\" }",
     /// );
     /// ```
+    ///
+    /// `name` is normally a path, such as `Foo` or `foo::Bar<T>`, but any
+    /// name is written out as given, `dyn Trait` included. Only the keywords
+    /// in it change, into raw identifiers. The same goes for the names given
+    /// to [`debug_tuple_struct`](Self::debug_tuple_struct),
+    /// [`debug_ident`](Self::debug_ident) and fields.
     #[must_use]
     pub fn debug_struct(self, name: &str) -> DebugStruct<'a> {
         DebugStruct::new(self, name)
@@ -382,6 +832,10 @@ impl<'a> Formatter<'a> {
     /// Creates a [`DebugTupleStruct`] builder designed to assist with creation of
     /// [`DebugPls`] implementations for tuple structs.
     ///
+    /// Generic arguments in `name` are written with a turbofish,
+    /// `Foo::<Bar>(1)`. Names that are not paths, such as `dyn Trait`, are
+    /// written as they are.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -406,6 +860,37 @@ impl<'a> Formatter<'a> {
         DebugTupleStruct::new(self, name)
     }
 
+    /// Creates a [`DebugEnum`] builder designed to assist with creation of
+    /// [`DebugPls`] implementations for enums, which writes `variant` with
+    /// `name`, the name of the enum, ahead of it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// enum Shape {
+    ///     Circle { radius: f64 },
+    /// }
+    ///
+    /// impl DebugPls for Shape {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         let Shape::Circle { radius } = self;
+    ///         f.debug_enum("Shape", "Circle")
+    ///             .struct_variant()
+    ///             .field("radius", radius)
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// let value = Shape::Circle { radius: 1.0 };
+    /// assert_eq!(format!("{}", pretty(&value)), "Shape::Circle { radius: 1.0 }");
+    /// ```
+    #[must_use]
+    pub fn debug_enum(self, name: &str, variant: &str) -> DebugEnum<'a> {
+        DebugEnum::new(self, name, variant)
+    }
+
     /// Creates a [`DebugList`] builder designed to assist with creation of
     /// [`DebugPls`] implementations for list-like structures.
     ///
@@ -430,6 +915,22 @@ impl<'a> Formatter<'a> {
         DebugList::new(self)
     }
 
+    /// Creates a [`DebugTable`] builder designed to assist with creation of
+    /// [`DebugPls`] implementations for lists of structs of the same shape,
+    /// which are laid out in aligned columns.
+    #[must_use]
+    pub fn debug_table(self) -> DebugTable<'a> {
+        DebugTable::new(self)
+    }
+
+    /// Creates a [`DebugTree`] builder designed to assist with creation of
+    /// [`DebugPls`] implementations for hierarchical data, which is drawn
+    /// with box-drawing guides below `label`.
+    #[must_use]
+    pub fn debug_tree(self, label: &str) -> DebugTree<'a> {
+        DebugTree::new(self, label)
+    }
+
     /// Creates a [`DebugMap`] builder designed to assist with creation of
     /// [`DebugPls`] implementations for maps.
     ///
@@ -514,11 +1015,684 @@ impl<'a> Formatter<'a> {
     /// assert_eq!(format!("{}", pretty(&Foo)), "Foo");
     /// ```
     pub fn debug_ident(self, name: &str) {
-        let path: syn::Path = syn::Ident::new(name, Span::call_site()).into();
-        self.write_expr(syn::ExprPath {
-            attrs: vec![],
-            qself: None,
-            path,
+        let text = self.tree.name(name);
+        self.write(Node::Path(text));
+    }
+
+    /// Writes the unit value, `()`, as the `()` impl does.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// /// A reply that carries nothing when it succeeds
+    /// struct Ack(Option<u16>);
+    ///
+    /// impl DebugPls for Ack {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         match self.0 {
+    ///             None => f.debug_unit(),
+    ///             Some(code) => code.fmt(f),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&[Ack(None), Ack(Some(404))])), "[(), 404]");
+    /// ```
+    pub fn debug_unit(self) {
+        self.debug_tuple().finish();
+    }
+
+    /// Writes a unit struct, `Foo`, as the derive does for a struct without
+    /// fields.
+    ///
+    /// This is the name alone, without the `Foo()` that a
+    /// [`debug_tuple_struct`](Self::debug_tuple_struct) with no fields
+    /// writes.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// struct Marker;
+    ///
+    /// impl DebugPls for Marker {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_tuple_struct_unit("Marker");
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Marker)), "Marker");
+    /// ```
+    pub fn debug_tuple_struct_unit(self, name: &str) {
+        self.debug_ident(name);
+    }
+
+    /// Writes `Some(value)`, as the `Option` impl does.
+    ///
+    /// The `Option` and `Result` helpers wrap a value that is kept in
+    /// another form, without a `debug_tuple_struct` for the variant.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// /// A port, where `0` stands for none
+    /// struct Port(u16);
+    ///
+    /// impl DebugPls for Port {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         match self.0 {
+    ///             0 => f.debug_none(),
+    ///             port => f.debug_some(&port),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&[Port(0), Port(80)])), "[None, Some(80)]");
+    /// assert_eq!(format!("{}", pretty(&[None, Some(80)])), "[None, Some(80)]");
+    /// ```
+    pub fn debug_some(self, value: &dyn DebugPls) {
+        self.debug_tuple_struct("Some").field(value).finish();
+    }
+
+    /// Writes `None`, as the `Option` impl does.
+    pub fn debug_none(self) {
+        self.debug_ident("None");
+    }
+
+    /// Writes `Ok(value)`, as the `Result` impl does.
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// /// A status code, where anything but `0` is an error
+    /// struct Status(i32);
+    ///
+    /// impl DebugPls for Status {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         match self.0 {
+    ///             0 => f.debug_ok(&()),
+    ///             code => f.debug_err(&code),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&[Status(0), Status(2)])), "[Ok(()), Err(2)]");
+    /// ```
+    pub fn debug_ok(self, value: &dyn DebugPls) {
+        self.debug_tuple_struct("Ok").field(value).finish();
+    }
+
+    /// Writes `Err(error)`, as the `Result` impl does.
+    pub fn debug_err(self, error: &dyn DebugPls) {
+        self.debug_tuple_struct("Err").field(error).finish();
+    }
+
+    /// Writes a string literal, escaped as the `str` impl does.
+    ///
+    /// The `debug_lit_*` methods write values that are kept in another form,
+    /// without putting together the literals by hand or with `syn`.
+    ///
+    /// ```rust
+    /// use dbg_pls::{pretty, DebugPls, Formatter};
+    ///
+    /// /// A string interned in a table
+    /// struct Symbol(u32);
+    ///
+    /// const SYMBOLS: &[&str] = &["main", "say \"hi\""];
+    ///
+    /// impl DebugPls for Symbol {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_lit_str(SYMBOLS[self.0 as usize]);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Symbol(1))), r#""say \"hi\"""#);
+    /// ```
+    pub fn debug_lit_str(self, value: &str) {
+        value.fmt(self);
+    }
+
+    /// Writes an integer literal. It is written without a type suffix,
+    /// even with [`Pretty::suffixes`], since it may stand for any type.
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// /// A 24 bit signed sample
+    /// struct Sample([u8; 3]);
+    ///
+    /// impl DebugPls for Sample {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         let [a, b, c] = self.0;
+    ///         f.debug_lit_int(i128::from(i32::from_le_bytes([0, a, b, c]) >> 8));
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Sample([0xff, 0xff, 0xff]))), "-1");
+    /// ```
+    pub fn debug_lit_int(self, value: i128) {
+        self.write_int("", itoa::Buffer::new().format(value), 3, "");
+    }
+
+    /// Writes a hexadecimal integer literal, `0xDEADBEEF`, for bitmasks,
+    /// addresses and the like, without a type suffix as with
+    /// [`debug_lit_int`](Self::debug_lit_int). Its digits are grouped in
+    /// fours with [`Pretty::digit_separators`].
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// struct Color(u32);
+    ///
+    /// impl DebugPls for Color {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_tuple_struct("Color")
+    ///             .field_with(|f| f.debug_int_hex(self.0.into()))
+    ///             .finish();
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Color(0xFF8000))), "Color(0xFF8000)");
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Color(0xDEADBEEF)).digit_separators()),
+    ///     "Color(0xDEAD_BEEF)",
+    /// );
+    /// ```
+    pub fn debug_int_hex(self, value: u128) {
+        self.write_int("0x", &format!("{value:X}"), 4, "");
+    }
+
+    /// Writes a binary integer literal, `0b1010`, for flags, like
+    /// [`debug_int_hex`](Self::debug_int_hex). Its digits are grouped in
+    /// fours with [`Pretty::digit_separators`].
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// struct Permissions(u16);
+    ///
+    /// impl DebugPls for Permissions {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_int_bin(self.0.into());
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Permissions(0b1_1110_1101)).digit_separators()),
+    ///     "0b1_1110_1101",
+    /// );
+    /// ```
+    pub fn debug_int_bin(self, value: u128) {
+        self.write_int("0b", &format!("{value:b}"), 4, "");
+    }
+
+    /// Writes an octal integer literal, `0o755`, for file modes, like
+    /// [`debug_int_hex`](Self::debug_int_hex). Its digits are grouped in
+    /// threes with [`Pretty::digit_separators`].
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// struct Mode(u32);
+    ///
+    /// impl DebugPls for Mode {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_int_oct(self.0.into());
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Mode(0o755))), "0o755");
+    /// ```
+    pub fn debug_int_oct(self, value: u128) {
+        self.write_int("0o", &format!("{value:o}"), 3, "");
+    }
+
+    /// Writes a float literal, without a type suffix as with
+    /// [`debug_lit_int`](Self::debug_lit_int). No literal stands for NaN
+    /// and the infinities, which are written as `f64::NAN`,
+    /// `f64::INFINITY` and `f64::NEG_INFINITY`.
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// /// A fixed point number with 8 fractional bits
+    /// struct Fixed(i32);
+    ///
+    /// impl DebugPls for Fixed {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_lit_float(f64::from(self.0) / 256.0);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Fixed(384))), "1.5");
+    /// ```
+    pub fn debug_lit_float(self, value: f64) {
+        let digits = self.tree.style.precision;
+        self.write_lit_float(value, digits);
+    }
+
+    /// Writes a float literal rounded to `digits` digits after the point,
+    /// like [`debug_lit_float`](Self::debug_lit_float), for coordinates and
+    /// measurements whose last digits are only noise. The literal is the
+    /// shortest one of the rounded value, which is still a float literal
+    /// with `digits` of `0`.
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// struct Point {
+    ///     x: f64,
+    ///     y: f64,
+    /// }
+    ///
+    /// impl DebugPls for Point {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Point")
+    ///             .field_with("x", |f| f.debug_float_with_precision(self.x, 3))
+    ///             .field_with("y", |f| f.debug_float_with_precision(self.y, 0))
+    ///             .finish();
+    ///     }
+    /// }
+    ///
+    /// let point = Point { x: 0.1 + 0.2, y: 2.5001 };
+    /// assert_eq!(format!("{}", pretty(&point)), "Point { x: 0.3, y: 3.0 }");
+    /// ```
+    ///
+    /// All floats can be rounded at once with [`Pretty::precision`].
+    pub fn debug_float_with_precision(self, value: f64, digits: usize) {
+        self.write_lit_float(value, Some(digits));
+    }
+
+    fn write_lit_float(self, value: f64, digits: Option<usize>) {
+        if value.is_finite() {
+            self.write_float(value, digits, "");
+        } else {
+            value.fmt(self);
+        }
+    }
+
+    /// Writes `true` or `false`.
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// struct Flags(u8);
+    ///
+    /// impl DebugPls for Flags {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_struct("Flags")
+    ///             .field_with("dirty", |f| f.debug_lit_bool(self.0 & 1 != 0))
+    ///             .finish();
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", pretty(&Flags(3))), "Flags { dirty: true }");
+    /// ```
+    pub fn debug_lit_bool(self, value: bool) {
+        value.fmt(self);
+    }
+
+    /// Writes a collection as only its type and length,
+    /// `Vec::<Frame>(len = 1024)`, for buffers and caches whose contents
+    /// would drown out the rest of the value.
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// struct Frames(Vec<[u8; 1024]>);
+    ///
+    /// impl DebugPls for Frames {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_len("Vec<Frame>", self.0.len());
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Frames(vec![[0; 1024]; 3]))),
+    ///     "Vec::<Frame>(len = 3)",
+    /// );
+    /// ```
+    pub fn debug_len(self, name: &str, len: usize) {
+        let name = self.tree.name(name);
+        let text = self.tree.text_with(|buf| {
+            buf.push_str("len = ");
+            buf.push_str(itoa::Buffer::new().format(len));
+        });
+        let len = self.tree.push(Node::Verbatim(text));
+        let mut args = node::Children::default();
+        self.tree.link(&mut args, len);
+        self.write(Node::TupleStruct(name, args));
+    }
+
+    /// Writes a size in bytes followed by a comment that reads it out,
+    /// `1572864 /* 1.5 MiB */`. With [`Pretty::humanized`], only the
+    /// readable size is written, as a string.
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// struct Allocated(u64);
+    ///
+    /// impl DebugPls for Allocated {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_byte_size(self.0);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Allocated(1_572_864))),
+    ///     "1572864 /* 1.5 MiB */",
+    /// );
+    /// assert_eq!(
+    ///     format!("{}", pretty(&Allocated(1_572_864)).humanized()),
+    ///     "\"1.5 MiB\"",
+    /// );
+    /// ```
+    pub fn debug_byte_size(self, bytes: u64) {
+        self.write_byte_size(bytes, "u64");
+    }
+
+    /// Writes a byte buffer, as a byte string if it is short, or else as a
+    /// hex dump, with the offset and the characters of each row in a
+    /// comment ahead of its bytes.
+    ///
+    /// ```rust
+    /// # use dbg_pls::{pretty, DebugPls, Formatter};
+    /// struct Packet(Vec<u8>);
+    ///
+    /// impl DebugPls for Packet {
+    ///     fn fmt(&self, f: Formatter<'_>) {
+    ///         f.debug_bytes(&self.0);
+    ///     }
+    /// }
+    ///
+    /// let short = Packet(b"GET /\r\n".to_vec());
+    /// assert_eq!(format!("{}", pretty(&short)), r#"b"GET /\r\n""#);
+    ///
+    /// let long = Packet(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n".to_vec());
+    /// assert_eq!(
+    ///     format!("{}", pretty(&long)),
+    ///     "\
+    /// [
+    ///     /* 0000 GET / HT */ 0x47, 0x45, 0x54, 0x20, 0x2f, 0x20, 0x48, 0x54,
+    ///     /* 0008 TP/1.1.. */ 0x54, 0x50, 0x2f, 0x31, 0x2e, 0x31, 0x0d, 0x0a,
+    ///     /* 0010 Host: ex */ 0x48, 0x6f, 0x73, 0x74, 0x3a, 0x20, 0x65, 0x78,
+    ///     /* 0018 ample.co */ 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x2e, 0x63, 0x6f,
+    ///     /* 0020 m....    */ 0x6d, 0x0d, 0x0a, 0x0d, 0x0a,
+    /// ]",
+    /// );
+    /// ```
+    pub fn debug_bytes(self, bytes: &[u8]) {
+        if bytes.len() <= hexdump::MAX_BYTE_STR {
+            self.write_lit_with(|buf| hexdump::byte_str(bytes, buf));
+        } else {
+            self.debug_list().entries(hexdump::rows(bytes)).finish();
+        }
+    }
+
+    /// Writes a size in bytes, see [`Formatter::debug_byte_size`], with the
+    /// type it was given in as its suffix.
+    pub(crate) fn write_byte_size(self, bytes: u64, ty: &str) {
+        let style = self.tree.style;
+        if style.humanized {
+            return self.write_lit_with(|buf| {
+                buf.push('"');
+                humanize::bytes(bytes, buf);
+                buf.push('"');
+            });
+        }
+        self.write_verbatim_with(|buf| {
+            buf.push_str(itoa::Buffer::new().format(bytes));
+            if style.suffixes {
+                buf.push_str(ty);
+            }
+            buf.push_str(" /* ");
+            humanize::bytes(bytes, buf);
+            buf.push_str(" */");
         });
     }
+
+    /// Implementation detail for the derive macro.
+    ///
+    /// Formats a whole struct in one call, so that derived impls don't
+    /// instantiate a builder chain per type.
+    #[doc(hidden)]
+    pub fn debug_struct_fields_finish(self, name: &str, names: &[&str], values: &[&dyn DebugPls]) {
+        assert_eq!(
+            names.len(),
+            values.len(),
+            "mismatched field names and values"
+        );
+        let mut d = self.debug_struct(name);
+        for (name, value) in names.iter().zip(values) {
+            d = d.field(name, *value);
+        }
+        d.finish();
+    }
+
+    pub(crate) fn debug_tuple_fields_finish(self, values: &[&dyn DebugPls]) {
+        let mut d = self.debug_tuple();
+        for value in values {
+            d = d.field(*value);
+        }
+        d.finish();
+    }
+
+    /// Implementation detail for the derive macro.
+    ///
+    /// Formats a whole tuple struct in one call, so that derived impls don't
+    /// instantiate a builder chain per type.
+    #[doc(hidden)]
+    pub fn debug_tuple_struct_fields_finish(self, name: &str, values: &[&dyn DebugPls]) {
+        let mut d = self.debug_tuple_struct(name);
+        for value in values {
+            d = d.field(*value);
+        }
+        d.finish();
+    }
+}
+
+/// Writes formatted text to the end of `buf`.
+pub(crate) fn push_fmt(buf: &mut String, args: std::fmt::Arguments<'_>) {
+    use std::fmt::Write;
+    // writing to a `String` does not fail
+    let _ = buf.write_fmt(args);
+}
+
+#[cfg(all(test, feature = "pretty"))]
+mod tests {
+    use crate::{pretty, DebugPls, Formatter};
+
+    #[test]
+    fn digit_separators() {
+        struct Flags(u32);
+
+        impl DebugPls for Flags {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple()
+                    .field_with(|f| f.debug_int_hex(self.0.into()))
+                    .field_with(|f| f.debug_int_bin(self.0.into()))
+                    .field_with(|f| f.debug_int_oct(self.0.into()))
+                    .finish();
+            }
+        }
+
+        assert_eq!(
+            pretty(&Flags(0x1F05)).to_string(),
+            "(0x1F05, 0b1111100000101, 0o17405)",
+        );
+        assert_eq!(
+            pretty(&Flags(0x1F05)).digit_separators().to_string(),
+            "(0x1F05, 0b1_1111_0000_0101, 0o17_405)",
+        );
+        assert_eq!(
+            pretty(&(1000_u16, i64::MIN, 0_u8))
+                .digit_separators()
+                .suffixes()
+                .to_string(),
+            "(1_000u16, -9_223_372_036_854_775_808i64, 0u8)",
+        );
+    }
+
+    #[test]
+    fn reborrowed_formatters() {
+        /// Writes small numbers as they are, leaving the rest to the caller.
+        fn small(n: u32, f: Formatter<'_>) -> bool {
+            if n < 10 {
+                n.fmt(f);
+            }
+            n < 10
+        }
+
+        struct Count(u32);
+
+        impl DebugPls for Count {
+            fn fmt(&self, mut f: Formatter<'_>) {
+                if small(self.0, f.reborrow()) {
+                    return;
+                }
+                // overwrites anything the helpers wrote
+                f.reborrow().debug_list().entry(&"many").finish();
+                f.debug_tuple_struct("Count").field(&self.0).finish();
+            }
+        }
+
+        assert_eq!(
+            pretty(&[Count(3), Count(300)]).to_string(),
+            "[3, Count(300)]",
+        );
+    }
+
+    #[test]
+    fn fallible_fields() {
+        struct Lazy(Result<u8, &'static str>);
+
+        impl DebugPls for Lazy {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.try_fmt(|f| {
+                    // the failure replaces the parts written before it
+                    let list = f.debug_list().entry(&"loaded");
+                    list.entry(&self.0?).finish();
+                    Ok::<_, &str>(())
+                });
+            }
+        }
+
+        assert_eq!(
+            pretty(&[Lazy(Ok(1)), Lazy(Err("file \"a\" is gone"))]).to_string(),
+            r#"[["loaded", 1], "<error: file \"a\" is gone>"]"#,
+        );
+    }
+
+    #[test]
+    fn foreign_types() {
+        /// A type with only `Display` and `Debug` impls, whose `Debug` is not Rust
+        struct Version(u8, u8);
+
+        impl std::fmt::Display for Version {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "v{}.{}", self.0, self.1)
+            }
+        }
+
+        impl std::fmt::Debug for Version {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "<{self}>")
+            }
+        }
+
+        struct Release(Version);
+
+        impl DebugPls for Release {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple()
+                    .field_with(|f| f.write_display(&self.0))
+                    .field_with(|f| f.write_debug(&self.0))
+                    .finish();
+            }
+        }
+
+        assert_eq!(
+            pretty(&Release(Version(1, 2))).to_string(),
+            r#"("v1.2", <v1.2>)"#,
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "syn")]
+    fn composed_exprs() {
+        /// A value written as the call that wraps it
+        struct Wrapped<T>(T);
+
+        impl<T: DebugPls> DebugPls for Wrapped<T> {
+            fn fmt(&self, mut f: Formatter<'_>) {
+                let value = f.value(&self.0);
+                let expr: syn::Expr = syn::parse_quote!(Wrapped(#value));
+                f.write_expr(expr);
+            }
+        }
+
+        struct Version;
+
+        impl std::fmt::Debug for Version {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("<v1.2")
+            }
+        }
+
+        struct Foreign;
+
+        impl DebugPls for Foreign {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple()
+                    .field_with(|f| f.write_debug(&Version))
+                    .field_with(|f| f.debug_tree("root").child(&1).finish())
+                    .finish();
+            }
+        }
+
+        assert_eq!(
+            pretty(&Wrapped((1..=2, Some("a")))).to_string(),
+            r#"Wrapped((1..=2, Some("a")))"#,
+        );
+        assert_eq!(
+            pretty(&Wrapped(vec![1, 2])).vec_macros().to_string(),
+            "Wrapped(vec![1, 2])",
+        );
+        assert_eq!(
+            pretty(&Wrapped(Foreign)).to_string(),
+            r#"Wrapped(("<v1.2", (root, [1])))"#,
+        );
+    }
+
+    #[test]
+    fn unit_sugar() {
+        struct Unit;
+
+        impl DebugPls for Unit {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_unit();
+            }
+        }
+
+        struct Marker;
+
+        impl DebugPls for Marker {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple_struct_unit("Marker");
+            }
+        }
+
+        /// Written as a derived unit struct is
+        struct Derived;
+
+        impl DebugPls for Derived {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_ident("Marker");
+            }
+        }
+
+        assert_eq!(pretty(&(Unit, Marker)).to_string(), "((), Marker)");
+        // highlighted as the values they stand for
+        #[cfg(feature = "colors")]
+        {
+            assert_eq!(
+                crate::color(&Unit).to_string(),
+                crate::color(&()).to_string()
+            );
+            assert_eq!(
+                crate::color(&Marker).to_string(),
+                crate::color(&Derived).to_string()
+            );
+        }
+    }
 }
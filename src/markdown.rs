@@ -0,0 +1,89 @@
+use std::fmt;
+
+use crate::{pretty, DebugPls, Pretty};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// A [`DebugPls`] type wrapped into a fenced `rust` code block, see
+/// [`markdown`]
+#[derive(Clone, Copy)]
+pub struct Markdown<'a> {
+    pretty: Pretty<'a>,
+    title: Option<&'a str>,
+}
+
+impl<'a> Markdown<'a> {
+    /// Puts a line of text above the code block, such as what the value is
+    /// or where it came from.
+    #[must_use]
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = Some(title);
+        self
+    }
+}
+
+impl<'a> From<Pretty<'a>> for Markdown<'a> {
+    fn from(pretty: Pretty<'a>) -> Self {
+        Markdown {
+            pretty,
+            title: None,
+        }
+    }
+}
+
+impl fmt::Debug for Markdown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(title) = self.title {
+            writeln!(f, "{title}")?;
+        }
+        let output = self.pretty.to_string();
+        // the fence has to be longer than any run of backticks in the
+        // output, such as in a string holding markdown of its own
+        let longest = output
+            .split(|ch| ch != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest.max(2) + 1);
+        write!(f, "{fence}rust\n{output}\n{fence}")
+    }
+}
+
+impl fmt::Display for Markdown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// Wraps a [`DebugPls`] type into a fenced `rust` code block, which keeps
+/// its highlighting when pasted into an issue or a pull request.
+///
+/// ```
+/// use dbg_pls::markdown;
+///
+/// let value = vec![Some(1), None];
+/// assert_eq!(
+///     format!("{}", markdown(&value).title("after the retry:")),
+///     "after the retry:\n```rust\n[Some(1), None]\n```",
+/// );
+/// ```
+///
+/// Values with a [`Budget`](crate::Budget) or any other option of
+/// [`Pretty`] are converted with [`Markdown::from`].
+pub fn markdown(value: &dyn DebugPls) -> Markdown<'_> {
+    Markdown::from(pretty(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::markdown;
+
+    #[test]
+    fn fence_outgrows_backticks() {
+        assert_eq!(
+            markdown(&"```sh\nls\n```").to_string(),
+            "````rust\n\"```sh\\nls\\n```\"\n````",
+        );
+        assert_eq!(markdown(&"``").to_string(), "```rust\n\"``\"\n```");
+    }
+}
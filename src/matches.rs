@@ -0,0 +1,379 @@
+use std::fmt;
+
+#[cfg(feature = "syn")]
+use crate::node::{Children, Node};
+use crate::{
+    node::{NodeId, Style, Tree},
+    DebugPls,
+};
+
+const MISMATCH: &str = "\x1b[1;41m";
+const RESET: &str = "\x1b[0m";
+
+/// The value and pattern that an
+/// [`assert_pls_matches!`](crate::assert_pls_matches) panics with, with
+/// the part of the value that does not match the pattern highlighted.
+pub struct Mismatch<'a> {
+    value: &'a dyn DebugPls,
+    /// The alternatives of the pattern, as written
+    patterns: &'a [&'a str],
+}
+
+impl<'a> Mismatch<'a> {
+    pub fn new(value: &'a dyn DebugPls, patterns: &'a [&'a str]) -> Self {
+        Mismatch { value, patterns }
+    }
+}
+
+impl fmt::Display for Mismatch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tree = Tree::capture_with(self.value, None, Style::default());
+        // the highlighting is only known to the built-in layout
+        let value = match mismatch(&tree, self.patterns) {
+            Some(id) => crate::layout::pretty_string_marked(&tree, id, MISMATCH, RESET),
+            None => crate::pretty::pretty_string(&tree),
+        };
+        write!(
+            f,
+            "pattern: {}\n  value: {}",
+            self.patterns.join(" | "),
+            value.replace('\n', "\n         "),
+        )
+    }
+}
+
+/// The node of the value that the patterns fail on, if the captured value
+/// tells, such as the `2` of `Some(2)` for the pattern `Some(1)`.
+///
+/// Without the `syn` feature the patterns can't be read, and the value is
+/// shown without anything highlighted.
+#[cfg(not(feature = "syn"))]
+fn mismatch(_: &Tree, _: &[&str]) -> Option<NodeId> {
+    None
+}
+
+#[cfg(feature = "syn")]
+fn mismatch(tree: &Tree, patterns: &[&str]) -> Option<NodeId> {
+    let patterns = patterns
+        .iter()
+        .map(|pattern| syn::parse_str::<syn::Pat>(pattern))
+        .collect::<syn::Result<Vec<_>>>()
+        .ok()?;
+    Matcher { tree }.any(Tree::ROOT, &patterns).err()
+}
+
+/// Matches patterns against a captured value, as far as its rendered form
+/// tells. Anything it can't judge, such as guards, constants and ranges,
+/// is taken to match, so that only a node that certainly does not match is
+/// highlighted.
+#[cfg(feature = "syn")]
+struct Matcher<'a> {
+    tree: &'a Tree,
+}
+
+#[cfg(feature = "syn")]
+impl Matcher<'_> {
+    /// Matches the node against the first of the alternatives that it
+    /// could match, or returns the node that the first alternative to get
+    /// past the node itself fails on.
+    fn any<'p>(
+        &self,
+        id: NodeId,
+        patterns: impl IntoIterator<Item = &'p syn::Pat>,
+    ) -> Result<(), NodeId> {
+        let mut mismatch = id;
+        for pattern in patterns {
+            match self.pattern(id, pattern) {
+                Ok(()) => return Ok(()),
+                Err(at) if mismatch == id => mismatch = at,
+                Err(_) => {}
+            }
+        }
+        Err(mismatch)
+    }
+
+    fn pattern(&self, id: NodeId, pattern: &syn::Pat) -> Result<(), NodeId> {
+        use syn::Pat;
+
+        let tree = self.tree;
+        match pattern {
+            Pat::Ident(pattern) => match &pattern.subpat {
+                Some((_, subpattern)) => self.pattern(id, subpattern),
+                // `None`, as opposed to a binding
+                None if is_uppercase(&pattern.ident) => self.name(id, &pattern.ident),
+                None => Ok(()),
+            },
+            Pat::Path(pattern) => match pattern.path.segments.last() {
+                Some(segment) => self.name(id, &segment.ident),
+                None => Ok(()),
+            },
+            Pat::TupleStruct(pattern) => match pattern.path.segments.last() {
+                Some(segment) => {
+                    self.name(id, &segment.ident)?;
+                    match tree.get(id) {
+                        Node::TupleStruct(_, fields) => self.elems(id, &pattern.pat.elems, fields),
+                        _ => Ok(()),
+                    }
+                }
+                None => Ok(()),
+            },
+            Pat::Struct(pattern) => match pattern.path.segments.last() {
+                Some(segment) => {
+                    self.name(id, &segment.ident)?;
+                    match tree.get(id) {
+                        Node::Struct(_, fields) => self.fields(fields, pattern),
+                        _ => Ok(()),
+                    }
+                }
+                None => Ok(()),
+            },
+            Pat::Tuple(pattern) => match tree.get(id) {
+                Node::Tuple(elems) => self.elems(id, &pattern.elems, elems),
+                _ => Ok(()),
+            },
+            Pat::Slice(pattern) => match tree.get(id) {
+                Node::List(elems) | Node::Vec(elems) | Node::Table(elems) => {
+                    self.elems(id, &pattern.elems, elems)
+                }
+                _ => Ok(()),
+            },
+            Pat::Lit(pattern) => self.lit(id, &pattern.expr),
+            Pat::Reference(pattern) => self.pattern(id, &pattern.pat),
+            Pat::Box(pattern) => self.pattern(id, &pattern.pat),
+            Pat::Or(pattern) => self.any(id, &pattern.cases),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the node is named `ident`, if it is named at all.
+    fn name(&self, id: NodeId, ident: &syn::Ident) -> Result<(), NodeId> {
+        let name = match self.tree.get(id) {
+            Node::Path(name) | Node::Struct(name, _) | Node::TupleStruct(name, _) => {
+                self.tree.str(name)
+            }
+            _ => return Ok(()),
+        };
+        // `Foo` of `crate::Foo<T>`
+        let name = name.split('<').next().unwrap_or(name);
+        let name = name.rsplit("::").next().unwrap_or(name);
+        if ident == name {
+            Ok(())
+        } else {
+            Err(id)
+        }
+    }
+
+    fn fields(&self, fields: Children, pattern: &syn::PatStruct) -> Result<(), NodeId> {
+        let tree = self.tree;
+        for field in &pattern.fields {
+            let child = match &field.member {
+                syn::Member::Named(name) => tree
+                    .children(fields)
+                    .find(|&child| name == tree.label(child)),
+                syn::Member::Unnamed(index) => tree.children(fields).nth(index.index as usize),
+            };
+            // a field that is skipped or elided can't be checked
+            if let Some(child) = child {
+                self.pattern(child, &field.pat)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn elems(
+        &self,
+        id: NodeId,
+        patterns: &syn::punctuated::Punctuated<syn::Pat, syn::Token![,]>,
+        elems: Children,
+    ) -> Result<(), NodeId> {
+        let tree = self.tree;
+        let patterns: Vec<_> = patterns.iter().collect();
+        let elems: Vec<_> = tree.children(elems).collect();
+
+        // elided elements can't be checked, nor can how many there were
+        if let Some(elided) = elems
+            .iter()
+            .position(|&elem| matches!(tree.get(elem), Node::Elided | Node::More(_)))
+        {
+            let known = patterns.iter().take_while(|pattern| !is_rest(pattern));
+            return elems[..elided]
+                .iter()
+                .zip(known)
+                .try_for_each(|(&elem, pattern)| self.pattern(elem, pattern));
+        }
+
+        let (before, after) = match patterns.iter().position(|pattern| is_rest(pattern)) {
+            Some(rest) => (&patterns[..rest], &patterns[rest + 1..]),
+            None if patterns.len() == elems.len() => (&patterns[..], &[][..]),
+            None => return Err(id),
+        };
+        if before.len() + after.len() > elems.len() {
+            return Err(id);
+        }
+        elems
+            .iter()
+            .zip(before)
+            .chain(elems[elems.len() - after.len()..].iter().zip(after))
+            .try_for_each(|(&elem, pattern)| self.pattern(elem, pattern))
+    }
+
+    /// Compares a literal pattern to a literal node by value, so that `0x10`
+    /// matches `16`, and `1u8` matches `1`.
+    fn lit(&self, id: NodeId, pattern: &syn::Expr) -> Result<(), NodeId> {
+        let text = match self.tree.get(id) {
+            Node::Lit(text) | Node::Path(text) => self.tree.str(text),
+            _ => return Ok(()),
+        };
+        let Ok(value) = syn::parse_str::<syn::Expr>(text) else {
+            return Ok(());
+        };
+        match (lit(pattern), lit(&value)) {
+            (Some((negative, pattern)), Some((is_negative, value))) => match same(pattern, value) {
+                Some(false) => Err(id),
+                _ if negative != is_negative => Err(id),
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A literal, such as `1` or `"a"`, or a negated one, such as `-1`.
+#[cfg(feature = "syn")]
+fn lit(expr: &syn::Expr) -> Option<(bool, &syn::Lit)> {
+    match expr {
+        syn::Expr::Lit(expr) => Some((false, &expr.lit)),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            syn::Expr::Lit(expr) => Some((true, &expr.lit)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether two literals have the same value, if they are of the same kind.
+#[cfg(feature = "syn")]
+fn same(a: &syn::Lit, b: &syn::Lit) -> Option<bool> {
+    use syn::Lit;
+
+    Some(match (a, b) {
+        (Lit::Int(a), Lit::Int(b)) => a.base10_digits() == b.base10_digits(),
+        (Lit::Str(a), Lit::Str(b)) => a.value() == b.value(),
+        (Lit::ByteStr(a), Lit::ByteStr(b)) => a.value() == b.value(),
+        (Lit::Byte(a), Lit::Byte(b)) => a.value() == b.value(),
+        (Lit::Char(a), Lit::Char(b)) => a.value() == b.value(),
+        (Lit::Bool(a), Lit::Bool(b)) => a.value == b.value,
+        _ => return None,
+    })
+}
+
+/// `..`, or `rest @ ..` in a slice pattern.
+#[cfg(feature = "syn")]
+fn is_rest(pattern: &syn::Pat) -> bool {
+    match pattern {
+        syn::Pat::Rest(_) => true,
+        syn::Pat::Ident(pattern) => {
+            matches!(&pattern.subpat, Some((_, subpattern)) if is_rest(subpattern))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(feature = "syn")]
+fn is_uppercase(ident: &syn::Ident) -> bool {
+    ident.to_string().starts_with(char::is_uppercase)
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+#[macro_export]
+/// Asserts that a value matches a pattern, like [`matches!`], and shows
+/// the pretty printed value when it does not.
+///
+/// ```
+/// use dbg_pls::assert_pls_matches;
+///
+/// let parsed: Result<Vec<u8>, String> = Ok(vec![1, 2, 3]);
+/// assert_pls_matches!(parsed, Ok(ref bytes) if bytes.len() == 3);
+/// assert_pls_matches!(Some(4), Some(1..=9) | None, "a message, {}", "formatted");
+/// ```
+///
+/// With the `syn` feature, the part of the value that the pattern fails
+/// on is highlighted, as in the `2` of `Some((1, 2))` for the pattern
+/// `Some((1, 3))`. What can't be told from the pretty printed value, such
+/// as guards and constants, is taken to match.
+macro_rules! assert_pls_matches {
+    ($value:expr, $($pattern:pat)|+ $(if $guard:expr)? $(,)?) => {
+        match $value {
+            $($pattern)|+ $(if $guard)? => {}
+            ref value => ::std::panic!(
+                "assertion failed: `(value matches pattern)`\n\n{}\n",
+                $crate::__private::Mismatch::new(value, &[$(::std::stringify!($pattern)),+])
+            ),
+        }
+    };
+    ($value:expr, $($pattern:pat)|+ $(if $guard:expr)?, $($arg:tt)+) => {
+        match $value {
+            $($pattern)|+ $(if $guard)? => {}
+            ref value => ::std::panic!(
+                "assertion failed: `(value matches pattern)`: {}\n\n{}\n",
+                ::std::format_args!($($arg)+),
+                $crate::__private::Mismatch::new(value, &[$(::std::stringify!($pattern)),+])
+            ),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mismatch;
+
+    fn value(mismatch: &Mismatch<'_>) -> String {
+        let output = mismatch.to_string();
+        output.split_once("  value: ").unwrap().1.to_owned()
+    }
+
+    #[test]
+    #[cfg(feature = "syn")]
+    fn highlights() {
+        assert_eq!(
+            value(&Mismatch::new(&Some((1, "two")), &["Some((1, \"three\"))"])),
+            "Some((1, \x1b[1;41m\"two\"\x1b[0m))",
+        );
+        assert_eq!(
+            value(&Mismatch::new(&Some(1), &["None"])),
+            "\x1b[1;41mSome(1)\x1b[0m",
+        );
+        assert_eq!(
+            value(&Mismatch::new(&vec![1, 2, 3], &["[_, _]"])),
+            "\x1b[1;41m[1, 2, 3]\x1b[0m",
+        );
+        assert_eq!(
+            value(&Mismatch::new(&vec![1, 2, 3], &["[0x1, .., 4]"])),
+            "[1, 2, \x1b[1;41m3\x1b[0m]",
+        );
+        assert_eq!(
+            value(&Mismatch::new(&Ok::<i32, ()>(-1), &["Ok(1)", "Err(_)"])),
+            "Ok(\x1b[1;41m-1\x1b[0m)",
+        );
+        // the guard is what failed
+        assert_eq!(value(&Mismatch::new(&Some(1), &["Some(x)"])), "Some(1)");
+    }
+
+    #[test]
+    fn message() {
+        let mismatch = Mismatch::new(&[(); 0], &["[_]", "[_, _]"]);
+        assert!(mismatch
+            .to_string()
+            .starts_with("pattern: [_] | [_, _]\n  value: "));
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: `(value matches pattern)`: retry 3\n\npattern: ")]
+    fn assert_pls_matches() {
+        crate::assert_pls_matches!(Some(1), Some(2) | None, "retry {}", 3);
+    }
+}
@@ -0,0 +1,701 @@
+//! The tree that [`DebugPls`] implementations are captured into.
+//!
+//! All the nodes and text captured while formatting one value live in a
+//! single [`Tree`], which acts as an arena for them: nodes refer to each other
+//! by index and to their text by range into one shared buffer. Capturing a
+//! value therefore only costs the few amortised allocations of those two
+//! buffers, however many nodes it is made of.
+//!
+//! Nothing here borrows from the value or depends on `syn`, so a captured
+//! tree can be laid out (or sent to another thread) on its own.
+
+use std::time::Instant;
+
+use crate::{DebugPls, Formatter};
+
+#[cfg(feature = "syn")]
+mod expr;
+mod order;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod truncate;
+
+pub(crate) use truncate::Truncate;
+
+/// Every node and string captured from one value.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Tree {
+    entries: Vec<Entry>,
+    text: String,
+    limit: Option<Limit>,
+    /// Whether `limit` has been reached
+    spent: bool,
+    /// Number of entries at which the deadline is next checked
+    next_clock: usize,
+    /// Addresses of the shared values currently being captured, innermost last
+    pub(crate) shared: Vec<usize>,
+    /// Number of values currently being captured, one inside the other
+    depth: usize,
+    pub(crate) style: Style,
+    /// The node whose entries, or the chars of whose string, are cut short,
+    /// and to how many, for a field marked `#[dbg_pls(truncate = N)]`
+    pub(crate) truncation: Option<(NodeId, usize)>,
+}
+
+/// How deeply values are captured without a [`Limit`] on it, which keeps
+/// deep values from overflowing the stack of the secondary threads
+/// (2 MiB by default). `prettyplease` needs a lot more stack to lay them out.
+pub(crate) const MAX_DEPTH: usize = if cfg!(feature = "syn-backend") {
+    128
+} else {
+    1024
+};
+
+/// How values are written, where there is more than one way to.
+#[allow(clippy::struct_excessive_bools)] // each is a choice of its own
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Style {
+    /// Named maps and sets as the constructors that build them,
+    /// `HashMap::from([(1, 2)])`
+    pub(crate) constructors: bool,
+    /// Number literals with their type as a suffix, `5u8`
+    pub(crate) suffixes: bool,
+    /// Vecs, `VecDeque`s and slices as the `vec!` that builds them,
+    /// `vec![1, 2]`
+    pub(crate) vec_macros: bool,
+    /// Strings with quotes or backslashes in them as raw string literals,
+    /// `r#"say "hi""#`
+    pub(crate) raw_strings: bool,
+    /// Strings of several lines as a `concat!` of each of them
+    pub(crate) multiline_strings: bool,
+    /// Integer literals with their digits in groups, `1_000_000` and
+    /// `0xDEAD_BEEF`
+    pub(crate) digit_separators: bool,
+    /// Floats rounded to this many digits after the point, `0.3` rather
+    /// than `0.30000000000000004`
+    pub(crate) precision: Option<usize>,
+    /// Durations and times followed by a comment that reads them out,
+    /// `Duration::from_millis(123400) /* 2m 3.4s */`, and sizes in
+    /// bytes as only their readable unit, `"1.5 MiB"`
+    pub(crate) humanized: bool,
+    /// Structs, tuples and lists broken over lines without a comma after
+    /// their last element
+    pub(crate) no_trailing_commas: bool,
+    /// The radix integer literals are written in, `0xFF` rather than `255`
+    pub(crate) radix: Radix,
+}
+
+/// The radix of an integer literal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Radix {
+    #[default]
+    Decimal,
+    Hex,
+    Binary,
+}
+
+/// The longest list that is captured element by element. Longer ones, as
+/// slices of zero-sized types easily are, are summarized by their length,
+/// `[..; 10000000]`, before any of their elements is looked at.
+pub(crate) const MAX_LEN: usize = 1 << 20;
+
+/// How far capturing a value may go before the rest of it is elided.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Limit {
+    /// Rough length of the output, in bytes
+    pub(crate) len: Option<usize>,
+    pub(crate) deadline: Option<Instant>,
+    /// How deeply values are nested, instead of [`MAX_DEPTH`]
+    pub(crate) depth: Option<usize>,
+}
+
+/// Index of a node in its [`Tree`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct NodeId(usize);
+
+/// Range of a string in its [`Tree`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Text {
+    start: usize,
+    end: usize,
+}
+
+/// The children of a node, linked through their entries in the order they
+/// were appended.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Children {
+    first: Option<NodeId>,
+    last: Option<NodeId>,
+    len: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    node: Node,
+    /// The field name, for the children of a [`Node::Struct`]
+    label: Text,
+    /// The next sibling
+    next: Option<NodeId>,
+    hint: Hint,
+    /// Written after the node, for the fields of a struct and the entries
+    /// of a list or map
+    comment: Text,
+    /// Whether the node ends in a comma when it is broken over lines, where
+    /// its builder chose for it
+    trailing_comma: Option<bool>,
+}
+
+/// How a node is broken over lines, where its builder overrides the
+/// layout's own choice by width.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Hint {
+    #[default]
+    Auto,
+    /// Broken over lines, however short
+    Expanded,
+    /// Kept on one line, children and all, however long
+    Compact,
+}
+
+/// A captured value, in the shape it will be rendered as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Node {
+    /// A literal in its Rust source form, such as `5`, `1.5` or `"hello"`
+    Lit(Text),
+    /// A path to a unit value, such as `None` or `Foo`
+    Path(Text),
+    /// `Foo { bar: 1, baz: 2 }`, each field labelled with its name
+    Struct(Text, Children),
+    /// `Foo(1, 2)`
+    TupleStruct(Text, Children),
+    /// `(1, 2)`
+    Tuple(Children),
+    /// `[1, 2]`
+    List(Children),
+    /// `vec![1, 2]`
+    Vec(Children),
+    /// A string broken over lines as the literals of each of them,
+    /// `concat!("a\n", "b")`
+    Concat(Children),
+    /// A list of structs of the same shape, laid out with their fields in
+    /// aligned columns where they fit
+    Table(Children),
+    /// A label with its children drawn below it, joined to it by
+    /// box-drawing guides
+    Branch(Text, Children),
+    /// `{ 1; 2 }`
+    Set(Children),
+    /// `{ [1] = 2; }`, with keys and values alternating
+    Map(Children),
+    /// `1..2`, `..=2`, `..`
+    Range(Range),
+    /// Source text that none of the other nodes can represent
+    Verbatim(Text),
+    /// `..`, in place of everything left out once the [`Limit`] was reached
+    Elided,
+    /// `.. /* 4032 more */`, in place of the entries of a collection left
+    /// out past those it was truncated to
+    More(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Range {
+    pub(crate) start: Option<NodeId>,
+    pub(crate) end: Option<NodeId>,
+    /// `..=` rather than `..`
+    pub(crate) inclusive: bool,
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Node::Verbatim(Text::default())
+    }
+}
+
+impl Tree {
+    pub(crate) const ROOT: NodeId = NodeId(0);
+
+    /// Captures `value` into a new tree, whose root is [`Tree::ROOT`].
+    #[cfg(test)]
+    pub(crate) fn capture(value: &dyn DebugPls) -> Self {
+        let mut tree = Tree::default();
+        tree.process(value);
+        tree
+    }
+
+    /// Captures `value` into a new tree in the given `style`, eliding
+    /// whatever does not fit in `limit`.
+    pub(crate) fn capture_with(value: &dyn DebugPls, limit: Option<Limit>, style: Style) -> Self {
+        let mut tree = Tree {
+            limit,
+            style,
+            ..Tree::default()
+        };
+        tree.process(value);
+        tree
+    }
+
+    /// Captures `value` into a new node of this tree, or elides it if the
+    /// limit has been reached.
+    pub(crate) fn process(&mut self, value: &dyn DebugPls) -> NodeId {
+        if self.elides() {
+            return self.push(Node::Elided);
+        }
+        self.capture_node(value)
+    }
+
+    /// Captures `value` into a new node of this tree, regardless of the limit.
+    pub(crate) fn capture_node(&mut self, value: &dyn DebugPls) -> NodeId {
+        let id = self.push(Node::default());
+        self.depth += 1;
+        value.fmt(Formatter { tree: self, id });
+        self.depth -= 1;
+        id
+    }
+
+    /// Whether the next value would be elided, for being nested too deeply or
+    /// for the limit having been reached.
+    pub(crate) fn elides(&mut self) -> bool {
+        let max_depth = match self.limit {
+            Some(Limit {
+                depth: Some(depth), ..
+            }) => depth,
+            _ => MAX_DEPTH,
+        };
+        self.depth >= max_depth || self.spent()
+    }
+
+    /// Whether the limit has been reached. From then on, every value that is
+    /// still to be captured is elided.
+    pub(crate) fn spent(&mut self) -> bool {
+        if let (Some(limit), false) = (self.limit, self.spent) {
+            // punctuation comes to about a separator for every node
+            let len = self.text.len() + 2 * self.entries.len();
+            self.spent = matches!(limit.len, Some(max) if len >= max);
+            // reading the clock costs about as much as capturing a small
+            // value, so it is only done every so often
+            if let Some(deadline) = limit.deadline {
+                if self.entries.len() >= self.next_clock {
+                    self.next_clock = self.entries.len() + 64;
+                    self.spent |= Instant::now() >= deadline;
+                }
+            }
+        }
+        self.spent
+    }
+
+    pub(crate) fn push(&mut self, node: Node) -> NodeId {
+        let id = NodeId(self.entries.len());
+        self.entries.push(Entry {
+            node,
+            label: Text::default(),
+            next: None,
+            hint: Hint::Auto,
+            comment: Text::default(),
+            trailing_comma: None,
+        });
+        id
+    }
+
+    pub(crate) fn set(&mut self, id: NodeId, node: Node) {
+        self.entries[id.0].node = node;
+    }
+
+    pub(crate) fn get(&self, id: NodeId) -> Node {
+        self.entries[id.0].node
+    }
+
+    pub(crate) fn text(&mut self, text: &str) -> Text {
+        self.text_with(|buf| buf.push_str(text))
+    }
+
+    /// Writes text straight into the buffer.
+    pub(crate) fn text_with(&mut self, write: impl FnOnce(&mut String)) -> Text {
+        let start = self.text.len();
+        write(&mut self.text);
+        Text {
+            start,
+            end: self.text.len(),
+        }
+    }
+
+    /// Writes the name of a type, field or value, turning every segment of it
+    /// that is a keyword into a raw identifier: `r#type`, `foo::r#match`.
+    /// Generic arguments are written with a turbofish, `Foo::<Bar>`, as they
+    /// would be in an expression. Names that are not paths, such as
+    /// `dyn Trait`, are written as they are.
+    pub(crate) fn name(&mut self, name: &str) -> Text {
+        self.text_with(|buf| {
+            let Some(segments) = path_segments(name) else {
+                return buf.push_str(name);
+            };
+            for (i, (ident, args)) in segments.into_iter().enumerate() {
+                if i > 0 {
+                    buf.push_str("::");
+                }
+                if is_keyword(ident) {
+                    buf.push_str("r#");
+                }
+                buf.push_str(ident);
+                if !args.is_empty() {
+                    if !ident.is_empty() {
+                        buf.push_str("::");
+                    }
+                    buf.push_str(args);
+                }
+            }
+        })
+    }
+
+    /// Writes the path to the `from` constructor of the type `name`.
+    pub(crate) fn constructor_path(&mut self, name: &str) -> Text {
+        let name = self.name(name);
+        let from = self.text("::from");
+        Text {
+            start: name.start,
+            end: from.end,
+        }
+    }
+
+    /// Writes `name` with the generic arguments after it, `Matrix::<3, 3>`.
+    pub(crate) fn generic_name(&mut self, name: Text, args: &[&str]) -> Text {
+        if args.is_empty() {
+            return name;
+        }
+        let name = self.str(name).to_owned();
+        self.text_with(|buf| {
+            buf.push_str(&name);
+            buf.push_str("::<");
+            for (i, arg) in args.iter().enumerate() {
+                if i > 0 {
+                    buf.push_str(", ");
+                }
+                buf.push_str(arg);
+            }
+            buf.push('>');
+        })
+    }
+
+    pub(crate) fn str(&self, text: Text) -> &str {
+        &self.text[text.start..text.end]
+    }
+
+    /// Appends `child` to the end of `children`, unlinking it from any
+    /// siblings it had.
+    pub(crate) fn link(&mut self, children: &mut Children, child: NodeId) {
+        self.entries[child.0].next = None;
+        match children.last {
+            Some(last) => self.entries[last.0].next = Some(child),
+            None => children.first = Some(child),
+        }
+        children.last = Some(child);
+        children.len += 1;
+    }
+
+    /// Captures `value` and appends it to the end of `children`.
+    pub(crate) fn append(&mut self, children: &mut Children, value: &dyn DebugPls) {
+        if self.elides() {
+            return self.elide(children);
+        }
+        let child = self.capture_node(value);
+        self.link(children, child);
+    }
+
+    /// Captures every value and appends them to the end of `children`,
+    /// stopping early once the limit has been reached.
+    pub(crate) fn append_all<D: DebugPls>(
+        &mut self,
+        children: &mut Children,
+        values: impl IntoIterator<Item = D>,
+    ) {
+        for value in values {
+            if self.elides() {
+                return self.elide(children);
+            }
+            self.append(children, &value);
+        }
+    }
+
+    /// Marks the end of `children` as elided, unless it already is.
+    pub(crate) fn elide(&mut self, children: &mut Children) {
+        if let Some(last) = children.last {
+            if self.get(last) == Node::Elided {
+                return;
+            }
+        }
+        let child = self.push(Node::Elided);
+        self.link(children, child);
+    }
+
+    /// Captures `value` and appends it to the end of `children` as the field `name`.
+    pub(crate) fn append_field(
+        &mut self,
+        children: &mut Children,
+        name: &str,
+        value: &dyn DebugPls,
+    ) {
+        if self.elides() {
+            return self.elide(children);
+        }
+        let label = self.name(name);
+        let child = self.capture_node(value);
+        self.label_as(child, label);
+        self.link(children, child);
+    }
+
+    /// Captures `value` and appends its fields to the end of `children`,
+    /// when it is a struct with named fields, or appends it as the field
+    /// `name` otherwise.
+    pub(crate) fn append_flattened(
+        &mut self,
+        children: &mut Children,
+        name: &str,
+        value: &dyn DebugPls,
+    ) {
+        if self.elides() {
+            return self.elide(children);
+        }
+        let child = self.capture_node(value);
+        let Node::Struct(_, fields) = self.get(child) else {
+            let label = self.name(name);
+            self.label_as(child, label);
+            return self.link(children, child);
+        };
+        // linking a field unlinks it from the fields after it
+        let fields: Vec<_> = self.children(fields).collect();
+        for field in fields {
+            if self.get(field) == Node::Elided {
+                self.elide(children);
+            } else {
+                self.link(children, field);
+            }
+        }
+    }
+
+    /// The constructor that builds a collection out of `elems`,
+    /// `HashMap::from([..])`, given the path to it, see [`Tree::constructor_path`].
+    pub(crate) fn constructor(&mut self, from: Text, elems: Children) -> Node {
+        let list = self.push(Node::List(elems));
+        let mut args = Children::default();
+        self.link(&mut args, list);
+        Node::TupleStruct(from, args)
+    }
+
+    pub(crate) fn label_as(&mut self, id: NodeId, label: Text) {
+        self.entries[id.0].label = label;
+    }
+
+    pub(crate) fn label(&self, id: NodeId) -> &str {
+        self.str(self.entries[id.0].label)
+    }
+
+    pub(crate) fn hint_as(&mut self, id: NodeId, hint: Hint) {
+        self.entries[id.0].hint = hint;
+    }
+
+    pub(crate) fn hint(&self, id: NodeId) -> Hint {
+        self.entries[id.0].hint
+    }
+
+    pub(crate) fn trailing_comma_as(&mut self, id: NodeId, trailing: bool) {
+        self.entries[id.0].trailing_comma = Some(trailing);
+    }
+
+    pub(crate) fn trailing_comma(&self, id: NodeId) -> Option<bool> {
+        self.entries[id.0].trailing_comma
+    }
+
+    /// Attaches `comment` to the last of `children`, on one line, unless
+    /// it stands for what was left out.
+    pub(crate) fn comment_last(&mut self, children: Children, comment: &str) {
+        let Some(last) = children.last else {
+            return;
+        };
+        if let Node::Elided | Node::More(_) = self.get(last) {
+            return;
+        }
+        let comment = self.text_with(|buf| {
+            buf.extend(
+                comment
+                    .chars()
+                    .map(|ch| if ch == '\n' || ch == '\r' { ' ' } else { ch }),
+            );
+        });
+        self.entries[last.0].comment = comment;
+    }
+
+    pub(crate) fn comment(&self, id: NodeId) -> Option<&str> {
+        let comment = self.entries[id.0].comment;
+        (comment.end > comment.start).then(|| self.str(comment))
+    }
+
+    pub(crate) fn children(&self, children: Children) -> Iter<'_> {
+        Iter {
+            tree: self,
+            next: children.first,
+            len: children.len,
+        }
+    }
+}
+
+pub(crate) struct Iter<'a> {
+    tree: &'a Tree,
+    next: Option<NodeId>,
+    len: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        self.next = self.tree.entries[id.0].next;
+        self.len -= 1;
+        Some(id)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// Splits a path into the identifier and generic arguments of each of its
+/// segments, `Foo<Bar>` into `("Foo", "<Bar>")`, or returns `None` if it is
+/// not a path. Arguments already written with a turbofish are a segment of
+/// their own, with no identifier.
+fn path_segments(name: &str) -> Option<Vec<(&str, &str)>> {
+    let bytes = name.as_bytes();
+    let mut segments = Vec::new();
+    // where the current segment and its generic arguments start
+    let (mut start, mut args) = (0, None);
+    let mut depth = 0_usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'<' if depth == 0 && args.is_some() => return None,
+            b'<' => {
+                args = args.or(Some(i));
+                depth += 1;
+            }
+            // the arrow of a `Fn() -> T` argument
+            b'>' if depth > 0 && bytes[i - 1] == b'-' => {}
+            b'>' => depth = depth.checked_sub(1)?,
+            b':' if depth == 0 && bytes.get(i + 1) == Some(&b':') => {
+                segments.push(name[start..i].split_at(args.unwrap_or(i) - start));
+                (start, args) = (i + 2, None);
+                i += 1;
+            }
+            // anything but `::` after the arguments, or a space as in
+            // `dyn Trait`
+            _ if depth > 0 => {}
+            _ if args.is_some() => return None,
+            byte if byte.is_ascii() && !(byte.is_ascii_alphanumeric() || b"_#".contains(&byte)) => {
+                return None;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if depth > 0 {
+        return None;
+    }
+    segments.push(name[start..].split_at(args.unwrap_or(name.len()) - start));
+    // `<T as Trait>::Assoc` or `::std::vec::Vec` start from no type
+    match segments.first() {
+        Some(("", _)) => None,
+        _ => Some(segments),
+    }
+}
+
+/// Whether `name` is a keyword that can be written as a raw identifier.
+/// `self`, `Self`, `super` and `crate` cannot, and `true` and `false` are left
+/// as they are, being the values they name.
+fn is_keyword(name: &str) -> bool {
+    matches!(
+        name,
+        "as" | "async"
+            | "await"
+            | "break"
+            | "const"
+            | "continue"
+            | "dyn"
+            | "else"
+            | "enum"
+            | "extern"
+            | "fn"
+            | "for"
+            | "if"
+            | "impl"
+            | "in"
+            | "let"
+            | "loop"
+            | "match"
+            | "mod"
+            | "move"
+            | "mut"
+            | "pub"
+            | "ref"
+            | "return"
+            | "static"
+            | "struct"
+            | "trait"
+            | "try"
+            | "type"
+            | "unsafe"
+            | "use"
+            | "where"
+            | "while"
+            | "abstract"
+            | "become"
+            | "box"
+            | "do"
+            | "final"
+            | "gen"
+            | "macro"
+            | "override"
+            | "priv"
+            | "typeof"
+            | "unsized"
+            | "virtual"
+            | "yield"
+    )
+}
+
+#[cfg(all(test, feature = "pretty"))]
+mod tests {
+    use crate::{pretty, DebugPls, Formatter};
+
+    #[test]
+    fn names_that_are_not_paths() {
+        struct Adapter(&'static str);
+
+        impl DebugPls for Adapter {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_struct(self.0).field("0", &1).finish();
+            }
+        }
+
+        struct Wrapper(&'static str);
+
+        impl DebugPls for Wrapper {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple_struct(self.0).field(&1).finish();
+            }
+        }
+
+        assert_eq!(
+            pretty(&(Wrapper("Foo<Bar>"), Adapter("Foo"))).to_string(),
+            "(Foo::<Bar>(1), Foo { 0: 1 })",
+        );
+        assert_eq!(
+            pretty(&[Wrapper("std::vec::Vec<Option<u8>>"), Wrapper("Foo::<Bar>")]).to_string(),
+            "[std::vec::Vec::<Option<u8>>(1), Foo::<Bar>(1)]",
+        );
+        assert_eq!(
+            pretty(&[Adapter("dyn Trait"), Adapter("<T as Trait>::Assoc")]).to_string(),
+            "[dyn Trait { 0: 1 }, <T as Trait>::Assoc { 0: 1 }]",
+        );
+    }
+}
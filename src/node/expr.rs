@@ -0,0 +1,711 @@
+//! Conversions between [`Node`]s and [`syn::Expr`]s.
+
+use std::fmt::Display;
+
+use quote::ToTokens;
+use syn::{punctuated::Punctuated, BinOp, Expr, Lit, Member, PathArguments, RangeLimits, Stmt};
+
+use super::{Children, Node, NodeId, Range, Text, Tree};
+use crate::push_fmt;
+
+impl Tree {
+    /// Captures an expression written through [`Formatter::write_expr`](crate::Formatter::write_expr).
+    pub(crate) fn capture_expr(&mut self, expr: &Expr) -> Node {
+        match expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Bool(lit) => Node::Lit(self.text(if lit.value { "true" } else { "false" })),
+                lit => Node::Lit(self.tokens(lit)),
+            },
+            Expr::Path(expr) if expr.qself.is_none() => Node::Path(self.path(&expr.path)),
+            Expr::Struct(expr) if expr.rest.is_none() => {
+                let name = self.path(&expr.path);
+                let mut fields = Children::default();
+                for field in &expr.fields {
+                    let label = match &field.member {
+                        Member::Named(ident) => self.display(ident),
+                        Member::Unnamed(index) => self.display(&index.index),
+                    };
+                    let child = self.push_expr(&field.expr);
+                    self.label_as(child, label);
+                    self.link(&mut fields, child);
+                }
+                Node::Struct(name, fields)
+            }
+            Expr::Call(call) => match &*call.func {
+                Expr::Path(func) if func.qself.is_none() => {
+                    let name = self.path(&func.path);
+                    Node::TupleStruct(name, self.exprs(&call.args))
+                }
+                _ => Node::Verbatim(self.expr_text(expr)),
+            },
+            Expr::Tuple(expr) => Node::Tuple(self.exprs(&expr.elems)),
+            Expr::Array(expr) => Node::List(self.exprs(&expr.elems)),
+            Expr::Block(block) if block.label.is_none() => match self.stmts(&block.block.stmts) {
+                Some(node) => node,
+                None => Node::Verbatim(self.expr_text(expr)),
+            },
+            Expr::Range(expr) => Node::Range(Range {
+                start: expr.from.as_deref().map(|start| self.push_bound(start)),
+                end: expr.to.as_deref().map(|end| self.push_bound(end)),
+                inclusive: matches!(expr.limits, RangeLimits::Closed(_)),
+            }),
+            // the macros the layout writes, as lowered by `Tree::expr`
+            Expr::Macro(mac) if mac.mac.path.is_ident("vec") || mac.mac.path.is_ident("concat") => {
+                let parser = Punctuated::<Expr, syn::Token![,]>::parse_terminated;
+                match mac.mac.parse_body_with(parser) {
+                    Ok(elems) if mac.mac.path.is_ident("vec") => Node::Vec(self.exprs(&elems)),
+                    Ok(lines) => Node::Concat(self.exprs(&lines)),
+                    Err(_) => Node::Verbatim(self.expr_text(expr)),
+                }
+            }
+            expr => Node::Verbatim(self.expr_text(expr)),
+        }
+    }
+
+    fn push_expr(&mut self, expr: &Expr) -> NodeId {
+        let node = self.capture_expr(expr);
+        self.push(node)
+    }
+
+    /// Captures the bound of a range. Ranges are parenthesized when they are
+    /// laid out, anything that binds looser has to be parenthesized here.
+    fn push_bound(&mut self, bound: &Expr) -> NodeId {
+        if Precedence::of(bound) >= Precedence::Range {
+            return self.push_expr(bound);
+        }
+        let text = self.text_with(|out| write_operand(out, bound, true));
+        self.push(Node::Verbatim(text))
+    }
+
+    fn exprs<'a>(&mut self, exprs: impl IntoIterator<Item = &'a Expr>) -> Children {
+        let mut children = Children::default();
+        for expr in exprs {
+            let child = self.push_expr(expr);
+            self.link(&mut children, child);
+        }
+        children
+    }
+
+    /// Recognises the blocks that maps (`{ [k] = v; }`) and sets (`{ a; b }`) are made of.
+    fn stmts(&mut self, stmts: &[Stmt]) -> Option<Node> {
+        fn entry(stmt: &Stmt) -> Option<[&Expr; 2]> {
+            match stmt {
+                Stmt::Semi(Expr::Assign(assign), _) => match &*assign.left {
+                    Expr::Array(key) if key.elems.len() == 1 => {
+                        Some([&key.elems[0], &*assign.right])
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        if let Some(entries) = stmts.iter().map(entry).collect::<Option<Vec<_>>>() {
+            if !entries.is_empty() {
+                return Some(Node::Map(self.exprs(entries.into_iter().flatten())));
+            }
+        }
+
+        let (last, init) = match stmts.split_last() {
+            Some((Stmt::Expr(last), init)) => (Some(last), init),
+            Some(_) => return None,
+            None => (None, stmts),
+        };
+        let mut entries = Vec::with_capacity(stmts.len());
+        for stmt in init {
+            match stmt {
+                Stmt::Semi(expr, _) => entries.push(expr),
+                _ => return None,
+            }
+        }
+        entries.extend(last);
+        Some(Node::Set(self.exprs(entries)))
+    }
+
+    fn path(&mut self, path: &syn::Path) -> Text {
+        self.text_with(|out| write_path(out, path))
+    }
+
+    /// Writes an expression that has no node of its own, such as `-x` or
+    /// `a[0].b`, parenthesized wherever precedence requires it.
+    fn expr_text(&mut self, expr: &Expr) -> Text {
+        self.text_with(|out| write_expr(out, expr))
+    }
+
+    fn tokens(&mut self, tokens: &impl ToTokens) -> Text {
+        self.display(&tokens.to_token_stream())
+    }
+
+    /// Writes `value` straight into the text buffer.
+    fn display(&mut self, value: &impl Display) -> Text {
+        self.text_with(|buf| {
+            push_fmt(buf, format_args!("{value}"));
+        })
+    }
+}
+
+/// How tightly an expression binds, loosest first, as in the
+/// [reference](https://doc.rust-lang.org/reference/expressions.html#expression-precedence).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    /// Closures and `return`, `break` or `yield` with a value, which take in
+    /// everything up to the end of the expression.
+    Jump,
+    Assign,
+    Range,
+    Or,
+    And,
+    Compare,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Sum,
+    Product,
+    Cast,
+    Prefix,
+    Postfix,
+    /// Literals, paths and anything delimited by brackets of its own.
+    Atom,
+}
+
+impl Precedence {
+    fn of(expr: &Expr) -> Self {
+        match expr {
+            Expr::Closure(_) | Expr::Return(_) | Expr::Break(_) | Expr::Yield(_) | Expr::Let(_) => {
+                Precedence::Jump
+            }
+            Expr::Assign(_) | Expr::AssignOp(_) => Precedence::Assign,
+            Expr::Range(_) => Precedence::Range,
+            Expr::Binary(expr) => Precedence::of_op(&expr.op),
+            Expr::Cast(_) | Expr::Type(_) => Precedence::Cast,
+            Expr::Unary(_) | Expr::Reference(_) | Expr::Box(_) => Precedence::Prefix,
+            Expr::Field(_)
+            | Expr::Index(_)
+            | Expr::Call(_)
+            | Expr::MethodCall(_)
+            | Expr::Try(_)
+            | Expr::Await(_) => Precedence::Postfix,
+            // a negative literal is as good as `-` applied to it
+            Expr::Lit(lit) if is_negative(&lit.lit) => Precedence::Prefix,
+            Expr::Group(group) => Precedence::of(&group.expr),
+            _ => Precedence::Atom,
+        }
+    }
+
+    fn of_op(op: &BinOp) -> Self {
+        match op {
+            BinOp::Or(_) => Precedence::Or,
+            BinOp::And(_) => Precedence::And,
+            BinOp::Eq(_)
+            | BinOp::Ne(_)
+            | BinOp::Lt(_)
+            | BinOp::Le(_)
+            | BinOp::Gt(_)
+            | BinOp::Ge(_) => Precedence::Compare,
+            BinOp::BitOr(_) => Precedence::BitOr,
+            BinOp::BitXor(_) => Precedence::BitXor,
+            BinOp::BitAnd(_) => Precedence::BitAnd,
+            BinOp::Shl(_) | BinOp::Shr(_) => Precedence::Shift,
+            BinOp::Add(_) | BinOp::Sub(_) => Precedence::Sum,
+            BinOp::Mul(_) | BinOp::Div(_) | BinOp::Rem(_) => Precedence::Product,
+            // the compound assignments, `+=` and so on
+            _ => Precedence::Assign,
+        }
+    }
+}
+
+fn is_negative(lit: &Lit) -> bool {
+    match lit {
+        Lit::Int(lit) => lit.base10_digits().starts_with('-'),
+        Lit::Float(lit) => lit.base10_digits().starts_with('-'),
+        _ => false,
+    }
+}
+
+/// Writes the expression with the spacing `rustfmt` would give it, rather
+/// than the spacing of its tokens, `- x . y`. The expressions it does not
+/// know of are written as tokens.
+fn write_expr(out: &mut String, expr: &Expr) {
+    match expr {
+        Expr::Path(expr) if expr.qself.is_none() => write_path(out, &expr.path),
+        Expr::Paren(expr) => {
+            out.push('(');
+            write_expr(out, &expr.expr);
+            out.push(')');
+        }
+        Expr::Group(group) => write_expr(out, &group.expr),
+        Expr::Unary(expr) => {
+            write_tokens(out, &expr.op);
+            write_operand(
+                out,
+                &expr.expr,
+                Precedence::of(&expr.expr) < Precedence::Prefix,
+            );
+        }
+        Expr::Reference(expr) => {
+            out.push('&');
+            if expr.mutability.is_some() {
+                out.push_str("mut ");
+            }
+            write_operand(
+                out,
+                &expr.expr,
+                Precedence::of(&expr.expr) < Precedence::Prefix,
+            );
+        }
+        Expr::Binary(expr) => {
+            let precedence = Precedence::of_op(&expr.op);
+            // left associative, apart from the comparisons which do not chain
+            let left = Precedence::of(&expr.left);
+            let left = left < precedence || (left == Precedence::Compare && precedence == left);
+            write_operand(out, &expr.left, left);
+            out.push(' ');
+            write_tokens(out, &expr.op);
+            out.push(' ');
+            write_operand(out, &expr.right, Precedence::of(&expr.right) <= precedence);
+        }
+        Expr::Range(expr) => {
+            if let Some(start) = &expr.from {
+                write_operand(out, start, Precedence::of(start) <= Precedence::Range);
+            }
+            write_tokens(out, &expr.limits);
+            if let Some(end) = &expr.to {
+                write_operand(out, end, Precedence::of(end) <= Precedence::Range);
+            }
+        }
+        Expr::Cast(expr) => {
+            write_operand(
+                out,
+                &expr.expr,
+                Precedence::of(&expr.expr) < Precedence::Cast,
+            );
+            out.push_str(" as ");
+            write_tokens(out, &expr.ty);
+        }
+        Expr::Field(_) | Expr::Index(_) | Expr::Try(_) | Expr::Call(_) | Expr::MethodCall(_) => {
+            write_postfix(out, expr);
+        }
+        Expr::Tuple(expr) => {
+            out.push('(');
+            write_list(out, &expr.elems);
+            if expr.elems.len() == 1 {
+                out.push(',');
+            }
+            out.push(')');
+        }
+        Expr::Array(expr) => {
+            out.push('[');
+            write_list(out, &expr.elems);
+            out.push(']');
+        }
+        Expr::Repeat(expr) => {
+            out.push('[');
+            write_expr(out, &expr.expr);
+            out.push_str("; ");
+            write_expr(out, &expr.len);
+            out.push(']');
+        }
+        expr => write_tokens(out, expr),
+    }
+}
+
+fn write_postfix(out: &mut String, expr: &Expr) {
+    match expr {
+        Expr::Field(expr) => {
+            // `1.0` would be a float
+            let int = matches!(&*expr.base, Expr::Lit(base) if matches!(base.lit, Lit::Int(_)));
+            let unnamed = matches!(expr.member, Member::Unnamed(_));
+            write_receiver(out, &expr.base, int && unnamed);
+            out.push('.');
+            write_tokens(out, &expr.member);
+        }
+        Expr::Index(expr) => {
+            write_receiver(out, &expr.expr, false);
+            out.push('[');
+            write_expr(out, &expr.index);
+            out.push(']');
+        }
+        Expr::Try(expr) => {
+            write_receiver(out, &expr.expr, false);
+            out.push('?');
+        }
+        Expr::Call(call) => {
+            write_receiver(out, &call.func, false);
+            out.push('(');
+            write_list(out, &call.args);
+            out.push(')');
+        }
+        Expr::MethodCall(call) => {
+            write_receiver(out, &call.receiver, false);
+            out.push('.');
+            write_tokens(out, &call.method);
+            if let Some(turbofish) = &call.turbofish {
+                write_tokens(out, turbofish);
+            }
+            out.push('(');
+            write_list(out, &call.args);
+            out.push(')');
+        }
+        expr => write_tokens(out, expr),
+    }
+}
+
+/// Writes the operand of a method call, field access, index and the like.
+fn write_receiver(out: &mut String, expr: &Expr, parens: bool) {
+    write_operand(
+        out,
+        expr,
+        parens || Precedence::of(expr) < Precedence::Postfix,
+    );
+}
+
+fn write_operand(out: &mut String, expr: &Expr, parens: bool) {
+    if parens {
+        out.push('(');
+        write_expr(out, expr);
+        out.push(')');
+    } else {
+        write_expr(out, expr);
+    }
+}
+
+fn write_list<'a>(out: &mut String, exprs: impl IntoIterator<Item = &'a Expr>) {
+    for (i, expr) in exprs.into_iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_expr(out, expr);
+    }
+}
+
+fn write_path(out: &mut String, path: &syn::Path) {
+    for (i, segment) in path.segments.iter().enumerate() {
+        if i > 0 || path.leading_colon.is_some() {
+            out.push_str("::");
+        }
+        push_fmt(out, format_args!("{}", segment.ident));
+        if !matches!(segment.arguments, PathArguments::None) {
+            write_tokens(out, &segment.arguments);
+        }
+    }
+}
+
+fn write_tokens(out: &mut String, tokens: &impl ToTokens) {
+    push_fmt(out, format_args!("{}", tokens.to_token_stream()));
+}
+
+mod to_expr {
+    use std::iter::FromIterator;
+
+    use syn::__private::TokenStream2;
+    use syn::{ext::IdentExt, parse::Parser, punctuated::Punctuated, Expr};
+
+    use crate::node::{Children, Hint, Node, NodeId, Range, Text, Tree};
+
+    impl Tree {
+        /// Lowers the node into the expression `prettyplease` lays out, or
+        /// `None` if it holds a name or literal that is not valid Rust, or
+        /// a node its builder gave a layout or comment of its own.
+        pub(crate) fn to_expr(&self, id: NodeId) -> Option<Expr> {
+            // `prettyplease` would break it over lines by width alone,
+            // leave out its comment and end it in a comma
+            if self.hint(id) != Hint::Auto
+                || self.comment(id).is_some()
+                || self.trailing_comma(id) == Some(false)
+            {
+                return None;
+            }
+            // every arm is a function of its own, which keeps this frame
+            // small for deeply nested values
+            match self.get(id) {
+                // the comments in it would be lost
+                Node::Verbatim(text) if self.str(text).contains("/*") => None,
+                // the guides of a tree are not Rust, `prettyplease` leaves
+                // the tokens of a macro as they are, and the count of the
+                // entries left out is a comment
+                Node::Branch(..) | Node::Vec(_) | Node::Concat(_) | Node::More(_) => None,
+                Node::Lit(text) | Node::Verbatim(text) => {
+                    // `prettyplease` panics on what syn could only parse
+                    // verbatim, and spaces out the tokens of a macro,
+                    // `date!(2023 - 11 - 14)`
+                    match syn::parse_str(self.str(text)).ok()? {
+                        Expr::Verbatim(_) | Expr::Macro(_) => None,
+                        expr => Some(expr),
+                    }
+                }
+                Node::Path(path) => path_expr(self.str(path)),
+                Node::Struct(name, fields) => self.struct_expr(self.str(name), fields),
+                Node::TupleStruct(name, fields) => self.call_expr(self.str(name), fields),
+                Node::Tuple(elems) => self.to_exprs(elems).map(tuple),
+                Node::List(elems) | Node::Table(elems) => self.to_exprs(elems).map(array),
+                Node::Set(entries) => self.to_exprs(entries).map(set),
+                Node::Map(entries) => self.to_exprs(entries).map(map),
+                Node::Elided => Some(elided()),
+                Node::Range(range) => self.range_expr(range),
+            }
+        }
+
+        /// Lowers the node into an expression for [`Formatter::value`](crate::Formatter::value),
+        /// writing what [`Tree::to_expr`] can't lower as best it can:
+        /// macros as macros, a tree as a tuple of its label and children,
+        /// and text that is not an expression as a string.
+        pub(crate) fn expr(&self, id: NodeId) -> Expr {
+            if let Some(expr) = self.to_expr(id) {
+                return expr;
+            }
+            match self.get(id) {
+                Node::Lit(text) | Node::Path(text) | Node::Verbatim(text) => {
+                    text_expr(self.str(text))
+                }
+                // those not named by a path are a tuple of their name and fields
+                Node::Struct(name, fields) => self
+                    .struct_with(self.str(name), fields, |field| Some(self.expr(field)))
+                    .unwrap_or_else(|| self.named_tuple(name, fields)),
+                Node::TupleStruct(name, fields) => call(self.str(name), self.lower_all(fields))
+                    .unwrap_or_else(|| self.named_tuple(name, fields)),
+                Node::Tuple(elems) => tuple(self.lower_all(elems)),
+                Node::List(elems) | Node::Table(elems) => array(self.lower_all(elems)),
+                Node::Vec(elems) => {
+                    let elems = self.lower_all(elems);
+                    syn::parse_quote!(vec![#(#elems),*])
+                }
+                Node::Concat(lines) => {
+                    let lines = self.lower_all(lines);
+                    syn::parse_quote!(concat!(#(#lines),*))
+                }
+                Node::Branch(label, children) => {
+                    let label = text_expr(self.str(label));
+                    tuple(vec![label, array(self.lower_all(children))])
+                }
+                Node::Set(entries) => set(self.lower_all(entries)),
+                Node::Map(entries) => map(self.lower_all(entries)),
+                Node::Elided | Node::More(_) => elided(),
+                Node::Range(range) => {
+                    let bound = |bound: NodeId| match self.expr(bound) {
+                        expr @ Expr::Range(_) => Box::new(syn::parse_quote!((#expr))),
+                        expr => Box::new(expr),
+                    };
+                    syn::ExprRange {
+                        attrs: vec![],
+                        from: range.start.map(bound),
+                        limits: if range.inclusive {
+                            syn::RangeLimits::Closed(syn::token::DotDotEq::default())
+                        } else {
+                            syn::RangeLimits::HalfOpen(syn::token::Dot2::default())
+                        },
+                        to: range.end.map(bound),
+                    }
+                    .into()
+                }
+            }
+        }
+
+        fn named_tuple(&self, name: Text, fields: Children) -> Expr {
+            let fields = tuple(self.lower_all(fields));
+            tuple(vec![text_expr(self.str(name)), fields])
+        }
+
+        fn lower_all(&self, children: Children) -> Vec<Expr> {
+            self.children(children)
+                .map(|child| self.expr(child))
+                .collect()
+        }
+
+        fn to_exprs(&self, children: Children) -> Option<Vec<Expr>> {
+            self.children(children)
+                .map(|child| self.to_expr(child))
+                .collect()
+        }
+
+        fn struct_expr(&self, name: &str, fields: Children) -> Option<Expr> {
+            self.struct_with(name, fields, |field| self.to_expr(field))
+        }
+
+        /// A struct expression of the fields, each lowered by `lower`.
+        fn struct_with(
+            &self,
+            name: &str,
+            fields: Children,
+            lower: impl Fn(NodeId) -> Option<Expr>,
+        ) -> Option<Expr> {
+            let elided = self
+                .children(fields)
+                .any(|field| self.get(field) == Node::Elided);
+            let mut values = Punctuated::new();
+            for field in self.children(fields) {
+                if self.get(field) == Node::Elided {
+                    continue;
+                }
+                values.push(syn::FieldValue {
+                    attrs: vec![],
+                    member: syn::Member::Named(ident(self.label(field))?),
+                    colon_token: Some(syn::token::Colon::default()),
+                    expr: lower(field)?,
+                });
+            }
+            let expr = syn::ExprStruct {
+                attrs: vec![],
+                path: path(name)?,
+                brace_token: syn::token::Brace::default(),
+                fields: values,
+                // an empty rest is printed as a bare `..`
+                dot2_token: elided.then(syn::token::Dot2::default),
+                rest: elided.then(|| Box::new(Expr::Verbatim(TokenStream2::new()))),
+            };
+            Some(expr.into())
+        }
+
+        fn call_expr(&self, name: &str, fields: Children) -> Option<Expr> {
+            call(name, self.to_exprs(fields)?)
+        }
+
+        fn range_expr(&self, range: Range) -> Option<Expr> {
+            let from = match range.start {
+                Some(start) => Some(self.bound(start)?),
+                None => None,
+            };
+            let to = match range.end {
+                Some(end) => Some(self.bound(end)?),
+                None => None,
+            };
+            let expr = syn::ExprRange {
+                attrs: vec![],
+                from,
+                limits: if range.inclusive {
+                    syn::RangeLimits::Closed(syn::token::DotDotEq::default())
+                } else {
+                    syn::RangeLimits::HalfOpen(syn::token::Dot2::default())
+                },
+                to,
+            };
+            Some(expr.into())
+        }
+
+        fn bound(&self, bound: NodeId) -> Option<Box<Expr>> {
+            let expr = match self.to_expr(bound)? {
+                // ranges do not chain
+                expr @ Expr::Range(_) => syn::ExprParen {
+                    attrs: vec![],
+                    paren_token: syn::token::Paren::default(),
+                    expr: Box::new(expr),
+                }
+                .into(),
+                expr => expr,
+            };
+            Some(Box::new(expr))
+        }
+    }
+
+    /// Parses a name as a path, such as `Foo` or `foo::Bar<T>`, or as an
+    /// identifier, which takes in keywords such as `true`.
+    fn path(name: &str) -> Option<syn::Path> {
+        match syn::parse_str(name) {
+            Ok(path) => Some(path),
+            Err(_) => ident(name).map(Into::into),
+        }
+    }
+
+    /// The expression `text` parses as, which leaves out its comments, or a
+    /// string of it if it is not one.
+    fn text_expr(text: &str) -> Expr {
+        syn::parse_str(text).unwrap_or_else(|_| {
+            let text = syn::LitStr::new(text, proc_macro2::Span::call_site());
+            syn::parse_quote!(#text)
+        })
+    }
+
+    fn path_expr(name: &str) -> Option<Expr> {
+        let expr = syn::ExprPath {
+            attrs: vec![],
+            qself: None,
+            path: path(name)?,
+        };
+        Some(expr.into())
+    }
+
+    fn ident(name: &str) -> Option<syn::Ident> {
+        syn::Ident::parse_any.parse_str(name).ok()
+    }
+
+    fn call(name: &str, args: Vec<Expr>) -> Option<Expr> {
+        let expr = syn::ExprCall {
+            attrs: vec![],
+            func: Box::new(path_expr(name)?),
+            paren_token: syn::token::Paren::default(),
+            args: args.into_iter().collect(),
+        };
+        Some(expr.into())
+    }
+
+    fn tuple(elems: Vec<Expr>) -> Expr {
+        syn::ExprTuple {
+            attrs: vec![],
+            paren_token: syn::token::Paren::default(),
+            elems: elems.into_iter().collect(),
+        }
+        .into()
+    }
+
+    fn set(entries: Vec<Expr>) -> Expr {
+        let mut stmts: Vec<_> = entries
+            .into_iter()
+            .map(|entry| syn::Stmt::Semi(entry, syn::token::Semi::default()))
+            .collect();
+        // remove the last semicolon
+        if let Some(syn::Stmt::Semi(entry, _)) = stmts.pop() {
+            stmts.push(syn::Stmt::Expr(entry));
+        }
+        block(stmts)
+    }
+
+    fn map(entries: Vec<Expr>) -> Expr {
+        let len = entries.len();
+        let mut entries = entries.into_iter();
+        let mut stmts = vec![];
+        while let (Some(key), Some(value)) = (entries.next(), entries.next()) {
+            let entry = syn::ExprAssign {
+                attrs: vec![],
+                left: Box::new(array([key])),
+                eq_token: syn::token::Eq::default(),
+                right: Box::new(value),
+            };
+            stmts.push(syn::Stmt::Semi(entry.into(), syn::token::Semi::default()));
+        }
+        // the rest of the entries were elided, which leaves a lone key
+        if len % 2 == 1 {
+            stmts.push(syn::Stmt::Expr(elided()));
+        }
+        block(stmts)
+    }
+
+    fn array(elems: impl IntoIterator<Item = Expr>) -> Expr {
+        syn::ExprArray {
+            attrs: vec![],
+            bracket_token: syn::token::Bracket::default(),
+            elems: Punctuated::from_iter(elems),
+        }
+        .into()
+    }
+
+    fn block(stmts: Vec<syn::Stmt>) -> Expr {
+        syn::ExprBlock {
+            attrs: vec![],
+            label: None,
+            block: syn::Block {
+                brace_token: syn::token::Brace::default(),
+                stmts,
+            },
+        }
+        .into()
+    }
+
+    fn elided() -> Expr {
+        syn::ExprRange {
+            attrs: vec![],
+            from: None,
+            limits: syn::RangeLimits::HalfOpen(syn::token::Dot2::default()),
+            to: None,
+        }
+        .into()
+    }
+}
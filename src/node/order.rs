@@ -0,0 +1,104 @@
+//! Ordering captured values by what they hold, for sorting the entries of
+//! collections that are iterated in no particular order.
+
+use std::cmp::Ordering;
+
+use super::{Node, NodeId, Tree};
+
+impl Tree {
+    /// Orders two captured values: number literals by their value, paths,
+    /// strings and other literals by their text, and everything else by its
+    /// name and then its children, one after another. Values of different
+    /// shapes are ordered by their shape.
+    pub(crate) fn cmp(&self, a: NodeId, b: NodeId) -> Ordering {
+        match (self.get(a), self.get(b)) {
+            (Node::Lit(a), Node::Lit(b)) => {
+                let (a, b) = (self.str(a), self.str(b));
+                let by_value = match (number(a), number(b)) {
+                    (Some(x), Some(y)) => x.partial_cmp(&y),
+                    _ => None,
+                };
+                by_value.unwrap_or(Ordering::Equal).then_with(|| a.cmp(b))
+            }
+            (Node::Path(a), Node::Path(b)) | (Node::Verbatim(a), Node::Verbatim(b)) => {
+                self.str(a).cmp(self.str(b))
+            }
+            (Node::Struct(a, x), Node::Struct(b, y))
+            | (Node::TupleStruct(a, x), Node::TupleStruct(b, y))
+            | (Node::Branch(a, x), Node::Branch(b, y)) => self
+                .str(a)
+                .cmp(self.str(b))
+                .then_with(|| self.cmp_all(self.children(x), self.children(y))),
+            (Node::Tuple(x), Node::Tuple(y))
+            | (Node::List(x), Node::List(y))
+            | (Node::Vec(x), Node::Vec(y))
+            | (Node::Concat(x), Node::Concat(y))
+            | (Node::Table(x), Node::Table(y))
+            | (Node::Set(x), Node::Set(y))
+            | (Node::Map(x), Node::Map(y)) => self.cmp_all(self.children(x), self.children(y)),
+            (Node::Range(a), Node::Range(b)) => self
+                .cmp_all(a.start, b.start)
+                .then_with(|| self.cmp_all(a.end, b.end))
+                .then(a.inclusive.cmp(&b.inclusive)),
+            (a, b) => shape(a).cmp(&shape(b)),
+        }
+    }
+
+    /// Orders two sequences of values lexicographically.
+    fn cmp_all(
+        &self,
+        a: impl IntoIterator<Item = NodeId>,
+        b: impl IntoIterator<Item = NodeId>,
+    ) -> Ordering {
+        let mut b = b.into_iter();
+        for a in a {
+            let Some(b) = b.next() else {
+                return Ordering::Greater;
+            };
+            match self.cmp(a, b) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            }
+        }
+        if b.next().is_some() {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+/// The value of a number literal, `-1_000i32`, without its separators and
+/// type suffix.
+fn number(lit: &str) -> Option<f64> {
+    let digits = lit.replace('_', "");
+    let suffix = ["i", "u", "f"]
+        .iter()
+        .filter_map(|ty| digits.rfind(ty))
+        .min()
+        .unwrap_or(digits.len());
+    digits[..suffix].parse().ok()
+}
+
+/// The rank of each shape of node, for ordering values of different shapes.
+fn shape(node: Node) -> u8 {
+    match node {
+        Node::Lit(_) => 0,
+        Node::Path(_) => 1,
+        Node::Struct(..) => 2,
+        Node::TupleStruct(..) => 3,
+        Node::Tuple(_) => 4,
+        Node::List(_) => 5,
+        Node::Vec(_) => 6,
+        Node::Concat(_) => 7,
+        Node::Table(_) => 8,
+        Node::Branch(..) => 9,
+        Node::Set(_) => 10,
+        Node::Map(_) => 11,
+        Node::Range(_) => 12,
+        Node::Verbatim(_) => 13,
+        // entries left out go last
+        Node::Elided => 14,
+        Node::More(_) => 15,
+    }
+}
@@ -0,0 +1,252 @@
+//! Capturing long slices of values and map entries on the rayon thread pool.
+//!
+//! Every chunk of the slice is captured into a tree of its own, which is
+//! then grafted onto the end of the main one.
+
+use rayon::prelude::*;
+
+use super::{Children, Entry, Node, NodeId, Range, Text, Tree};
+use crate::DebugPls;
+
+/// Below this many values, splitting the work up costs more than it saves.
+const MIN_PARALLEL_LEN: usize = 1 << 14;
+
+impl Tree {
+    /// Captures every value and appends them to the end of `children`, like
+    /// [`Tree::append_all`], spreading the work over the rayon thread pool.
+    pub(crate) fn append_slice<T: DebugPls + Sync>(
+        &mut self,
+        children: &mut Children,
+        values: &[T],
+    ) {
+        match self.chunk_len(values.len()) {
+            Some(chunk_len) => {
+                self.append_chunks(children, values, chunk_len, |tree, children, chunk| {
+                    tree.append_all(children, chunk);
+                });
+            }
+            None => self.append_all(children, values),
+        }
+    }
+
+    /// Captures the key and value of every entry and appends them to the end
+    /// of `children`, as [`DebugMap`](crate::DebugMap) does, spreading the
+    /// work over the available threads.
+    pub(crate) fn append_pairs<K: DebugPls + Sync, V: DebugPls + Sync>(
+        &mut self,
+        children: &mut Children,
+        entries: &[(K, V)],
+    ) {
+        match self.chunk_len(entries.len()) {
+            Some(chunk_len) => {
+                self.append_chunks(children, entries, chunk_len, Tree::append_all_pairs);
+            }
+            None => self.append_all_pairs(children, entries),
+        }
+    }
+
+    /// Captures every entry on this thread, for [`Tree::append_pairs`].
+    fn append_all_pairs<K: DebugPls, V: DebugPls>(
+        &mut self,
+        children: &mut Children,
+        entries: &[(K, V)],
+    ) {
+        for (key, value) in entries {
+            let key = self.process(key);
+            if self.get(key) == Node::Elided {
+                // the rest of the map is marked by a lone key
+                return self.elide(children);
+            }
+            self.link(children, key);
+            let value = self.capture_node(value);
+            self.link(children, value);
+        }
+    }
+
+    /// How long each chunk captured in parallel is, or `None` if `len`
+    /// values are captured on this thread alone.
+    fn chunk_len(&self, len: usize) -> Option<usize> {
+        let threads = rayon::current_num_threads();
+        // the limit is only kept track of by a single tree
+        if self.limit.is_some() || threads == 1 || len < MIN_PARALLEL_LEN {
+            return None;
+        }
+        Some(len / threads + 1)
+    }
+
+    /// Captures the chunks of `values` in parallel, with `capture`.
+    fn append_chunks<T: Sync>(
+        &mut self,
+        children: &mut Children,
+        values: &[T],
+        chunk_len: usize,
+        capture: fn(&mut Tree, &mut Children, &[T]),
+    ) {
+        let (shared, depth, style) = (&self.shared, self.depth, self.style);
+        let chunks: Vec<_> = values
+            .par_chunks(chunk_len)
+            .map(|chunk| {
+                // carry on from where the slice is nested, which also
+                // catches cycles back out of it
+                let mut tree = Tree {
+                    shared: shared.clone(),
+                    depth,
+                    style,
+                    ..Tree::default()
+                };
+                let mut children = Children::default();
+                capture(&mut tree, &mut children, chunk);
+                (tree, children)
+            })
+            .collect();
+        for (tree, chunk) in chunks {
+            let chunk = self.graft(tree, chunk);
+            match children.last {
+                Some(last) => self.entries[last.0].next = chunk.first,
+                None => children.first = chunk.first,
+            }
+            children.last = chunk.last;
+            children.len += chunk.len;
+        }
+    }
+
+    /// Moves every node of `other` onto the end of this tree, returning where
+    /// `children` of `other` ended up.
+    fn graft(&mut self, other: Tree, children: Children) -> Children {
+        let shift = Shift {
+            ids: self.entries.len(),
+            text: self.text.len(),
+        };
+        self.text.push_str(&other.text);
+        self.entries
+            .extend(other.entries.into_iter().map(|entry| Entry {
+                node: shift.node(entry.node),
+                label: shift.text(entry.label),
+                next: entry.next.map(|id| shift.id(id)),
+                hint: entry.hint,
+                comment: shift.text(entry.comment),
+                trailing_comma: entry.trailing_comma,
+            }));
+        shift.children(children)
+    }
+}
+
+/// How far the nodes of a grafted tree moved.
+#[derive(Clone, Copy)]
+struct Shift {
+    ids: usize,
+    text: usize,
+}
+
+impl Shift {
+    fn node(self, node: Node) -> Node {
+        match node {
+            Node::Lit(text) => Node::Lit(self.text(text)),
+            Node::Path(text) => Node::Path(self.text(text)),
+            Node::Struct(name, fields) => Node::Struct(self.text(name), self.children(fields)),
+            Node::TupleStruct(name, fields) => {
+                Node::TupleStruct(self.text(name), self.children(fields))
+            }
+            Node::Tuple(elems) => Node::Tuple(self.children(elems)),
+            Node::List(elems) => Node::List(self.children(elems)),
+            Node::Vec(elems) => Node::Vec(self.children(elems)),
+            Node::Concat(lines) => Node::Concat(self.children(lines)),
+            Node::Table(rows) => Node::Table(self.children(rows)),
+            Node::Branch(label, children) => {
+                Node::Branch(self.text(label), self.children(children))
+            }
+            Node::Set(entries) => Node::Set(self.children(entries)),
+            Node::Map(entries) => Node::Map(self.children(entries)),
+            Node::Range(Range {
+                start,
+                end,
+                inclusive,
+            }) => Node::Range(Range {
+                start: start.map(|id| self.id(id)),
+                end: end.map(|id| self.id(id)),
+                inclusive,
+            }),
+            Node::Verbatim(text) => Node::Verbatim(self.text(text)),
+            Node::Elided => Node::Elided,
+            Node::More(more) => Node::More(more),
+        }
+    }
+
+    fn id(self, id: NodeId) -> NodeId {
+        NodeId(self.ids + id.0)
+    }
+
+    fn text(self, text: Text) -> Text {
+        Text {
+            start: self.text + text.start,
+            end: self.text + text.end,
+        }
+    }
+
+    fn children(self, children: Children) -> Children {
+        Children {
+            first: children.first.map(|id| self.id(id)),
+            last: children.last.map(|id| self.id(id)),
+            len: children.len,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "pretty"))]
+mod tests {
+    use std::{collections::BTreeMap, ops::Range};
+
+    use crate::{
+        node::{Children, Node},
+        pretty, DebugPls, Formatter,
+    };
+
+    struct Chunked<'a, T>(&'a [T]);
+
+    impl<T: DebugPls + Sync> DebugPls for Chunked<'_, T> {
+        fn fmt(&self, f: Formatter<'_>) {
+            let mut elems = Children::default();
+            f.tree
+                .append_chunks(&mut elems, self.0, 100, |tree, children, chunk| {
+                    tree.append_all(children, chunk);
+                });
+            f.write(Node::List(elems));
+        }
+    }
+
+    #[test]
+    fn matches_sequential() {
+        let values: Vec<(u8, String, Option<Range<u8>>)> = (0..=u8::MAX)
+            .cycle()
+            .take(1234)
+            .map(|i| (i, format!("value {i}"), Some(0..i).filter(|_| i % 3 == 0)))
+            .collect();
+        assert_eq!(
+            pretty(&Chunked(&values)).to_string(),
+            pretty(&values).to_string()
+        );
+    }
+
+    struct ChunkedMap<'a, K, V>(&'a [(K, V)]);
+
+    impl<K: DebugPls + Sync, V: DebugPls + Sync> DebugPls for ChunkedMap<'_, K, V> {
+        fn fmt(&self, f: Formatter<'_>) {
+            let mut entries = Children::default();
+            f.tree
+                .append_chunks(&mut entries, self.0, 100, |tree, children, chunk| {
+                    tree.append_all_pairs(children, chunk);
+                });
+            f.write(Node::Map(entries));
+        }
+    }
+
+    #[test]
+    fn pairs_match_sequential() {
+        let entries: BTreeMap<u16, Vec<u16>> = (0..1234).map(|i| (i, vec![i; 3])).collect();
+        let pairs: Vec<_> = entries.clone().into_iter().collect();
+        assert_eq!(
+            pretty(&ChunkedMap(&pairs)).to_string(),
+            pretty(&entries).to_string()
+        );
+    }
+}
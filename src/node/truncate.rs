@@ -0,0 +1,91 @@
+//! Keeping collections to their first few entries.
+
+use super::{Children, Node, NodeId, Tree};
+use crate::DebugPls;
+
+/// How many entries of a collection are kept, and how many were left out.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Truncate {
+    pub(crate) max: Option<usize>,
+    more: usize,
+    /// Whether the entries left out could not be counted, for coming from
+    /// an iterator that may never end
+    endless: bool,
+}
+
+impl Truncate {
+    /// How many more entries a collection of `len` entries keeps.
+    pub(crate) fn room(&self, len: usize) -> usize {
+        self.max.map_or(usize::MAX, |max| max.saturating_sub(len))
+    }
+
+    /// Whether a collection of `len` entries has all the entries it keeps.
+    pub(crate) fn full(&self, len: usize) -> bool {
+        self.room(len) == 0
+    }
+
+    /// Counts an entry that was left out.
+    pub(crate) fn skip(&mut self) {
+        self.more += 1;
+    }
+
+    /// Counts the entries left in `values`, without looking at any of them
+    /// when the iterator knows how many it has.
+    pub(crate) fn skip_all(&mut self, mut values: impl Iterator) {
+        match values.size_hint() {
+            (lower, Some(upper)) if lower == upper => self.more += lower,
+            (_, Some(_)) => self.more += values.by_ref().count(),
+            (_, None) => self.endless |= values.next().is_some(),
+        }
+    }
+}
+
+impl Tree {
+    /// How many entries the collection written at `id` keeps, the number a
+    /// field marked `#[dbg_pls(truncate = N)]` was given if it is that
+    /// field, or else all of them.
+    pub(crate) fn truncation(&self, id: NodeId) -> Truncate {
+        Truncate {
+            max: self
+                .truncation
+                .and_then(|(at, max)| (at == id).then_some(max)),
+            ..Truncate::default()
+        }
+    }
+
+    /// Captures every value and appends them to the end of `children`, like
+    /// [`Tree::append_all`], counting the ones past those that `truncate`
+    /// keeps.
+    pub(crate) fn append_truncated<D: DebugPls>(
+        &mut self,
+        children: &mut Children,
+        truncate: &mut Truncate,
+        values: impl IntoIterator<Item = D>,
+    ) {
+        if truncate.max.is_none() {
+            return self.append_all(children, values);
+        }
+        let mut values = values.into_iter();
+        let room = truncate.room(self.children(*children).len());
+        self.append_all(children, values.by_ref().take(room));
+        truncate.skip_all(values);
+    }
+
+    /// Marks the end of `children` with the number of entries `truncate`
+    /// left out, unless the limit elided the rest already.
+    pub(crate) fn truncated(&mut self, children: &mut Children, truncate: Truncate) {
+        if truncate.endless {
+            return self.elide(children);
+        }
+        if truncate.more == 0 {
+            return;
+        }
+        if let Some(last) = children.last {
+            if self.get(last) == Node::Elided {
+                return;
+            }
+        }
+        let child = self.push(Node::More(truncate.more));
+        self.link(children, child);
+    }
+}
@@ -0,0 +1,319 @@
+//! Fields of derived implementations that are not formatted in full.
+
+use std::convert::TryInto;
+
+use crate::{
+    node::{Radix, Style},
+    DebugPls, Formatter,
+};
+
+/// Implementation detail for `#[dbg_pls(opaque)]` fields
+pub struct Opaque(&'static str);
+
+impl Opaque {
+    #[must_use]
+    pub fn of<T: ?Sized>(_: &T) -> Self {
+        Opaque(std::any::type_name::<T>())
+    }
+}
+
+impl DebugPls for Opaque {
+    fn fmt(&self, f: Formatter<'_>) {
+        // a string, like `"<cycle>"`, to keep the output valid Rust
+        f.write_lit_with(|buf| {
+            buf.push_str("\"<");
+            buf.push_str(&short_name(self.0));
+            buf.push_str(">\"");
+        });
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(len_only)]` fields
+pub struct LenOnly {
+    name: &'static str,
+    len: usize,
+}
+
+impl LenOnly {
+    #[must_use]
+    pub fn of<'a, C: ?Sized>(collection: &'a C) -> Self
+    where
+        &'a C: IntoIterator,
+        <&'a C as IntoIterator>::IntoIter: ExactSizeIterator,
+    {
+        LenOnly {
+            name: std::any::type_name::<C>(),
+            len: collection.into_iter().len(),
+        }
+    }
+}
+
+impl DebugPls for LenOnly {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_len(&short_name(self.name), self.len);
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(len_only = N)]` fields
+pub struct LenOnlyAbove<'a, C: ?Sized> {
+    collection: &'a C,
+    max: usize,
+}
+
+impl<'a, C: ?Sized> LenOnlyAbove<'a, C> {
+    #[must_use]
+    pub fn of(collection: &'a C, max: usize) -> Self {
+        LenOnlyAbove { collection, max }
+    }
+}
+
+impl<'a, C: ?Sized + DebugPls> DebugPls for LenOnlyAbove<'a, C>
+where
+    &'a C: IntoIterator,
+    <&'a C as IntoIterator>::IntoIter: ExactSizeIterator,
+{
+    fn fmt(&self, f: Formatter<'_>) {
+        let summary = LenOnly::of(self.collection);
+        if summary.len > self.max {
+            summary.fmt(f);
+        } else {
+            self.collection.fmt(f);
+        }
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(bytes)]` fields
+pub struct Bytes<'a, T> {
+    value: &'a T,
+}
+
+impl<'a, T> Bytes<'a, T> {
+    #[must_use]
+    pub fn of(value: &'a T) -> Self {
+        Bytes { value }
+    }
+}
+
+impl<T: Copy + TryInto<u64> + DebugPls> DebugPls for Bytes<'_, T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        // such as a negative size, which has no unit to read it in
+        match (*self.value).try_into() {
+            Ok(bytes) => f.write_byte_size(bytes, std::any::type_name::<T>()),
+            Err(_) => self.value.fmt(f),
+        }
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(vec)]` fields
+pub struct VecMacro<'a, T> {
+    value: &'a T,
+}
+
+impl<'a, T> VecMacro<'a, T> {
+    #[must_use]
+    pub fn of(value: &'a T) -> Self {
+        VecMacro { value }
+    }
+}
+
+impl<T: DebugPls> DebugPls for VecMacro<'_, T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_styled(
+            |style| Style {
+                vec_macros: true,
+                ..style
+            },
+            self.value,
+        );
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(raw)]` fields
+pub struct RawStrings<'a, T> {
+    value: &'a T,
+}
+
+impl<'a, T> RawStrings<'a, T> {
+    #[must_use]
+    pub fn of(value: &'a T) -> Self {
+        RawStrings { value }
+    }
+}
+
+impl<T: DebugPls> DebugPls for RawStrings<'_, T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_styled(
+            |style| Style {
+                raw_strings: true,
+                ..style
+            },
+            self.value,
+        );
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(truncate = N)]` fields
+pub struct Truncated<'a, T: ?Sized> {
+    value: &'a T,
+    max: usize,
+}
+
+impl<'a, T: ?Sized> Truncated<'a, T> {
+    #[must_use]
+    pub fn of(value: &'a T, max: usize) -> Self {
+        Truncated { value, max }
+    }
+}
+
+impl<T: ?Sized + DebugPls> DebugPls for Truncated<'_, T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_truncated(self.max, &self.value);
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(hex)]` fields
+pub struct Hex<'a, T> {
+    value: &'a T,
+}
+
+impl<'a, T> Hex<'a, T> {
+    #[must_use]
+    pub fn of(value: &'a T) -> Self {
+        Hex { value }
+    }
+}
+
+impl<T: DebugPls> DebugPls for Hex<'_, T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_styled(
+            |style| Style {
+                radix: Radix::Hex,
+                digit_separators: true,
+                ..style
+            },
+            self.value,
+        );
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(binary)]` fields
+pub struct Binary<'a, T> {
+    value: &'a T,
+}
+
+impl<'a, T> Binary<'a, T> {
+    #[must_use]
+    pub fn of(value: &'a T) -> Self {
+        Binary { value }
+    }
+}
+
+impl<T: DebugPls> DebugPls for Binary<'_, T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_styled(
+            |style| Style {
+                radix: Radix::Binary,
+                digit_separators: true,
+                ..style
+            },
+            self.value,
+        );
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(display)]` fields
+pub struct AsDisplay<'a, T: ?Sized> {
+    value: &'a T,
+}
+
+impl<'a, T: ?Sized> AsDisplay<'a, T> {
+    #[must_use]
+    pub fn of(value: &'a T) -> Self {
+        AsDisplay { value }
+    }
+}
+
+impl<T: ?Sized + std::fmt::Display> DebugPls for AsDisplay<'_, T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.write_display(&self.value);
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(debug)]` fields
+pub struct AsDebug<'a, T: ?Sized> {
+    value: &'a T,
+}
+
+impl<'a, T: ?Sized> AsDebug<'a, T> {
+    #[must_use]
+    pub fn of(value: &'a T) -> Self {
+        AsDebug { value }
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> DebugPls for AsDebug<'_, T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        // most `Debug` impls write Rust, which is laid out like any other
+        // value once it is parsed
+        #[cfg(feature = "syn")]
+        if let Ok(expr) = syn::parse_str::<syn::Expr>(&format!("{:?}", self.value)) {
+            return f.write_expr(expr);
+        }
+        f.write_debug(&self.value);
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(with = "...")]` fields
+pub struct FormatWith<'a, T: ?Sized, F> {
+    value: &'a T,
+    fmt: F,
+}
+
+impl<'a, T: ?Sized, F: Fn(&T, Formatter<'_>)> FormatWith<'a, T, F> {
+    #[must_use]
+    pub fn of(value: &'a T, fmt: F) -> Self {
+        FormatWith { value, fmt }
+    }
+}
+
+impl<T: ?Sized, F: Fn(&T, Formatter<'_>)> DebugPls for FormatWith<'_, T, F> {
+    fn fmt(&self, f: Formatter<'_>) {
+        (self.fmt)(self.value, f);
+    }
+}
+
+/// Implementation detail for `#[dbg_pls(generics)]` types, which writes the
+/// name of the type with its generic arguments, `Wrapper::<Vec<u8>>`
+#[must_use]
+pub fn generic_name(name: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut out = format!("{name}::<");
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        out.push_str(&short_name(&arg.to_string()));
+    }
+    out.push('>');
+    out
+}
+
+/// The type name without the paths to the types in it,
+/// `Vec<Frame>` rather than `alloc::vec::Vec<app::Frame>`.
+fn short_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment = 0;
+    let mut rest = name;
+    while let Some(ch) = rest.chars().next() {
+        if rest.starts_with("::") {
+            short.truncate(segment);
+            rest = &rest[2..];
+            continue;
+        }
+        if !(ch.is_alphanumeric() || ch == '_') {
+            segment = short.len() + ch.len_utf8();
+        }
+        short.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    short
+}
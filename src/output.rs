@@ -0,0 +1,240 @@
+//! Writing the output of the printing macros.
+
+use std::{
+    fmt,
+    io::{self, StderrLock, Write},
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
+
+use once_cell::sync::Lazy;
+
+static SINK: Lazy<RwLock<Arc<dyn Sink>>> = Lazy::new(|| RwLock::new(Arc::new(Stderr)));
+
+/// Implementation detail for the printing macros
+///
+/// # Panics
+///
+/// If the sink does, as [`Stderr`] does when writing fails.
+pub fn print(
+    file: &'static str,
+    line: u32,
+    expr: &'static str,
+    shown: &dyn fmt::Display,
+    value: &dyn fmt::Display,
+) {
+    // laid out in full before it is handed to the sink, so that a dump is
+    // always written at once
+    let value = value.to_string();
+    let text = format!("[{file}:{line}] {shown} => {value}\n");
+    // not held while writing, so that a sink can dump values of its own
+    let sink = Arc::clone(&SINK.read().unwrap_or_else(PoisonError::into_inner));
+    sink.write(&Dump {
+        file,
+        line,
+        expr,
+        value,
+        text,
+    });
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// Receives the dumps of [`pretty!`](crate::pretty!),
+/// [`color!`](crate::color!) and [`dbg_pls_async!`](crate::dbg_pls_async!)
+/// once it is installed with [`set_sink`], in place of [`Stderr`].
+///
+/// ```rust
+/// use dbg_pls::{Dump, Sink};
+///
+/// struct Stdout;
+///
+/// impl Sink for Stdout {
+///     fn write(&self, dump: &Dump) {
+///         println!("{}:{}: {} = {}", dump.file(), dump.line(), dump.expr(), dump.value());
+///     }
+/// }
+///
+/// dbg_pls::set_sink(Stdout);
+/// ```
+pub trait Sink: Send + Sync {
+    /// Writes one dump. The dumps of different threads can be written at
+    /// the same time.
+    fn write(&self, dump: &Dump);
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// Makes `sink` receive every dump from now on.
+pub fn set_sink(sink: impl Sink + 'static) {
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(sink);
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// One value printed by the printing macros, and where it was printed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dump {
+    file: &'static str,
+    line: u32,
+    expr: &'static str,
+    value: String,
+    text: String,
+}
+
+impl Dump {
+    /// The file of the macro call, as in [`file!`].
+    #[must_use]
+    pub fn file(&self) -> &'static str {
+        self.file
+    }
+
+    /// The line of the macro call, as in [`line!`].
+    #[must_use]
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The source of the expression that was printed.
+    #[must_use]
+    pub fn expr(&self) -> &'static str {
+        self.expr
+    }
+
+    /// The value, laid out as it is printed, with the escape codes of
+    /// [`color!`](crate::color!) if it was highlighted.
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The whole dump as it is written to stderr, such as
+    /// `[src/main.rs:2] a * 2 => 4\n`.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// The [`Sink`] that dumps are written to unless another one is set,
+/// which writes them to stderr.
+///
+/// # Panics
+///
+/// If writing to stderr fails, as `eprintln!` does.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Stderr;
+
+impl Sink for Stderr {
+    fn write(&self, dump: &Dump) {
+        if let Err(err) = io::stderr().lock().write_all(dump.text.as_bytes()) {
+            panic!("failed printing to stderr: {}", err);
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// A [`Sink`] that keeps the dumps, to check in a test what was printed.
+///
+/// ```rust
+/// use dbg_pls::{pretty, Capture, Stderr};
+///
+/// let capture = Capture::new();
+/// dbg_pls::set_sink(capture.clone());
+/// pretty!(1 + 1);
+/// dbg_pls::set_sink(Stderr);
+///
+/// let dumps = capture.take();
+/// assert_eq!((dumps[0].expr(), dumps[0].value()), ("1 + 1", "2"));
+/// ```
+///
+/// Clones share the dumps they keep. Every dump of the process goes to the
+/// sink, including those of other tests that run at the same time.
+#[derive(Clone, Debug, Default)]
+pub struct Capture {
+    dumps: Arc<Mutex<Vec<Dump>>>,
+}
+
+impl Capture {
+    /// A sink that has not kept any dumps yet.
+    #[must_use]
+    pub fn new() -> Self {
+        Capture::default()
+    }
+
+    /// The dumps written so far.
+    #[must_use]
+    pub fn dumps(&self) -> Vec<Dump> {
+        self.dumps
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The dumps written so far, leaving none behind.
+    pub fn take(&self) -> Vec<Dump> {
+        std::mem::take(&mut *self.dumps.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl Sink for Capture {
+    fn write(&self, dump: &Dump) {
+        self.dumps
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(dump.clone());
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// Holds back the output of every other thread, including the dumps of
+/// [`pretty!`](crate::pretty!), [`color!`](crate::color!) and
+/// [`dbg_pls_async!`](crate::dbg_pls_async!), until the lock is dropped.
+///
+/// Every dump is written at once already. This keeps several of them, and
+/// anything else written to stderr in between, together:
+///
+/// ```rust
+/// # use dbg_pls::pretty;
+/// use std::io::Write;
+///
+/// let mut lock = dbg_pls::lock_output();
+/// let (a, b) = pretty!(1, 2);
+/// writeln!(lock, "a + b = {}", a + b).unwrap();
+/// drop(lock);
+/// ```
+///
+/// The lock is the one of [`std::io::stderr`], so the printing macros
+/// and `eprintln!` can still be used on the thread that holds it. It only
+/// holds back dumps that go to the [`Stderr`] sink.
+pub fn lock_output() -> StderrLock<'static> {
+    io::stderr().lock()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_sink, Capture, Stderr};
+
+    #[test]
+    fn capture() {
+        let capture = Capture::new();
+        set_sink(capture.clone());
+        let value = crate::pretty!(vec![Some(1), None]);
+        set_sink(Stderr);
+
+        assert_eq!(value, [Some(1), None]);
+        let dump = capture
+            .take()
+            .into_iter()
+            .find(|dump| dump.file() == file!())
+            .unwrap();
+        assert_eq!(dump.expr(), "vec![Some(1), None]");
+        assert_eq!(dump.value(), "[Some(1), None]");
+        assert_eq!(
+            dump.text(),
+            format!(
+                "[{}:{}] vec![Some(1), None] => [Some(1), None]\n",
+                file!(),
+                dump.line()
+            ),
+        );
+        assert!(capture.dumps().is_empty());
+    }
+}
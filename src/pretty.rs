@@ -1,8 +1,38 @@
-use syn::__private::Span;
+use std::time::{Duration, Instant};
 
-use crate::{DebugPls, Formatter};
+use once_cell::unsync::OnceCell;
+
+use crate::{
+    node::{Hint, Limit, Style, Tree},
+    DebugPls,
+};
+
+#[cfg(not(feature = "syn-backend"))]
+pub(crate) use crate::layout::pretty_string;
+
+#[cfg(feature = "syn-backend")]
+pub(crate) fn pretty_string(tree: &Tree) -> String {
+    // `prettyplease` can't lay out names that aren't paths, such as
+    // `dyn Trait`, nor some of the expressions it parses, so those values
+    // are left to the built-in layout, as are values laid out without
+    // trailing commas, which `prettyplease` always writes
+    if tree.style.no_trailing_commas {
+        return crate::layout::pretty_string(tree);
+    }
+    match tree.to_expr(Tree::ROOT).and_then(|expr| unparse(&expr)) {
+        Some(output) => output,
+        None => crate::layout::pretty_string(tree),
+    }
+}
+
+/// Lays out `expr` with `prettyplease`, or returns `None` if it panicked
+/// on an expression it does not support, such as `const { 1 }`.
+#[cfg(feature = "syn-backend")]
+pub(crate) fn unparse(expr: &syn::Expr) -> Option<String> {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use syn::__private::Span;
 
-pub(crate) fn pretty_string(expr: syn::Expr) -> String {
     // unparse requires a `syn::File`, so we are forced to wrap
     // our expression in some junk. This is equivalent to
     // ```rust
@@ -14,7 +44,7 @@ pub(crate) fn pretty_string(expr: syn::Expr) -> String {
         shebang: None,
         attrs: vec![],
         items: vec![syn::Item::Const(syn::ItemConst {
-            expr: Box::new(expr),
+            expr: Box::new(expr.clone()),
             // junk...
             attrs: vec![],
             vis: syn::Visibility::Inherited,
@@ -29,33 +59,613 @@ pub(crate) fn pretty_string(expr: syn::Expr) -> String {
             semi_token: syn::token::Semi::default(),
         })],
     };
-    let output = prettyplease::unparse(&file);
+    let output = panic::catch_unwind(AssertUnwindSafe(|| prettyplease::unparse(&file))).ok()?;
 
     // strip out the junk
     let output = &output[14..];
     let output = &output[..output.len() - 2];
-    textwrap::dedent(output)
+    Some(textwrap::dedent(output))
+}
+
+/// Implementation detail for `#[dbg_pls(std_debug)]` types, whose `Debug`
+/// impl writes them on one line for `{:?}`, and pretty printed for `{:#?}`
+///
+/// # Errors
+///
+/// If writing to `f` fails.
+pub fn std_debug(value: &dyn DebugPls, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let pretty = pretty(value);
+    if f.alternate() {
+        std::fmt::Debug::fmt(&pretty, f)
+    } else {
+        std::fmt::Debug::fmt(&pretty.compact(), f)
+    }
+}
+
+/// Lays out the source of an expression with the built-in layout engine,
+/// as it lays out values, or returns `None` if it is not one. Source is
+/// only parsed with the `syn` feature.
+#[cfg(not(feature = "syn-backend"))]
+pub(crate) fn layout_source(source: &str) -> Option<String> {
+    #[cfg(feature = "syn")]
+    {
+        let expr: syn::Expr = syn::parse_str(source).ok()?;
+        let value = crate::from_fn(|f| f.write_expr(expr.clone()));
+        Some(pretty(&value).to_string())
+    }
+    #[cfg(not(feature = "syn"))]
+    {
+        let _ = source;
+        None
+    }
 }
 
 /// Implementation detail for the `pretty!` macro
 pub struct Str<'a>(pub &'a str);
 
-impl<'a> std::fmt::Display for Str<'a> {
+impl std::fmt::Display for Str<'_> {
+    #[cfg(not(feature = "syn-backend"))]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // written as it is if it can't be laid out
+        match layout_source(self.0) {
+            Some(output) => f.write_str(&output),
+            None => f.write_str(self.0),
+        }
+    }
+
+    #[cfg(feature = "syn-backend")]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // written as it is if it can't be laid out
+        match syn::parse_str(self.0).ok().and_then(|expr| unparse(&expr)) {
+            Some(output) => f.write_str(&output),
+            None => f.write_str(self.0),
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// Limits on how much of a value gets printed.
+///
+/// Once a limit is reached, the rest of the value is elided with `..`,
+/// which keeps the output valid Rust.
+///
+/// ```
+/// use dbg_pls::{pretty, Budget};
+///
+/// let numbers: Vec<u32> = (0..1000).collect();
+/// assert_eq!(
+///     format!("{}", pretty(&numbers).budget(Budget::new().bytes(20))),
+///     "[0, 1, 2, 3, 4, 5, ..]",
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Budget {
+    bytes: Option<usize>,
+    time: Option<Duration>,
+    depth: Option<usize>,
+}
+
+impl Budget {
+    /// A budget without any limits
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops after about `bytes` of output.
+    ///
+    /// The size is only estimated while the value is formatted: indentation
+    /// is not counted, and a literal is never cut short, so a long string can
+    /// take the output over the limit.
+    #[must_use]
+    pub fn bytes(mut self, bytes: usize) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    /// Stops after spending about `time` on formatting the value.
+    ///
+    /// The time is only checked every so often, in between the
+    /// [`DebugPls`] implementations being called. Laying out the part of the
+    /// value that made it in comes on top of this.
+    #[must_use]
+    pub fn time(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// Stops at values nested `depth` levels deep.
+    ///
+    /// Even without a budget, values are only formatted 1024 levels
+    /// deep (128 with the `syn-backend` feature), so that deeply nested
+    /// values can't overflow the stack.
+    ///
+    /// ```
+    /// use dbg_pls::{pretty, Budget};
+    ///
+    /// let nested = Some(Some(Some(Some(1))));
+    /// assert_eq!(
+    ///     format!("{}", pretty(&nested).budget(Budget::new().depth(2))),
+    ///     "Some(Some(..))",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    fn limit(self) -> Limit {
+        Limit {
+            len: self.bytes,
+            deadline: self.time.and_then(|time| Instant::now().checked_add(time)),
+            depth: self.depth,
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// A [`DebugPls`] type wrapped into a [`std::fmt::Debug`] type, see [`pretty`]
+#[derive(Clone, Copy)]
+pub struct Pretty<'a> {
+    value: &'a dyn DebugPls,
+    budget: Option<Budget>,
+    style: Style,
+    /// Whether the whole value is kept on one line
+    compact: bool,
+    label: Option<&'a str>,
+    prefix: Option<&'a str>,
+}
+
+impl<'a> Pretty<'a> {
+    /// Puts a label in front of the value, `label = value`. The value goes
+    /// on the next line if it does not fit on the same line as the label.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let retries = vec![1, 2, 3];
+    /// assert_eq!(
+    ///     format!("{}", pretty(&retries).named("state after retry #3")),
+    ///     "state after retry #3 = [1, 2, 3]",
+    /// );
+    ///
+    /// let retries: Vec<u32> = (1..=20).collect();
+    /// assert_eq!(
+    ///     format!("{}", pretty(&retries).named("state after retry #20")),
+    ///     "state after retry #20 =
+    /// [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn named(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Starts every line after the first with `prefix`, to line the output
+    /// up within the log line it is embedded in.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let map = BTreeMap::from([("retries", 3)]);
+    /// assert_eq!(
+    ///     format!("state: {}", pretty(&map).prefix("│ ")),
+    ///     "state: {
+    /// │     [\"retries\"] = 3;
+    /// │ }",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn prefix(mut self, prefix: &'a str) -> Self {
+        self.prefix = Some(prefix);
+        self
+    }
+
+    /// Limits how much of the value is printed.
+    #[must_use]
+    pub fn budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Writes maps and sets as the constructors that build them, so that the
+    /// output can be pasted back into code.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    /// use std::collections::{BTreeMap, BTreeSet};
+    ///
+    /// let map = BTreeMap::from([("a", 1), ("b", 2)]);
+    /// assert_eq!(
+    ///     format!("{}", pretty(&map).constructors()),
+    ///     "BTreeMap::from([(\"a\", 1), (\"b\", 2)])",
+    /// );
+    /// let set = BTreeSet::from([1, 2]);
+    /// assert_eq!(format!("{}", pretty(&set).constructors()), "BTreeSet::from([1, 2])");
+    /// ```
+    ///
+    /// This takes the name of the type, which the maps and sets of other
+    /// crates may not give, see [`DebugMap::type_name`](crate::DebugMap::type_name).
+    /// Those are written as arrays of their entries, `[("a", 1), ("b", 2)]`,
+    /// which the map or set can be collected from.
+    #[must_use]
+    pub fn constructors(mut self) -> Self {
+        self.style.constructors = true;
+        self
+    }
+
+    /// Writes number literals with their type as a suffix, `5u8` rather
+    /// than `5`, so that they keep their type when pasted back into code.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let value = (5_u8, -1_i128, 0.5_f32, u128::MAX);
+    /// assert_eq!(
+    ///     format!("{}", pretty(&value).suffixes()),
+    ///     "(5u8, -1i128, 0.5f32, 340282366920938463463374607431768211455u128)",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn suffixes(mut self) -> Self {
+        self.style.suffixes = true;
+        self
+    }
+
+    /// Writes `Vec`s, `VecDeque`s and slices as the `vec!` that builds
+    /// them, so that they can be pasted back into code where a `Vec` is
+    /// expected.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let value = (vec![1, 2], [3, 4]);
+    /// assert_eq!(
+    ///     format!("{}", pretty(&value).vec_macros()),
+    ///     "(vec![1, 2], [3, 4])",
+    /// );
+    /// ```
+    ///
+    /// Arrays are still written as arrays. Any other list can be written as
+    /// a `vec!` with [`DebugList::vec_macro`](crate::DebugList::vec_macro).
+    #[must_use]
+    pub fn vec_macros(mut self) -> Self {
+        self.style.vec_macros = true;
+        self
+    }
+
+    /// Writes strings with quotes or backslashes in them as raw string
+    /// literals, which need no escaping, for regexes, Windows paths and
+    /// the like. Strings that hold characters that have to be escaped
+    /// anyway, such as newlines, are written as they are otherwise.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let value = [r"C:\Users", r#"say "hi""#, r##"a"#b"##, "tab\t"];
+    /// assert_eq!(
+    ///     format!("{}", pretty(&value).raw_strings()),
+    ///     r###"[r"C:\Users", r#"say "hi""#, r##"a"#b"##, "tab\t"]"###,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn raw_strings(mut self) -> Self {
+        self.style.raw_strings = true;
+        self
+    }
+
+    /// Writes strings of several lines as a `concat!` of the literals of
+    /// each of them, one line after another, so that log messages, queries
+    /// and the like read the way they would be printed.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let query = (1, "SELECT name\nFROM users\nWHERE id = 1");
+    /// assert_eq!(
+    ///     format!("{}", pretty(&query).multiline_strings()),
+    ///     r#"(
+    ///     1,
+    ///     concat!(
+    ///         "SELECT name\n",
+    ///         "FROM users\n",
+    ///         "WHERE id = 1",
+    ///     ),
+    /// )"#,
+    /// );
+    /// ```
+    ///
+    /// The `syn-backend` leaves the literals in a macro as they are, so a
+    /// value that holds such a string is laid out by the built-in layout.
+    #[must_use]
+    pub fn multiline_strings(mut self) -> Self {
+        self.style.multiline_strings = true;
+        self
+    }
+
+    /// Writes integer literals with their digits in groups, `1_000_000`
+    /// rather than `1000000`, so that sizes and counts can be read at a
+    /// glance.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let value = (1_000_000_u64, -12345_i32, 999_u16);
+    /// assert_eq!(
+    ///     format!("{}", pretty(&value).digit_separators()),
+    ///     "(1_000_000, -12_345, 999)",
+    /// );
+    /// ```
+    ///
+    /// The literals written by [`Formatter::debug_int_hex`](crate::Formatter::debug_int_hex)
+    /// and [`Formatter::debug_int_bin`](crate::Formatter::debug_int_bin) are
+    /// grouped in fours, `0xDEAD_BEEF`. Floats are written as they are.
+    #[must_use]
+    pub fn digit_separators(mut self) -> Self {
+        self.style.digit_separators = true;
+        self
+    }
+
+    /// Rounds floats to `digits` digits after the point, so that sums like
+    /// `0.1 + 0.2` read as `0.3` rather than `0.30000000000000004`. Each is
+    /// written as the shortest literal of its rounded value, which is still
+    /// a float literal.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let value = [0.1 + 0.2, 1.0 / 3.0, 2.0, 1e-9];
+    /// assert_eq!(
+    ///     format!("{}", pretty(&value).precision(3)),
+    ///     "[0.3, 0.333, 2.0, 0.0]",
+    /// );
+    /// ```
+    ///
+    /// A single float can be rounded by its own precision with
+    /// [`Formatter::debug_float_with_precision`](crate::Formatter::debug_float_with_precision).
+    #[must_use]
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.style.precision = Some(digits);
+        self
+    }
+
+    /// Follows durations and times with a comment that reads them out, so
+    /// that the output stays valid Rust.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let elapsed = Duration::from_millis(123_400);
+    /// assert_eq!(
+    ///     format!("{}", pretty(&elapsed).humanized()),
+    ///     "Duration::from_millis(123400) /* 2m 3.4s */",
+    /// );
+    /// let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    /// assert_eq!(
+    ///     format!("{}", pretty(&time).humanized()),
+    ///     "SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000) /* 2023-11-14T22:13:20Z */",
+    /// );
+    /// ```
+    ///
+    /// Sizes written with [`Formatter::debug_byte_size`](crate::Formatter::debug_byte_size)
+    /// are only written in their readable unit, as a string.
+    ///
+    /// Times are given in UTC, as the standard library has no notion of the
+    /// local time zone. With the `syn-backend` feature, values with such a
+    /// comment are left to the built-in layout, which keeps it.
+    #[must_use]
+    pub fn humanized(mut self) -> Self {
+        self.style.humanized = true;
+        self
+    }
+
+    /// Chooses whether structs, tuples and lists that are broken over lines
+    /// end in a comma after their last element, which they do by default.
+    /// Leaving them out matches a rustfmt configured with
+    /// `trailing_comma = "Never"`, which keeps snapshots of the output in
+    /// line with the code around them.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let value = [
+    ///     "a string long enough to break the list",
+    ///     "over lines, with one more after it",
+    /// ];
+    /// assert_eq!(
+    ///     format!("{}", pretty(&value).trailing_commas(false)),
+    ///     "\
+    /// [
+    ///     \"a string long enough to break the list\",
+    ///     \"over lines, with one more after it\"
+    /// ]",
+    /// );
+    /// ```
+    ///
+    /// Builders choose for their own output with
+    /// [`DebugStruct::trailing_comma`](crate::DebugStruct::trailing_comma)
+    /// and the like. With the `syn-backend` feature, values without
+    /// trailing commas are laid out by the built-in layout.
+    #[must_use]
+    pub fn trailing_commas(mut self, trailing: bool) -> Self {
+        self.style.no_trailing_commas = !trailing;
+        self
+    }
+
+    /// Keeps the whole value on one line, however long it is, the way `{:?}`
+    /// writes values. Comments are written as block comments.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let value = vec!["a string long enough to break the list"; 3];
+    /// assert_eq!(
+    ///     format!("{}", pretty(&value).compact()),
+    ///     r#"["a string long enough to break the list", "a string long enough to break the list", "a string long enough to break the list"]"#,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn compact(mut self) -> Self {
+        self.compact = true;
+        self
+    }
+
+    /// Formats the value once, on first use, and displays that same output
+    /// every time after.
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let value = vec![Some(1), None];
+    /// let rendered = pretty(&value).rendered();
+    /// // both are formatted from the one output
+    /// assert_eq!(format!("{}", rendered), "[Some(1), None]");
+    /// assert_eq!(rendered.as_str(), "[Some(1), None]");
+    /// ```
+    #[must_use]
+    pub fn rendered(self) -> Rendered<'a> {
+        Rendered {
+            pretty: self,
+            plain: OnceCell::new(),
+            #[cfg(feature = "colors")]
+            colored: OnceCell::new(),
+        }
+    }
+
+    /// Captures the value now, to be laid out whenever it is displayed.
+    ///
+    /// Capturing is the part of formatting that reads the value. What it
+    /// captures is owned and [`Send`], so the more expensive layout can be
+    /// left to later or to another thread, see [`dbg_pls_async!`](crate::dbg_pls_async).
+    ///
+    /// ```
+    /// use dbg_pls::pretty;
+    ///
+    /// let mut value = vec![1, 2];
+    /// let captured = pretty(&value).capture();
+    /// value.push(3);
+    ///
+    /// let output = std::thread::spawn(move || captured.to_string());
+    /// assert_eq!(output.join().unwrap(), "[1, 2]");
+    /// ```
+    #[must_use]
+    pub fn capture(self) -> Captured {
+        Captured {
+            tree: self.tree(self.budget),
+            label: self.label.map(Box::from),
+            prefix: self.prefix.map(Box::from),
+        }
+    }
+
+    fn tree(&self, budget: Option<Budget>) -> Tree {
+        let mut tree = Tree::capture_with(self.value, budget.map(Budget::limit), self.style);
+        if self.compact {
+            tree.hint_as(Tree::ROOT, Hint::Compact);
+        }
+        tree
+    }
+
+    fn render(&self, budget: Option<Budget>) -> String {
+        render(&self.tree(budget), self.label, self.prefix)
+    }
+}
+
+fn render(tree: &Tree, label: Option<&str>, prefix: Option<&str>) -> String {
+    let mut output = pretty_string(tree);
+    if let Some(label) = label {
+        // the same width the value is laid out within
+        let width = label.chars().count() + " = ".len() + output.chars().count();
+        output = if output.contains('\n') || width > crate::layout::WIDTH {
+            format!("{label} =\n{output}")
+        } else {
+            format!("{label} = {output}")
+        };
+    }
+    match prefix {
+        Some(prefix) => output.replace('\n', &format!("\n{prefix}")),
+        None => output,
+    }
+}
+
+impl std::fmt::Debug for Pretty<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(self.budget))
+    }
+}
+
+impl std::fmt::Display for Pretty<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let expr = syn::parse_str(self.0).map_err(|_| std::fmt::Error)?;
-        f.write_str(&pretty_string(expr))
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// A value captured to be laid out later, see [`Pretty::capture`]
+#[derive(Clone)]
+pub struct Captured {
+    tree: Tree,
+    label: Option<Box<str>>,
+    prefix: Option<Box<str>>,
+}
+
+impl std::fmt::Debug for Captured {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&render(
+            &self.tree,
+            self.label.as_deref(),
+            self.prefix.as_deref(),
+        ))
+    }
+}
+
+impl std::fmt::Display for Captured {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
     }
 }
 
-struct Pretty<'a>(&'a dyn DebugPls);
+#[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
+/// The output of a [`Pretty`] value, formatted on first use and kept from
+/// then on, see [`Pretty::rendered`].
+pub struct Rendered<'a> {
+    pretty: Pretty<'a>,
+    plain: OnceCell<String>,
+    #[cfg(feature = "colors")]
+    colored: OnceCell<String>,
+}
+
+impl Rendered<'_> {
+    /// The pretty printed output
+    pub fn as_str(&self) -> &str {
+        self.plain
+            .get_or_init(|| self.pretty.render(self.pretty.budget))
+    }
+
+    /// The output with syntax highlighting, which is kept separately from
+    /// the plain output it is highlighted from.
+    #[cfg(feature = "colors")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "colors")))]
+    pub fn colored(&self) -> &str {
+        self.colored.get_or_init(|| {
+            let mut colored = String::new();
+            // writing to a `String` does not fail
+            let _ = crate::colors::highlight(self.as_str(), &mut colored);
+            colored
+        })
+    }
+}
 
-impl<'a> std::fmt::Debug for Pretty<'a> {
+impl std::fmt::Debug for Rendered<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&pretty_string(Formatter::process(self.0)))
+        f.write_str(self.as_str())
     }
 }
 
-impl<'a> std::fmt::Display for Pretty<'a> {
+impl std::fmt::Display for Rendered<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Debug::fmt(self, f)
     }
@@ -63,8 +673,18 @@ impl<'a> std::fmt::Display for Pretty<'a> {
 
 #[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
 /// Wraps a [`DebugPls`] type into a [`std::fmt::Debug`] type for use in regular [`format!`]
-pub fn pretty(value: &impl DebugPls) -> impl std::fmt::Debug + std::fmt::Display + '_ {
-    Pretty(value)
+///
+/// The value can also be a trait object, such as `&*boxed` for a
+/// `Box<dyn DebugPls>`.
+pub fn pretty(value: &dyn DebugPls) -> Pretty<'_> {
+    Pretty {
+        value,
+        budget: None,
+        style: Style::default(),
+        compact: false,
+        label: None,
+        prefix: None,
+    }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "pretty")))]
@@ -85,7 +705,13 @@ pub fn pretty(value: &impl DebugPls) -> impl std::fmt::Debug + std::fmt::Display
 /// The macro works by using the [`DebugPls`] implementation of the type of
 /// the given expression to print the value to [stderr] along with the
 /// source location of the macro invocation as well as the source code
-/// of the expression.
+/// of the expression. The source code is laid out as values are, which
+/// needs the `syn` feature to parse it; without it, the source code is
+/// written as `stringify!` gives it.
+///
+/// The dump is laid out in full and then written at once, so the dumps
+/// of different threads never interleave. [`lock_output`](crate::lock_output)
+/// keeps several of them together.
 ///
 /// Invoking the macro on an expression moves and takes ownership of it
 /// before returning the evaluated expression unchanged. If the type
@@ -113,12 +739,12 @@ macro_rules! pretty {
     ($val:expr $(,)?) => {
         match $val {
             tmp => {
-                ::std::eprintln!(
-                    "[{}:{}] {} => {}",
+                $crate::__private::print(
                     ::std::file!(),
                     ::std::line!(),
-                    $crate::__private::PrettyStr(::std::stringify!($val)),
-                    $crate::pretty(&tmp)
+                    ::std::stringify!($val),
+                    &$crate::__private::PrettyStr(::std::stringify!($val)),
+                    &$crate::pretty(&tmp),
                 );
                 tmp
             }
@@ -131,9 +757,14 @@ macro_rules! pretty {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{
+        cell::{Cell, RefCell},
+        collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+        rc::Rc,
+        time::Duration,
+    };
 
-    use crate::pretty;
+    use crate::{pretty, Budget, DebugPls, Formatter};
 
     #[test]
     fn pretty_macro() {
@@ -146,4 +777,313 @@ mod tests {
         // map is moved through properly
         assert_eq!(map, HashMap::from([("hello", 1), ("world", 2),]));
     }
+
+    #[test]
+    fn pretty_macro_label() {
+        #[cfg(feature = "syn")]
+        {
+            assert_eq!(super::Str("a * 2").to_string(), "a * 2");
+            assert_eq!(super::Str("vec ! [1 , 2]").to_string(), "vec![1, 2]");
+            assert_eq!(
+                super::Str("Point { x : 1 , y : 2 }").to_string(),
+                "Point { x: 1, y: 2 }",
+            );
+            let long = format!("[{}]", ["sixteen_chars_xx"; 6].join(", "));
+            assert_eq!(
+                super::Str(&long).to_string(),
+                format!("[\n{}]", "    sixteen_chars_xx,\n".repeat(6)),
+            );
+        }
+        // not an expression at all
+        assert_eq!(super::Str("a b").to_string(), "a b");
+    }
+
+    #[test]
+    fn budget_elides_the_rest() {
+        let nested = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        assert_eq!(
+            pretty(&nested).budget(Budget::new().bytes(16)).to_string(),
+            "[[1, 2, 3], [4, ..], ..]",
+        );
+
+        let map: BTreeMap<_, _> = (0..10).map(|i| (i, i * i)).collect();
+        assert_eq!(
+            pretty(&map).budget(Budget::new().bytes(12)).to_string(),
+            "{\n    [0] = 0;\n    [1] = 1;\n    ..\n}",
+        );
+        assert_eq!(
+            pretty(&map).budget(Budget::new().bytes(1)).to_string(),
+            "{ .. }"
+        );
+    }
+
+    #[test]
+    fn constructors() {
+        struct Unnamed;
+
+        impl DebugPls for Unnamed {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_map().entry(&1, &BTreeSet::from([2])).finish();
+            }
+        }
+
+        struct UnnamedSet;
+
+        impl DebugPls for UnnamedSet {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_set().entry(&"a").entry(&"b").finish();
+            }
+        }
+
+        let map: BTreeMap<_, _> = (0..10).map(|i| (i, BTreeSet::from([i]))).collect();
+        assert_eq!(
+            pretty(&map)
+                .constructors()
+                .budget(Budget::new().bytes(40))
+                .to_string(),
+            "BTreeMap::from([(0, BTreeSet::from([0])), ..])",
+        );
+        assert_eq!(
+            pretty(&Unnamed).constructors().to_string(),
+            "[(1, BTreeSet::from([2]))]",
+        );
+        assert_eq!(
+            pretty(&UnnamedSet).constructors().to_string(),
+            "[\"a\", \"b\"]"
+        );
+        assert_eq!(
+            pretty(&HashSet::<u8>::new()).constructors().to_string(),
+            "HashSet::from([])",
+        );
+    }
+
+    #[cfg(feature = "syn")]
+    #[test]
+    fn unsupported_expressions() {
+        struct Expr(&'static str);
+
+        impl DebugPls for Expr {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.write_expr(syn::parse_str::<syn::Expr>(self.0).unwrap());
+            }
+        }
+
+        // `prettyplease` panics on these
+        assert_eq!(
+            pretty(&Some(Expr("const { 1 }"))).to_string(),
+            "Some(const { 1 })"
+        );
+        assert_eq!(
+            pretty(&Some(Expr("1 + const { 1 }"))).to_string(),
+            "Some(1 + const { 1 })"
+        );
+    }
+
+    #[cfg(feature = "syn")]
+    #[test]
+    fn parenthesized_expressions() {
+        use syn::Expr;
+
+        struct Unparenthesized(&'static str);
+
+        impl DebugPls for Unparenthesized {
+            fn fmt(&self, f: Formatter<'_>) {
+                let mut expr = syn::parse_str(self.0).unwrap();
+                unparenthesize(&mut expr);
+                f.write_expr(expr);
+            }
+        }
+
+        /// Takes out the parentheses, as a hand built expression would not have them.
+        fn unparenthesize(expr: &mut Expr) {
+            while let Expr::Paren(paren) = expr {
+                *expr = (*paren.expr).clone();
+            }
+            match expr {
+                Expr::Unary(expr) => unparenthesize(&mut expr.expr),
+                Expr::Reference(expr) => unparenthesize(&mut expr.expr),
+                Expr::Binary(expr) => {
+                    unparenthesize(&mut expr.left);
+                    unparenthesize(&mut expr.right);
+                }
+                Expr::Cast(expr) => unparenthesize(&mut expr.expr),
+                Expr::Field(expr) => unparenthesize(&mut expr.base),
+                Expr::Index(expr) => {
+                    unparenthesize(&mut expr.expr);
+                    unparenthesize(&mut expr.index);
+                }
+                Expr::MethodCall(expr) => unparenthesize(&mut expr.receiver),
+                Expr::Call(expr) => expr.args.iter_mut().for_each(unparenthesize),
+                Expr::Range(expr) => {
+                    expr.from.iter_mut().for_each(|expr| unparenthesize(expr));
+                    expr.to.iter_mut().for_each(|expr| unparenthesize(expr));
+                }
+                _ => {}
+            }
+        }
+
+        let cases = [
+            ("-1", "-1"),
+            ("(-x).y", "(-x).y"),
+            ("-(x.y)", "-x.y"),
+            ("(-1).abs()", "(-1).abs()"),
+            ("(-a)[-1]", "(-a)[-1]"),
+            ("(&a[0]).0", "(&a[0]).0"),
+            ("(1).0", "(1).0"),
+            ("-(a + b)", "-(a + b)"),
+            ("(a + b) * -c", "(a + b) * -c"),
+            ("a - (b - c)", "a - (b - c)"),
+            ("(a - b) - c", "a - b - c"),
+            ("(a < b) == c", "(a < b) == c"),
+            ("(a as u8) as u16", "a as u8 as u16"),
+            ("(-a) as u8", "-a as u8"),
+            ("(a = b)..(c..d)", "(a = b)..(c..d)"),
+            ("Foo(-1, (-x).y, (..1)..)", "Foo(-1, (-x).y, (..1)..)"),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(
+                pretty(&Unparenthesized(expr)).to_string(),
+                expected,
+                "{expr}"
+            );
+        }
+        assert_eq!(pretty(&((0..1)..(2..3))).to_string(), "(0..1)..(2..3)");
+        assert_eq!(pretty(&(..(..=-1))).to_string(), "..(..=-1)");
+    }
+
+    #[test]
+    fn named() {
+        let map: BTreeMap<_, _> = (0..2).map(|i| (i, i * i)).collect();
+        assert_eq!(
+            pretty(&map).named("squares").to_string(),
+            "squares =\n{\n    [0] = 0;\n    [1] = 1;\n}",
+        );
+        assert_eq!(
+            pretty(&[1, 2, 3])
+                .named("first")
+                .budget(Budget::new().bytes(4))
+                .to_string(),
+            "first = [1, ..]",
+        );
+        // the label counts towards the width, in characters
+        let list: Vec<u32> = (0..10).collect();
+        let long = list.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let fits = "ö".repeat(crate::layout::WIDTH - long.join(", ").len() - "[] = ".len());
+        assert_eq!(
+            pretty(&list).named(&fits).to_string(),
+            format!("{fits} = [{}]", long.join(", ")),
+        );
+        let wider = format!("{fits}ö");
+        assert_eq!(
+            pretty(&list).named(&wider).to_string(),
+            format!("{wider} =\n[{}]", long.join(", ")),
+        );
+
+        let captured = pretty(&Some(1)).named("later").capture();
+        assert_eq!(captured.to_string(), "later = Some(1)");
+        #[cfg(feature = "colors")]
+        assert!(crate::color(&Some(1))
+            .named("colored")
+            .to_string()
+            .contains("colored"));
+    }
+
+    #[test]
+    fn prefix() {
+        let map: BTreeMap<_, _> = (0..2).map(|i| (i, i * i)).collect();
+        assert_eq!(
+            pretty(&map).named("squares").prefix("  | ").to_string(),
+            "squares =\n  | {\n  |     [0] = 0;\n  |     [1] = 1;\n  | }",
+        );
+        assert_eq!(pretty(&[1, 2]).prefix("> ").to_string(), "[1, 2]");
+        let captured = pretty(&map).prefix("\t").capture();
+        assert_eq!(
+            captured.to_string(),
+            "{\n\t    [0] = 0;\n\t    [1] = 1;\n\t}"
+        );
+    }
+
+    #[test]
+    fn spent_budget() {
+        let value = (1, "two", [3.0]);
+        assert_eq!(
+            pretty(&value).budget(Budget::new().bytes(0)).to_string(),
+            ".."
+        );
+        assert_eq!(
+            pretty(&value)
+                .budget(Budget::new().time(Duration::ZERO))
+                .to_string(),
+            "..",
+        );
+        assert_eq!(
+            pretty(&value).budget(Budget::new()).to_string(),
+            pretty(&value).to_string(),
+        );
+    }
+
+    #[test]
+    fn rendered_once() {
+        struct Counted(Cell<usize>);
+
+        impl DebugPls for Counted {
+            fn fmt(&self, f: Formatter<'_>) {
+                self.0.set(self.0.get() + 1);
+                f.debug_tuple_struct("Counted").finish();
+            }
+        }
+
+        let counted = Counted(Cell::default());
+        let rendered = pretty(&counted).rendered();
+        assert_eq!(counted.0.get(), 0);
+        assert_eq!(rendered.to_string(), "Counted()");
+        assert_eq!(format!("{rendered:?}"), "Counted()");
+        #[cfg(feature = "colors")]
+        assert!(rendered.colored().contains("Counted"));
+        assert_eq!(counted.0.get(), 1);
+    }
+
+    #[test]
+    fn cycles() {
+        struct Node(RefCell<Option<Rc<Node>>>);
+
+        impl DebugPls for Node {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple_struct("Node")
+                    .field(&*self.0.borrow())
+                    .finish();
+            }
+        }
+
+        let node = Rc::new(Node(RefCell::new(None)));
+        *node.0.borrow_mut() = Some(node.clone());
+        assert_eq!(pretty(&node).to_string(), "Node(Some(\"<cycle>\"))");
+
+        // the same value twice side by side is not a cycle
+        let leaf = Rc::new(Node(RefCell::new(None)));
+        assert_eq!(
+            pretty(&[leaf.clone(), leaf]).to_string(),
+            "[Node(None), Node(None)]"
+        );
+
+        node.0.borrow_mut().take();
+    }
+
+    #[test]
+    fn depth_guard() {
+        struct Endless;
+
+        impl DebugPls for Endless {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple_struct("Endless").field(&Endless).finish();
+            }
+        }
+
+        // on a thread with the default, smaller, stack
+        let output = std::thread::spawn(|| pretty(&Endless).to_string())
+            .join()
+            .unwrap();
+        assert_eq!(output.matches("Endless").count(), crate::node::MAX_DEPTH);
+        assert!(output.contains("Endless(..)"));
+    }
 }
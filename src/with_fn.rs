@@ -0,0 +1,117 @@
+//! Values that are formatted by a closure.
+
+use std::cell::Cell;
+
+use crate::{DebugPls, Formatter};
+
+/// Formats a value with the closure, for one-off formatting that doesn't
+/// need a type of its own.
+///
+/// The closure is called for every time the value is formatted.
+///
+/// # Examples
+///
+/// ```rust
+/// use dbg_pls::{from_fn, pretty, DebugPls, Formatter};
+///
+/// let point = (3, 4);
+/// let value = from_fn(|f| {
+///     f.debug_struct("Point")
+///         .field("x", &point.0)
+///         .field("y", &point.1)
+///         .finish()
+/// });
+/// assert_eq!(format!("{}", pretty(&value)), "Point { x: 3, y: 4 }");
+///
+/// struct Job {
+///     id: u32,
+///     tags: Vec<&'static str>,
+/// }
+///
+/// impl DebugPls for Job {
+///     fn fmt(&self, f: Formatter<'_>) {
+///         f.debug_struct("Job")
+///             .field("id", &self.id)
+///             .field("tags", &from_fn(|f| f.debug_set().entries(&self.tags).finish()))
+///             .finish()
+///     }
+/// }
+///
+/// let job = Job { id: 7, tags: vec!["a"] };
+/// assert_eq!(format!("{}", pretty(&job)), "Job { id: 7, tags: { \"a\" } }");
+/// ```
+pub fn from_fn<F: Fn(Formatter<'_>)>(fmt: F) -> FromFn<F> {
+    FromFn(fmt)
+}
+
+/// A value formatted by a closure, see [`from_fn`]
+#[derive(Clone, Copy)]
+pub struct FromFn<F>(F);
+
+impl<F: Fn(Formatter<'_>)> DebugPls for FromFn<F> {
+    fn fmt(&self, f: Formatter<'_>) {
+        (self.0)(f);
+    }
+}
+
+/// Formats the value by calling the closure, which is only called once.
+pub(crate) struct WithFn<F>(Cell<Option<F>>);
+
+impl<F: FnOnce(Formatter<'_>)> WithFn<F> {
+    pub(crate) fn new(fmt: F) -> Self {
+        WithFn(Cell::new(Some(fmt)))
+    }
+}
+
+impl<F: FnOnce(Formatter<'_>)> DebugPls for WithFn<F> {
+    fn fmt(&self, f: Formatter<'_>) {
+        if let Some(fmt) = self.0.take() {
+            fmt(f);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "pretty"))]
+mod tests {
+    use crate::{pretty, DebugPls, Formatter};
+
+    #[test]
+    fn closure_fields() {
+        struct Ids<'a>(&'a [u32]);
+
+        impl DebugPls for Ids<'_> {
+            fn fmt(&self, f: Formatter<'_>) {
+                let hex = |id: u32| move |f: Formatter<'_>| f.debug_ident(&format!("0x{id:x}"));
+                let mut list = f.debug_list();
+                for &id in self.0 {
+                    list = list.entry_with(hex(id));
+                }
+                list.finish();
+            }
+        }
+
+        struct Builders;
+
+        impl DebugPls for Builders {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple()
+                    .field_with(|f| {
+                        f.debug_struct("Foo")
+                            .field_with("ids", |f| Ids(&[10, 255]).fmt(f))
+                            .finish();
+                    })
+                    .field_with(|f| {
+                        f.debug_tuple_struct("Bar")
+                            .field_with(|f| 1.fmt(f))
+                            .finish();
+                    })
+                    .finish();
+            }
+        }
+
+        assert_eq!(
+            pretty(&Builders).to_string(),
+            "(Foo { ids: [0xa, 0xff] }, Bar(1))",
+        );
+    }
+}
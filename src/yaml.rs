@@ -0,0 +1,600 @@
+//! Writes a captured [`Tree`] as YAML.
+//!
+//! Structs are written as mappings of their fields, lists, tuples and sets
+//! as sequences, and maps as mappings, with an explicit `? key` where the
+//! key is not a scalar. Structs and tuple structs, such as the variants of
+//! an enum, keep their name as a tag, `!Some 1`, and so do the paths of unit
+//! variants, `!None`. Whatever was elided is left as a `# ..` comment.
+
+use crate::{
+    node::{Node, NodeId, Style, Tree},
+    push_fmt, DebugPls,
+};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+/// Writes the value as a YAML document, for tools that would rather read
+/// data than Rust.
+///
+/// ```
+/// # use dbg_pls::DebugPls;
+/// #[derive(DebugPls)]
+/// struct Demo {
+///     foo: i32,
+///     bar: &'static str,
+///     baz: Option<Vec<u8>>,
+/// }
+///
+/// let demo = Demo {
+///     foo: 5,
+///     bar: "hello\nworld",
+///     baz: None,
+/// };
+/// assert_eq!(
+///     dbg_pls::to_yaml(&demo),
+///     "!Demo\nfoo: 5\nbar: \"hello\\nworld\"\nbaz: !None\n",
+/// );
+/// ```
+pub fn to_yaml(value: &dyn DebugPls) -> String {
+    let tree = Tree::capture_with(value, None, Style::default());
+    let mut out = String::new();
+    Yaml {
+        tree: &tree,
+        out: &mut out,
+    }
+    .document();
+    out
+}
+
+struct Yaml<'a> {
+    tree: &'a Tree,
+    out: &'a mut String,
+}
+
+impl Yaml<'_> {
+    fn document(&mut self) {
+        if let Node::Elided = self.tree.get(Tree::ROOT) {
+            self.out.push_str("# ..\n");
+            return;
+        }
+        self.value(Tree::ROOT, 0);
+        // the space before a value only separates it from its key
+        self.out.remove(0);
+    }
+
+    /// Writes the node after a `key:` or `?`, either on the same line or as
+    /// a block indented by `indent`.
+    fn value(&mut self, id: NodeId, indent: usize) {
+        if let Some(scalar) = self.inline(id) {
+            self.out.push(' ');
+            self.out.push_str(&scalar);
+            self.comment(id);
+            self.out.push('\n');
+            return;
+        }
+        if let Some(tag) = self.tag(id) {
+            self.out.push(' ');
+            self.out.push_str(&tag);
+        }
+        self.comment(id);
+        self.out.push('\n');
+        self.block(id, indent);
+    }
+
+    /// Writes the comment of the node at the end of its first line.
+    fn comment(&mut self, id: NodeId) {
+        if let Some(comment) = self.tree.comment(id) {
+            self.out.push_str(" # ");
+            self.out.push_str(comment);
+        }
+    }
+
+    /// Writes the node as an entry of a sequence, with a mapping starting
+    /// on the same line as its `-`.
+    fn item(&mut self, id: NodeId, indent: usize) {
+        self.indent(indent);
+        self.out.push('-');
+        if self.inline(id).is_some() || self.tag(id).is_some() {
+            self.value(id, indent + 2);
+            return;
+        }
+        let start = self.out.len();
+        self.block(id, indent + 2);
+        self.out.replace_range(start..start + indent + 2, " ");
+    }
+
+    fn block(&mut self, id: NodeId, indent: usize) {
+        match self.tree.get(id) {
+            // the lines of a string, though only a style that YAML is not
+            // written in breaks strings into them
+            Node::List(children)
+            | Node::Vec(children)
+            | Node::Concat(children)
+            | Node::Table(children)
+            | Node::Tuple(children)
+            | Node::Set(children)
+            | Node::TupleStruct(_, children) => {
+                for child in self.tree.children(children) {
+                    match self.tree.get(child) {
+                        Node::Elided => self.elided(indent),
+                        Node::More(more) => self.more(more, indent),
+                        _ => self.item(child, indent),
+                    }
+                }
+            }
+            Node::Struct(_, fields) => {
+                for field in self.tree.children(fields) {
+                    if let Node::Elided = self.tree.get(field) {
+                        self.elided(indent);
+                        continue;
+                    }
+                    self.indent(indent);
+                    self.out.push_str(&key(self.tree.label(field)));
+                    self.out.push(':');
+                    self.value(field, indent + 2);
+                }
+            }
+            Node::Map(entries) => {
+                let mut entries = self.tree.children(entries);
+                while let Some(key) = entries.next() {
+                    let value = match (self.tree.get(key), entries.next()) {
+                        (Node::More(more), _) => {
+                            self.more(more, indent);
+                            continue;
+                        }
+                        (Node::Elided, _) | (_, None) => {
+                            self.elided(indent);
+                            continue;
+                        }
+                        (_, Some(value)) => value,
+                    };
+                    self.indent(indent);
+                    if let Some(key) = self.inline(key).filter(|key| !key.starts_with('!')) {
+                        self.out.push_str(&key);
+                    } else {
+                        self.out.push('?');
+                        self.value(key, indent + 2);
+                        self.indent(indent);
+                    }
+                    self.out.push(':');
+                    self.value(value, indent + 2);
+                }
+            }
+            // a mapping of the label to its children
+            Node::Branch(label, children) => {
+                self.indent(indent);
+                self.out.push_str(&key(self.tree.str(label)));
+                self.out.push_str(":\n");
+                for child in self.tree.children(children) {
+                    match self.tree.get(child) {
+                        Node::Elided => self.elided(indent + 2),
+                        Node::More(more) => self.more(more, indent + 2),
+                        _ => self.item(child, indent + 2),
+                    }
+                }
+            }
+            Node::Range(range) => {
+                for (name, bound) in [("start", range.start), ("end", range.end)] {
+                    if let Some(bound) = bound {
+                        self.indent(indent);
+                        self.out.push_str(name);
+                        self.out.push(':');
+                        self.value(bound, indent + 2);
+                    }
+                }
+            }
+            // always written inline
+            Node::Lit(_) | Node::Path(_) | Node::Verbatim(_) | Node::Elided | Node::More(_) => {}
+        }
+    }
+
+    /// The node written on one line, if it fits on one.
+    fn inline(&self, id: NodeId) -> Option<String> {
+        match self.tree.get(id) {
+            Node::Lit(text) => Some(lit(self.tree.str(text))),
+            Node::Path(text) => Some(path(self.tree.str(text))),
+            Node::Verbatim(text) => Some(quote(self.tree.str(text))),
+            Node::List(children)
+            | Node::Vec(children)
+            | Node::Table(children)
+            | Node::Tuple(children)
+            | Node::Set(children)
+                if self.tree.children(children).len() == 0 =>
+            {
+                Some("[]".to_owned())
+            }
+            Node::Struct(_, children) if self.tree.children(children).len() == 0 => {
+                match self.tag(id) {
+                    Some(tag) => Some(tag + " {}"),
+                    None => Some("{}".to_owned()),
+                }
+            }
+            Node::Map(children) if self.tree.children(children).len() == 0 => Some("{}".to_owned()),
+            Node::Branch(label, children) if self.tree.children(children).len() == 0 => {
+                Some(quote(self.tree.str(label)))
+            }
+            Node::TupleStruct(name, fields) => {
+                let mut fields = self.tree.children(fields);
+                let tag = tag(self.tree.str(name));
+                match (fields.next(), fields.next()) {
+                    (None, _) => Some(tag + " []"),
+                    // a node only has the one tag
+                    (Some(field), None) => self
+                        .inline(field)
+                        .filter(|field| !field.starts_with('!'))
+                        .map(|field| tag + " " + &field),
+                    _ => None,
+                }
+            }
+            Node::Range(range) => {
+                let mut out = self.tag(id)?;
+                out.push_str(" {");
+                for (name, bound) in [("start", range.start), ("end", range.end)] {
+                    if let Some(bound) = bound {
+                        if !out.ends_with('{') {
+                            out.push_str(", ");
+                        }
+                        push_fmt(&mut out, format_args!("{}: {}", name, self.inline(bound)?));
+                    }
+                }
+                out.push('}');
+                Some(out)
+            }
+            _ => None,
+        }
+    }
+
+    /// The tag that a block node is written after.
+    fn tag(&self, id: NodeId) -> Option<String> {
+        match self.tree.get(id) {
+            Node::TupleStruct(name, _) => Some(tag(self.tree.str(name))),
+            // such as the fields flattened into a map, which have no name
+            Node::Struct(name, _) if !self.tree.str(name).is_empty() => {
+                Some(tag(self.tree.str(name)))
+            }
+            Node::Range(range) if range.inclusive => Some("!RangeInclusive".to_owned()),
+            Node::Range(_) => Some("!Range".to_owned()),
+            _ => None,
+        }
+    }
+
+    fn elided(&mut self, indent: usize) {
+        self.indent(indent);
+        self.out.push_str("# ..\n");
+    }
+
+    fn more(&mut self, more: usize, indent: usize) {
+        self.indent(indent);
+        push_fmt(self.out, format_args!("# .. {more} more\n"));
+    }
+
+    fn indent(&mut self, indent: usize) {
+        self.out.extend(std::iter::repeat_n(' ', indent));
+    }
+}
+
+/// A literal as a YAML scalar. Numbers are written as they are, without
+/// their type suffix, and strings and chars as double quoted strings.
+/// Anything else, such as a byte string, is quoted as it was written.
+fn lit(text: &str) -> String {
+    let unquoted = match text.as_bytes().first() {
+        Some(b'"') => text.strip_prefix('"').and_then(|s| s.strip_suffix('"')),
+        Some(b'\'') => text.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')),
+        _ => return number(text).unwrap_or_else(|| quote(text)),
+    };
+    match unquoted.and_then(unescape) {
+        Some(s) => quote(&s),
+        None => quote(text),
+    }
+}
+
+fn number(text: &str) -> Option<String> {
+    const SUFFIXES: [&str; 14] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+        "f32", "f64",
+    ];
+    let text = SUFFIXES
+        .iter()
+        .find_map(|suffix| text.strip_suffix(suffix))
+        .unwrap_or(text)
+        .replace('_', "");
+    let digits = text
+        .bytes()
+        .all(|b| matches!(b, b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-'));
+    // `str::parse` also reads words such as `inf`
+    (digits && text.parse::<f64>().is_ok()).then_some(text)
+}
+
+/// Paths are either a value YAML has its own word for, such as `true` or
+/// `.nan`, or the unit variant they name, as a tag.
+fn path(text: &str) -> String {
+    let value = match text {
+        "true" | "false" => text,
+        "f32::NAN" | "f64::NAN" => ".nan",
+        "f32::INFINITY" | "f64::INFINITY" => ".inf",
+        "f32::NEG_INFINITY" | "f64::NEG_INFINITY" => "-.inf",
+        _ => return tag(text),
+    };
+    value.to_owned()
+}
+
+/// The name as a tag, `!Some`, with the characters a tag cannot hold,
+/// such as the `<` of `Foo<T>`, percent encoded.
+fn tag(name: &str) -> String {
+    let mut tag = String::from("!");
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() || matches!(ch, '_' | ':' | '-' | '.') {
+            tag.push(ch);
+        } else {
+            let mut buf = [0; 4];
+            for b in ch.encode_utf8(&mut buf).bytes() {
+                push_fmt(&mut tag, format_args!("%{b:02X}"));
+            }
+        }
+    }
+    tag
+}
+
+/// A field name as a key, quoted unless YAML would read it as a string.
+fn key(label: &str) -> String {
+    let plain = label.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+        && label
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    let reserved = matches!(
+        &*label.to_ascii_lowercase(),
+        "true" | "false" | "null" | "yes" | "no" | "on" | "off"
+    );
+    if plain && !reserved {
+        label.to_owned()
+    } else {
+        quote(label)
+    }
+}
+
+/// The contents of a Rust string or char literal, or `None` if it has an
+/// escape this does not know of.
+fn unescape(s: &str) -> Option<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            '0' => out.push('\0'),
+            ch @ ('\\' | '"' | '\'') => out.push(ch),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                out.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+            }
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+                let hex: String = chars.by_ref().take_while(|&ch| ch != '}').collect();
+                out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            // a line continuation skips the whitespace that follows it
+            '\n' => while chars.next_if(|ch| ch.is_whitespace()).is_some() {},
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+/// The string as a double quoted YAML scalar.
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\0' => out.push_str("\\0"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\u{1b}' => out.push_str("\\e"),
+            '\u{85}' => out.push_str("\\N"),
+            '\u{a0}' => out.push_str("\\_"),
+            '\u{2028}' => out.push_str("\\L"),
+            '\u{2029}' => out.push_str("\\P"),
+            ch if ch.is_control() => {
+                push_fmt(&mut out, format_args!("\\x{:02X}", u32::from(ch)));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use yaml_rust::{Yaml, YamlLoader};
+
+    use super::to_yaml;
+    use crate::{DebugPls, Formatter};
+
+    struct Point {
+        x: i64,
+        y: i64,
+    }
+
+    impl DebugPls for Point {
+        fn fmt(&self, f: Formatter<'_>) {
+            f.debug_struct("Point")
+                .field("x", &self.x)
+                .field("y", &self.y)
+                .finish();
+        }
+    }
+
+    /// The output, checked to be a single YAML document.
+    fn yaml(value: &impl DebugPls) -> String {
+        let out = to_yaml(value);
+        let docs = YamlLoader::load_from_str(&out).unwrap();
+        assert_eq!(docs.len(), 1, "{out}");
+        out
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(yaml(&5), "5\n");
+        assert_eq!(yaml(&-1.5), "-1.5\n");
+        assert_eq!(yaml(&f64::NAN), ".nan\n");
+        assert_eq!(yaml(&f32::NEG_INFINITY), "-.inf\n");
+        assert_eq!(yaml(&true), "true\n");
+        assert_eq!(yaml(&'\''), "\"'\"\n");
+        assert_eq!(
+            yaml(&"tab\t \"quoted\" \u{7f}"),
+            "\"tab\\t \\\"quoted\\\" \\x7F\"\n"
+        );
+        assert_eq!(yaml(&()), "[]\n");
+
+        let doc = &YamlLoader::load_from_str(&yaml(&"é\\\n\0")).unwrap()[0];
+        assert_eq!(doc, &Yaml::String("é\\\n\0".to_owned()));
+    }
+
+    #[test]
+    fn collections() {
+        assert_eq!(
+            yaml(&vec![Point { x: 1, y: -1 }, Point { x: 2, y: -2 }]),
+            "- !Point\n  x: 1\n  y: -1\n- !Point\n  x: 2\n  y: -2\n",
+        );
+        assert_eq!(
+            yaml(&(vec![vec![1], vec![]], BTreeSet::from(["a"]))),
+            "- - - 1\n  - []\n- - \"a\"\n",
+        );
+        assert_eq!(
+            yaml(&BTreeMap::from([("a", vec![1, 2]), ("b", vec![])])),
+            "\"a\":\n  - 1\n  - 2\n\"b\": []\n",
+        );
+        assert_eq!(
+            yaml(&BTreeMap::from([((1, 2), Some(3)), ((4, 5), None)])),
+            "?\n  - 1\n  - 2\n: !Some 3\n?\n  - 4\n  - 5\n: !None\n",
+        );
+    }
+
+    #[test]
+    fn tagged() {
+        assert_eq!(yaml(&Some(Some(1))), "!Some\n- !Some 1\n");
+        assert_eq!(
+            yaml(&Some(Point { x: 1, y: 2 })),
+            "!Some\n- !Point\n  x: 1\n  y: 2\n",
+        );
+        assert_eq!(yaml(&Ok::<_, ()>(vec![None::<u8>])), "!Ok\n- - !None\n",);
+        assert_eq!(
+            yaml(&(1..2, ..=Some(3))),
+            "- !Range {start: 1, end: 2}\n- !RangeInclusive {end: !Some 3}\n",
+        );
+        assert_eq!(
+            yaml(&(Point { x: 1, y: 2 }..Point { x: 3, y: 4 })),
+            "!Range\nstart: !Point\n  x: 1\n  y: 2\nend: !Point\n  x: 3\n  y: 4\n",
+        );
+    }
+
+    #[test]
+    fn struct_variants() {
+        enum Shape {
+            Circle { r: u8 },
+            Square { r: u8 },
+            Empty {},
+        }
+
+        impl DebugPls for Shape {
+            fn fmt(&self, f: Formatter<'_>) {
+                match self {
+                    Shape::Circle { r } => f.debug_struct("Circle").field("r", r).finish(),
+                    Shape::Square { r } => f.debug_struct("Square").field("r", r).finish(),
+                    Shape::Empty {} => f.debug_struct("Empty").finish(),
+                }
+            }
+        }
+
+        let shapes = [
+            Shape::Circle { r: 1 },
+            Shape::Square { r: 1 },
+            Shape::Empty {},
+        ];
+        let out = yaml(&shapes);
+        assert_eq!(out, "- !Circle\n  r: 1\n- !Square\n  r: 1\n- !Empty {}\n");
+
+        // the variants stay apart once read back by a parser that keeps tags
+        let shapes: Vec<serde_yaml::Value> = serde_yaml::from_str(&out).unwrap();
+        let tags: Vec<_> = shapes
+            .iter()
+            .map(|shape| match shape {
+                serde_yaml::Value::Tagged(tagged) => tagged.tag.to_string(),
+                _ => panic!("{:?}", shape),
+            })
+            .collect();
+        assert_eq!(tags, ["!Circle", "!Square", "!Empty"]);
+    }
+
+    #[test]
+    fn truncated() {
+        struct Truncated(usize);
+
+        impl DebugPls for Truncated {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tuple()
+                    .field_with(|f| f.debug_list().truncate(2).entries(0..self.0).finish())
+                    .field_with(|f| {
+                        let pairs = (0..self.0).map(|i| (i, i));
+                        f.debug_map().truncate(1).entries(pairs).finish();
+                    })
+                    .finish();
+            }
+        }
+
+        assert_eq!(
+            yaml(&Truncated(5)),
+            "- - 0\n  - 1\n  # .. 3 more\n- 0: 0\n  # .. 4 more\n",
+        );
+    }
+
+    #[test]
+    fn comments() {
+        struct Commented;
+
+        impl DebugPls for Commented {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_struct("Commented")
+                    .field_commented("mode", &0o755, "rwxr-xr-x")
+                    .field_commented("masks", &[1, 2], "one per\nlevel")
+                    .field_with("limits", |f| {
+                        f.debug_map()
+                            .entry_commented(&"timeout", &500, "ms")
+                            .finish();
+                    })
+                    .finish();
+            }
+        }
+
+        assert_eq!(
+            yaml(&Commented),
+            "!Commented\nmode: 493 # rwxr-xr-x\nmasks: # one per level\n  - 1\n  - 2\nlimits:\n  \"timeout\": 500 # ms\n",
+        );
+    }
+
+    #[test]
+    fn trees() {
+        struct Dir(&'static str, Vec<Dir>);
+
+        impl DebugPls for Dir {
+            fn fmt(&self, f: Formatter<'_>) {
+                f.debug_tree(self.0).children(&self.1).finish();
+            }
+        }
+
+        let src = Dir("src", vec![Dir("layout", vec![Dir("node.rs", vec![])])]);
+        assert_eq!(yaml(&src), "src:\n  - layout:\n      - \"node.rs\"\n");
+    }
+}
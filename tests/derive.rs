@@ -1,7 +1,7 @@
-#![allow(clippy::blacklisted_name)]
+#![allow(clippy::disallowed_names)]
 
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     ops,
 };
 
@@ -190,5 +190,1525 @@ fn ranges() {
     });
 }
 
+#[allow(dead_code)]
 #[derive(DebugPls)]
 enum EmptyEnum {}
+
+#[derive(DebugPls)]
+pub struct Keywords {
+    r#type: &'static str,
+    r#match: Option<r#gen::r#struct>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(DebugPls)]
+pub struct r#struct;
+
+mod r#gen {
+    pub use super::r#struct;
+}
+
+#[test]
+fn raw_identifiers() {
+    let value = Keywords {
+        r#type: "hello",
+        r#match: Some(r#struct),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&value).to_string(),
+        "Keywords {\n    r#type: \"hello\",\n    r#match: Some(r#struct),\n}",
+    );
+
+    struct Manual;
+
+    impl DebugPls for Manual {
+        fn fmt(&self, f: dbg_pls::Formatter<'_>) {
+            f.debug_struct("r#gen::struct").field("type", &1).finish();
+        }
+    }
+    assert_eq!(
+        dbg_pls::pretty(&Manual).to_string(),
+        "r#gen::r#struct { r#type: 1 }",
+    );
+}
+
+#[derive(DebugPls)]
+pub struct EmptyStruct {}
+
+#[derive(DebugPls)]
+pub struct EmptyTupleStruct();
+
+#[derive(DebugPls)]
+pub struct Empties {
+    empty_struct: EmptyStruct,
+    empty_tuple_struct: EmptyTupleStruct,
+    tuple: (),
+    list: Vec<u8>,
+    map: BTreeMap<u8, u8>,
+    set: BTreeSet<u8>,
+}
+
+#[test]
+fn empty_containers() {
+    struct Builders;
+
+    impl DebugPls for Builders {
+        fn fmt(&self, f: dbg_pls::Formatter<'_>) {
+            f.debug_tuple()
+                .field(&dbg_pls::debug_iter(&[] as &[u8]))
+                .field(&Builder(|f| f.debug_struct("Foo").finish()))
+                .field(&Builder(|f| f.debug_tuple_struct("Foo").finish()))
+                .field(&Builder(|f| f.debug_tuple().finish()))
+                .field(&Builder(|f| f.debug_list().finish()))
+                .field(&Builder(|f| f.debug_map().finish()))
+                .field(&Builder(|f| f.debug_set().finish()))
+                .finish();
+        }
+    }
+
+    struct Builder(fn(dbg_pls::Formatter<'_>));
+
+    impl DebugPls for Builder {
+        fn fmt(&self, f: dbg_pls::Formatter<'_>) {
+            (self.0)(f);
+        }
+    }
+
+    assert_eq!(
+        dbg_pls::pretty(&Builders).to_string(),
+        "([], Foo {}, Foo(), (), [], {}, {})",
+    );
+
+    let empties = Empties {
+        empty_struct: EmptyStruct {},
+        empty_tuple_struct: EmptyTupleStruct(),
+        tuple: (),
+        list: vec![],
+        map: BTreeMap::new(),
+        set: BTreeSet::new(),
+    };
+    // the same once the surrounding struct is broken over several lines
+    assert_eq!(
+        dbg_pls::pretty(&empties).to_string(),
+        "Empties {
+    empty_struct: EmptyStruct {},
+    empty_tuple_struct: EmptyTupleStruct(),
+    tuple: (),
+    list: [],
+    map: {},
+    set: {},
+}",
+    );
+}
+
+struct Handle;
+
+#[derive(DebugPls)]
+struct Pool<T> {
+    name: &'static str,
+    #[dbg_pls(opaque)]
+    handle: Handle,
+    #[dbg_pls(opaque)]
+    conns: Vec<T>,
+}
+
+#[derive(DebugPls)]
+struct Wrapped(#[dbg_pls(opaque)] Handle, u8);
+
+#[derive(DebugPls)]
+enum Connection {
+    Open {
+        #[dbg_pls(opaque)]
+        handle: Handle,
+    },
+    Closed(#[dbg_pls(opaque)] Option<Handle>),
+}
+
+#[test]
+fn opaque_fields() {
+    let pool = Pool {
+        name: "db",
+        handle: Handle,
+        conns: vec![1_u8],
+    };
+    assert_eq!(
+        dbg_pls::pretty(&pool).to_string(),
+        r#"Pool {
+    name: "db",
+    handle: "<Handle>",
+    conns: "<Vec<u8>>",
+}"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Wrapped(Handle, 1)).to_string(),
+        r#"Wrapped("<Handle>", 1)"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&[
+            Connection::Open { handle: Handle },
+            Connection::Closed(None)
+        ])
+        .to_string(),
+        r#"[Open { handle: "<Handle>" }, Closed("<Option<Handle>>")]"#,
+    );
+}
+
+struct Frame;
+
+#[derive(DebugPls)]
+struct Decoder {
+    name: &'static str,
+    #[dbg_pls(len_only)]
+    frames: Vec<Frame>,
+    #[dbg_pls(len_only = 2)]
+    cache: std::collections::BTreeMap<u8, u8>,
+}
+
+#[derive(DebugPls)]
+struct Buffered(#[dbg_pls(len_only = 3)] Vec<u8>);
+
+#[test]
+fn len_only_fields() {
+    let decoder = Decoder {
+        name: "h264",
+        frames: vec![Frame, Frame, Frame],
+        cache: (0..2).map(|i| (i, i)).collect(),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&decoder).to_string(),
+        r#"Decoder {
+    name: "h264",
+    frames: Vec::<Frame>(len = 3),
+    cache: {
+        [0] = 0;
+        [1] = 1;
+    },
+}"#,
+    );
+    let decoder = Decoder {
+        cache: (0..3).map(|i| (i, i)).collect(),
+        ..decoder
+    };
+    assert_eq!(
+        dbg_pls::pretty(&decoder).to_string(),
+        r#"Decoder {
+    name: "h264",
+    frames: Vec::<Frame>(len = 3),
+    cache: BTreeMap::<u8, u8>(len = 3),
+}"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Buffered(vec![1, 2, 3])).to_string(),
+        "Buffered([1, 2, 3])",
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Buffered(vec![1, 2, 3, 4])).to_string(),
+        "Buffered(Vec::<u8>(len = 4))",
+    );
+}
+
+#[derive(DebugPls)]
+struct MemoryStats {
+    #[dbg_pls(bytes)]
+    resident: u64,
+    #[dbg_pls(bytes)]
+    peak: usize,
+    #[dbg_pls(bytes)]
+    delta: i64,
+    allocations: u32,
+}
+
+#[derive(DebugPls)]
+struct Quota<T> {
+    #[dbg_pls(bytes)]
+    limit: T,
+}
+
+#[test]
+fn bytes_fields() {
+    let stats = MemoryStats {
+        resident: 1_572_864,
+        peak: 512,
+        delta: -4096,
+        allocations: 12,
+    };
+    assert_eq!(
+        dbg_pls::pretty(&stats).to_string(),
+        "MemoryStats {
+    resident: 1572864 /* 1.5 MiB */,
+    peak: 512 /* 512 B */,
+    delta: -4096,
+    allocations: 12,
+}",
+    );
+    assert_eq!(
+        dbg_pls::pretty(&stats).humanized().to_string(),
+        r#"MemoryStats {
+    resident: "1.5 MiB",
+    peak: "512 B",
+    delta: -4096,
+    allocations: 12,
+}"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&MemoryStats {
+            resident: u64::MAX,
+            ..stats
+        })
+        .suffixes()
+        .to_string(),
+        "MemoryStats {
+    resident: 18446744073709551615u64 /* 16 EiB */,
+    peak: 512usize /* 512 B */,
+    delta: -4096i64,
+    allocations: 12u32,
+}",
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Quota { limit: 2048_u32 }).to_string(),
+        "Quota { limit: 2048 /* 2 KiB */ }",
+    );
+}
+
+#[derive(DebugPls)]
+enum Fixture {
+    Rows(#[dbg_pls(vec)] Vec<Option<Vec<u8>>>, Vec<u8>),
+    Slice {
+        #[dbg_pls(vec)]
+        rows: &'static [u8],
+    },
+}
+
+#[test]
+fn vec_fields() {
+    assert_eq!(
+        dbg_pls::pretty(&Fixture::Rows(vec![Some(vec![1]), None], vec![2])).to_string(),
+        "Rows(vec![Some(vec![1]), None], [2])",
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Fixture::Slice { rows: &[1, 2] }).to_string(),
+        "Slice { rows: vec![1, 2] }",
+    );
+    // arrays are still arrays
+    assert_eq!(
+        dbg_pls::pretty(&(vec![[1]], VecDeque::from([2]), [3]))
+            .vec_macros()
+            .to_string(),
+        "(vec![[1]], vec![2], [3])",
+    );
+}
+
+#[derive(DebugPls)]
+#[dbg_pls(qualify_variants)]
+enum Shape<T> {
+    Circle { radius: T },
+    Square(T),
+    Empty,
+}
+
+#[test]
+fn qualified_variants() {
+    assert_eq!(
+        dbg_pls::pretty(&[Shape::Circle { radius: 1 }, Shape::Square(2), Shape::Empty]).to_string(),
+        "[Shape::Circle { radius: 1 }, Shape::Square(2), Shape::Empty]",
+    );
+}
+
+mod units {
+    use dbg_pls::DebugPls;
+
+    #[derive(DebugPls)]
+    #[dbg_pls(module_path)]
+    pub struct Meters(pub u32);
+
+    #[derive(DebugPls)]
+    #[dbg_pls(module_path, qualify_variants)]
+    pub enum Angle {
+        Deg(u32),
+        Right,
+    }
+}
+
+#[test]
+fn module_paths() {
+    assert_eq!(
+        dbg_pls::pretty(&(units::Meters(5), units::Angle::Deg(90), units::Angle::Right))
+            .to_string(),
+        "\
+(
+    derive::units::Meters(5),
+    derive::units::Angle::Deg(90),
+    derive::units::Angle::Right,
+)",
+    );
+}
+
+#[derive(DebugPls)]
+struct Scene {
+    nodes: Vec<Box<dyn DebugPls>>,
+    root: std::rc::Rc<dyn DebugPls>,
+    camera: &'static dyn DebugPls,
+}
+
+#[test]
+fn trait_objects() {
+    let scene = Scene {
+        nodes: vec![Box::new(1), Box::new("two"), Box::new(Some(3.0))],
+        root: std::rc::Rc::new(()),
+        camera: &[1, 2],
+    };
+    assert_eq!(
+        dbg_pls::pretty(&scene).to_string(),
+        r#"Scene {
+    nodes: [1, "two", Some(3.0)],
+    root: (),
+    camera: [1, 2],
+}"#,
+    );
+    let node: &dyn DebugPls = &*scene.nodes[1];
+    assert_eq!(dbg_pls::pretty(node).to_string(), r#""two""#);
+    assert_eq!(dbg_pls::pretty(&*scene.root).to_string(), "()");
+}
+
+#[test]
+fn float_precision() {
+    #[derive(DebugPls)]
+    struct Coords {
+        at: (f64, f32),
+        path: Vec<f64>,
+    }
+
+    let coords = Coords {
+        at: (0.1 + 0.2, 2.0 / 3.0),
+        path: vec![-0.0004, 1e300, 12.5],
+    };
+    assert_eq!(
+        dbg_pls::pretty(&coords).precision(2).suffixes().to_string(),
+        "Coords {
+    at: (0.3f64, 0.67f32),
+    path: [-0.0f64, 1e300f64, 12.5f64],
+}",
+    );
+}
+
+#[derive(DebugPls)]
+struct Paths {
+    #[dbg_pls(raw)]
+    windows: Vec<&'static str>,
+    unix: &'static str,
+}
+
+#[test]
+fn raw_fields() {
+    let paths = Paths {
+        windows: vec![r"C:\Program Files", "plain", "line\nbreak"],
+        unix: r"/a\b",
+    };
+    assert_eq!(
+        dbg_pls::pretty(&paths).to_string(),
+        r#"Paths {
+    windows: [r"C:\Program Files", "plain", "line\nbreak"],
+    unix: "/a\\b",
+}"#,
+    );
+}
+
+mod generic_names {
+    use dbg_pls::DebugPls;
+
+    #[derive(DebugPls)]
+    #[dbg_pls(generics)]
+    pub struct Rows<'a, T, const N: usize> {
+        pub rows: &'a [T; N],
+    }
+
+    #[derive(DebugPls)]
+    #[dbg_pls(generics)]
+    pub enum Maybe<T> {
+        Just(T),
+        Nothing,
+    }
+
+    #[derive(DebugPls)]
+    #[dbg_pls(generics, module_path, qualify_variants)]
+    pub enum Either<L, R> {
+        Left(L),
+        Right(R),
+    }
+}
+
+#[test]
+fn generic_names() {
+    use generic_names::{Either, Maybe, Rows};
+
+    assert_eq!(
+        dbg_pls::pretty(&Rows { rows: &[1_i64, 2] }).to_string(),
+        "Rows::<i64, 2> { rows: [1, 2] }",
+    );
+    assert_eq!(
+        dbg_pls::pretty(&(Maybe::Just(1_u8), Maybe::<Option<u8>>::Nothing)).to_string(),
+        "(Just::<u8>(1), Nothing::<Option<u8>>)",
+    );
+    assert_eq!(
+        dbg_pls::pretty(&[Either::<u8, bool>::Left(1), Either::Right(true)]).to_string(),
+        "[
+    derive::generic_names::Either::<u8, bool>::Left(1),
+    derive::generic_names::Either::<u8, bool>::Right(true),
+]",
+    );
+}
+
+// the skipped fields are never read
+#[allow(dead_code)]
+#[derive(DebugPls)]
+struct Session {
+    peer: &'static str,
+    #[dbg_pls(skip)]
+    socket: Frame,
+    retries: u8,
+}
+
+#[derive(DebugPls)]
+struct Channel(u32, #[dbg_pls(skip)] Frame, bool);
+
+#[allow(dead_code)]
+#[derive(DebugPls)]
+enum Event {
+    Open {
+        #[dbg_pls(skip)]
+        socket: Frame,
+        peer: &'static str,
+    },
+    Read(#[dbg_pls(skip)] Frame, usize),
+}
+
+#[test]
+fn skipped_fields() {
+    let session = Session {
+        peer: "a",
+        socket: Frame,
+        retries: 2,
+    };
+    assert_eq!(
+        dbg_pls::pretty(&session).to_string(),
+        r#"Session { peer: "a", retries: 2 }"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Channel(1, Frame, true)).to_string(),
+        "Channel(1, true)",
+    );
+    let events = [
+        Event::Open {
+            socket: Frame,
+            peer: "a",
+        },
+        Event::Read(Frame, 4),
+    ];
+    assert_eq!(
+        dbg_pls::pretty(&events).to_string(),
+        r#"[Open { peer: "a" }, Read(4)]"#,
+    );
+}
+
+mod renamed {
+    use dbg_pls::DebugPls;
+
+    #[derive(DebugPls)]
+    #[dbg_pls(rename = "Request", module_path)]
+    pub struct Req {
+        #[dbg_pls(rename = "type")]
+        pub kind: u8,
+        #[dbg_pls(rename = "len", len_only)]
+        pub body: Vec<u8>,
+    }
+
+    #[derive(DebugPls)]
+    #[dbg_pls(rename = "State", qualify_variants)]
+    pub enum St {
+        #[dbg_pls(rename = "Idle")]
+        I,
+        #[dbg_pls(rename = "Busy")]
+        B {
+            #[dbg_pls(rename = "job_id")]
+            id: u32,
+        },
+    }
+}
+
+#[test]
+fn renamed() {
+    use renamed::{Req, St};
+
+    let req = Req {
+        kind: 1,
+        body: vec![0; 3],
+    };
+    assert_eq!(
+        dbg_pls::pretty(&req).to_string(),
+        "\
+derive::renamed::Request {
+    r#type: 1,
+    len: Vec::<u8>(len = 3),
+}",
+    );
+    assert_eq!(
+        dbg_pls::pretty(&(St::I, St::B { id: 7 })).to_string(),
+        "(State::Idle, State::Busy { job_id: 7 })",
+    );
+}
+
+#[derive(DebugPls)]
+#[dbg_pls(rename_all = "SCREAMING_SNAKE_CASE")]
+struct Env {
+    home_dir: &'static str,
+    #[dbg_pls(rename = "path")]
+    search_path: &'static str,
+}
+
+#[derive(DebugPls)]
+#[dbg_pls(rename_all = "snake_case")]
+enum Mode {
+    ReadOnly,
+    ReadWrite { keep_open: bool },
+}
+
+#[test]
+fn renamed_all() {
+    let env = Env {
+        home_dir: "/",
+        search_path: "/bin",
+    };
+    assert_eq!(
+        dbg_pls::pretty(&env).to_string(),
+        r#"Env { HOME_DIR: "/", path: "/bin" }"#,
+    );
+    // the fields of a variant keep their names
+    assert_eq!(
+        dbg_pls::pretty(&[Mode::ReadOnly, Mode::ReadWrite { keep_open: true }]).to_string(),
+        "[read_only, read_write { keep_open: true }]",
+    );
+}
+
+mod formats {
+    use dbg_pls::Formatter;
+
+    pub fn micros(value: &std::time::Duration, f: Formatter<'_>) {
+        f.debug_tuple_struct("Micros")
+            .field(&value.as_micros())
+            .finish();
+    }
+}
+
+#[derive(DebugPls)]
+struct Timing {
+    #[dbg_pls(with = "formats::micros")]
+    elapsed: std::time::Duration,
+}
+
+#[derive(DebugPls)]
+enum Sample {
+    Taken(#[dbg_pls(with = "formats::micros")] std::time::Duration),
+}
+
+#[test]
+fn with_fields() {
+    let elapsed = std::time::Duration::from_millis(3);
+    assert_eq!(
+        dbg_pls::pretty(&(Timing { elapsed }, Sample::Taken(elapsed))).to_string(),
+        "(Timing { elapsed: Micros(3000) }, Taken(Micros(3000)))",
+    );
+}
+
+// the redacted fields are never read
+#[allow(dead_code)]
+#[derive(DebugPls)]
+struct Credentials {
+    #[dbg_pls(redact)]
+    token: &'static str,
+    #[dbg_pls(redact = "<key>")]
+    key: [u8; 4],
+}
+
+#[test]
+fn redacted_fields() {
+    let credentials = Credentials {
+        token: "s3cr3t",
+        key: [1, 2, 3, 4],
+    };
+    assert_eq!(
+        dbg_pls::pretty(&credentials).to_string(),
+        r#"Credentials {
+    token: "[REDACTED]",
+    key: "<key>",
+}"#,
+    );
+    let colored = dbg_pls::color(&credentials).to_string();
+    assert!(colored.contains("[REDACTED]") && !colored.contains("s3cr3t"));
+}
+
+#[derive(DebugPls)]
+#[dbg_pls(transparent)]
+struct Distance(f32);
+
+// the skipped field is never read
+#[allow(dead_code)]
+#[derive(DebugPls)]
+#[dbg_pls(transparent)]
+struct Labelled<T> {
+    #[dbg_pls(skip)]
+    label: &'static str,
+    #[dbg_pls(len_only)]
+    items: Vec<T>,
+}
+
+#[test]
+fn transparent() {
+    let labelled = Labelled {
+        label: "a",
+        items: vec![1, 2, 3],
+    };
+    assert_eq!(
+        dbg_pls::pretty(&(Distance(1.5), labelled)).to_string(),
+        "(1.5, Vec::<i32>(len = 3))",
+    );
+}
+
+#[derive(DebugPls)]
+struct Tls {
+    cert: &'static str,
+    verify: bool,
+}
+
+#[derive(DebugPls)]
+#[dbg_pls(rename_all = "camelCase")]
+struct Listener {
+    bind_addr: &'static str,
+    #[dbg_pls(flatten)]
+    tls: Tls,
+    // not a struct, so written as the field
+    #[dbg_pls(flatten)]
+    backlog: u32,
+}
+
+#[derive(DebugPls)]
+enum Upstream {
+    Secure {
+        host: &'static str,
+        #[dbg_pls(flatten)]
+        tls: Tls,
+    },
+}
+
+#[test]
+fn flattened_fields() {
+    let tls = || Tls {
+        cert: "a.pem",
+        verify: true,
+    };
+    let listener = Listener {
+        bind_addr: "::",
+        tls: tls(),
+        backlog: 128,
+    };
+    assert_eq!(
+        dbg_pls::pretty(&listener).to_string(),
+        r#"Listener {
+    bindAddr: "::",
+    cert: "a.pem",
+    verify: true,
+    backlog: 128,
+}"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Upstream::Secure {
+            host: "a",
+            tls: tls()
+        })
+        .to_string(),
+        r#"Secure {
+    host: "a",
+    cert: "a.pem",
+    verify: true,
+}"#,
+    );
+}
+
+mod bounds {
+    use dbg_pls::DebugPls;
+
+    /// Has no `DebugPls` impl
+    pub struct Callback;
+
+    #[derive(DebugPls)]
+    #[dbg_pls(bound = "")]
+    pub struct Hook<F> {
+        pub name: &'static str,
+        #[dbg_pls(opaque)]
+        pub callback: F,
+    }
+
+    #[derive(DebugPls)]
+    #[dbg_pls(bound = "T: DebugPls + Copy")]
+    pub enum Watch<T, F> {
+        Value(T),
+        Hook(#[dbg_pls(skip)] F),
+    }
+}
+
+#[test]
+fn custom_bounds() {
+    let hook = bounds::Hook {
+        name: "on_exit",
+        callback: bounds::Callback,
+    };
+    assert_eq!(
+        dbg_pls::pretty(&hook).to_string(),
+        r#"Hook {
+    name: "on_exit",
+    callback: "<Callback>",
+}"#,
+    );
+    let watches = [
+        bounds::Watch::Value(1),
+        bounds::Watch::Hook(bounds::Callback),
+    ];
+    assert_eq!(dbg_pls::pretty(&watches).to_string(), "[Value(1), Hook()]",);
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+#[derive(DebugPls)]
+struct Sparse {
+    #[dbg_pls(skip_if = "Option::is_none")]
+    user: Option<&'static str>,
+    #[dbg_pls(skip_if = "is_zero", bytes)]
+    size: u32,
+    #[dbg_pls(flatten, skip_if = "Option::is_none")]
+    tls: Option<Tls>,
+}
+
+#[derive(DebugPls)]
+enum Change {
+    Resize {
+        #[dbg_pls(skip_if = "is_zero")]
+        width: u32,
+        #[dbg_pls(skip_if = "is_zero")]
+        height: u32,
+    },
+}
+
+#[test]
+fn skipped_if() {
+    let sparse = Sparse {
+        user: None,
+        size: 0,
+        tls: None,
+    };
+    assert_eq!(dbg_pls::pretty(&sparse).to_string(), "Sparse {}");
+    let sparse = Sparse {
+        user: Some("a"),
+        size: 2048,
+        tls: Some(Tls {
+            cert: "a.pem",
+            verify: false,
+        }),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&sparse).to_string(),
+        r#"Sparse {
+    user: Some("a"),
+    size: 2048 /* 2 KiB */,
+    tls: Some(Tls {
+        cert: "a.pem",
+        verify: false,
+    }),
+}"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Change::Resize {
+            width: 0,
+            height: 4
+        })
+        .to_string(),
+        "Resize { height: 4 }",
+    );
+}
+
+#[derive(DebugPls)]
+struct Device {
+    #[dbg_pls(hex)]
+    base: usize,
+    #[dbg_pls(hex)]
+    offset: i16,
+    #[dbg_pls(binary)]
+    masks: Vec<u16>,
+    #[dbg_pls(hex)]
+    irq: Option<u8>,
+}
+
+#[test]
+fn radix_fields() {
+    let device = Device {
+        base: 0xFEC0_0000,
+        offset: -0x20,
+        masks: vec![0b1111_0000, 0],
+        irq: Some(11),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&device).to_string(),
+        "\
+Device {
+    base: 0xFEC0_0000,
+    offset: -0x20,
+    masks: [0b1111_0000, 0b0],
+    irq: Some(0xB),
+}",
+    );
+}
+
+/// Displays well, but has no `DebugPls` impl
+struct Version(u32, u32);
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}.{}", self.0, self.1)
+    }
+}
+
+#[derive(DebugPls)]
+enum Release {
+    Stable(#[dbg_pls(display)] Version),
+    Nightly {
+        #[dbg_pls(display)]
+        base: Version,
+        #[dbg_pls(display)]
+        date: &'static str,
+    },
+}
+
+#[test]
+fn display_fields() {
+    assert_eq!(
+        dbg_pls::pretty(&Release::Stable(Version(1, 2))).to_string(),
+        r#"Stable("v1.2")"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&Release::Nightly {
+            base: Version(1, 3),
+            date: "2024-01-01"
+        })
+        .to_string(),
+        r#"Nightly {
+    base: "v1.3",
+    date: "2024-01-01",
+}"#,
+    );
+}
+
+/// Implements no more than `Debug`
+// the fields are only read by the `Debug` impl
+#[allow(dead_code)]
+#[derive(Debug)]
+enum Level {
+    Warn,
+    Custom { severity: u8 },
+}
+
+#[derive(DebugPls)]
+struct Filter {
+    #[dbg_pls(debug)]
+    levels: Vec<Level>,
+    // not Rust, so written as it is
+    #[dbg_pls(debug)]
+    targets: std::collections::BTreeMap<&'static str, bool>,
+}
+
+#[test]
+fn debug_fields() {
+    let filter = Filter {
+        levels: vec![Level::Warn, Level::Custom { severity: 7 }],
+        targets: [("net", true)].iter().copied().collect(),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&filter).to_string(),
+        r#"Filter {
+    levels: [Warn, Custom { severity: 7 }],
+    targets: {"net": true},
+}"#,
+    );
+    // parsed, so broken over lines like any other list
+    let filter = Filter {
+        levels: (0..4).map(|severity| Level::Custom { severity }).collect(),
+        targets: std::collections::BTreeMap::new(),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&filter).to_string(),
+        r#"Filter {
+    levels: [
+        Custom { severity: 0 },
+        Custom { severity: 1 },
+        Custom { severity: 2 },
+        Custom { severity: 3 },
+    ],
+    targets: {},
+}"#,
+    );
+}
+
+#[derive(DebugPls, PartialEq)]
+#[dbg_pls(std_debug)]
+enum Token<'a> {
+    Ident(&'a str),
+    Number { value: u64, radix: u32 },
+}
+
+#[test]
+fn std_debug() {
+    let tokens = [
+        Token::Ident("a_long_enough_identifier"),
+        Token::Number {
+            value: 255,
+            radix: 16,
+        },
+    ];
+    assert_eq!(
+        format!("{:?}", tokens),
+        r#"[Ident("a_long_enough_identifier"), Number { value: 255, radix: 16 }]"#,
+    );
+    // each of them is pretty printed in the list `Debug` breaks over lines
+    assert_eq!(
+        format!("{:#?}", tokens),
+        r#"[
+    Ident("a_long_enough_identifier"),
+    Number { value: 255, radix: 16 },
+]"#,
+    );
+    // the impl is what `assert_eq!` writes the values with
+    let message = std::panic::catch_unwind(|| assert_eq!(tokens[0], Token::Ident("b")))
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    assert!(message.contains(r#"left: Ident("a_long_enough_identifier")"#));
+}
+
+#[derive(DebugPls)]
+#[dbg_pls(impl_display, std_debug)]
+struct Report<T> {
+    title: &'static str,
+    rows: Vec<T>,
+}
+
+#[test]
+fn impl_display() {
+    let report = Report {
+        title: "a title long enough to break the report",
+        rows: vec![1, 2],
+    };
+    let pretty = r#"Report {
+    title: "a title long enough to break the report",
+    rows: [1, 2],
+}"#;
+    assert_eq!(report.to_string(), pretty);
+    assert_eq!(format!("{:#?}", report), pretty);
+    assert_eq!(
+        format!("{:?}", report),
+        r#"Report { title: "a title long enough to break the report", rows: [1, 2] }"#,
+    );
+}
+
+mod phantom {
+    use std::marker::PhantomData;
+
+    use dbg_pls::DebugPls;
+
+    /// Has no `DebugPls` impl
+    pub struct Meters;
+
+    #[derive(DebugPls)]
+    pub struct Quantity<U> {
+        pub value: f64,
+        pub unit: PhantomData<U>,
+    }
+
+    #[derive(DebugPls)]
+    pub enum Tagged<T, U> {
+        Plain(T, core::marker::PhantomData<fn() -> U>),
+        Shown {
+            value: T,
+            #[dbg_pls(show)]
+            tag: PhantomData<U>,
+        },
+    }
+
+    pub mod units {
+        use dbg_pls::DebugPls;
+
+        /// Not the marker, only named like it
+        #[derive(DebugPls)]
+        pub struct PhantomData(pub u8);
+    }
+
+    #[derive(DebugPls)]
+    pub struct Reading {
+        pub unit: units::PhantomData,
+    }
+}
+
+#[test]
+fn phantom_fields() {
+    let quantity = phantom::Quantity::<phantom::Meters> {
+        value: 1.5,
+        unit: std::marker::PhantomData,
+    };
+    assert_eq!(
+        dbg_pls::pretty(&quantity).to_string(),
+        "Quantity { value: 1.5 }"
+    );
+    let tagged: [phantom::Tagged<u8, Vec<u8>>; 2] = [
+        phantom::Tagged::Plain(1, std::marker::PhantomData),
+        phantom::Tagged::Shown {
+            value: 2,
+            tag: std::marker::PhantomData,
+        },
+    ];
+    assert_eq!(
+        dbg_pls::pretty(&tagged).to_string(),
+        "\
+[
+    Plain(1),
+    Shown {
+        value: 2,
+        tag: PhantomData::<Vec<u8>>,
+    },
+]",
+    );
+    let reading = phantom::Reading {
+        unit: phantom::units::PhantomData(3),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&reading).to_string(),
+        "Reading { unit: PhantomData(3) }",
+    );
+}
+
+mod conversions {
+    use dbg_pls::DebugPls;
+
+    #[derive(DebugPls)]
+    pub struct Celsius(pub f32);
+
+    impl From<i16> for Celsius {
+        fn from(tenths: i16) -> Self {
+            Celsius(f32::from(tenths) / 10.0)
+        }
+    }
+
+    #[derive(DebugPls)]
+    pub struct Reading {
+        #[dbg_pls(as = "Celsius")]
+        pub temperature: i16,
+        #[dbg_pls(as = "String")]
+        pub sensor: &'static str,
+    }
+
+    #[derive(DebugPls)]
+    pub enum Event {
+        Sample(#[dbg_pls(as = "Celsius")] i16),
+    }
+
+    #[derive(DebugPls)]
+    pub struct Raw<T> {
+        #[dbg_pls(as = "Celsius")]
+        pub tenths: T,
+        #[dbg_pls(as = "Vec<T>")]
+        pub history: [T; 2],
+    }
+}
+
+#[test]
+fn converted_fields() {
+    let reading = conversions::Reading {
+        temperature: 215,
+        sensor: "a",
+    };
+    assert_eq!(
+        dbg_pls::pretty(&reading).to_string(),
+        r#"Reading {
+    temperature: Celsius(21.5),
+    sensor: "a",
+}"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&conversions::Event::Sample(-5)).to_string(),
+        "Sample(Celsius(-0.5))",
+    );
+    let raw = conversions::Raw {
+        tenths: 20_i16,
+        history: [1, 2],
+    };
+    assert_eq!(
+        dbg_pls::pretty(&raw).to_string(),
+        "Raw {
+    tenths: Celsius(2.0),
+    history: [1, 2],
+}",
+    );
+}
+
+mod field_bounds {
+    use dbg_pls::{DebugPls, Formatter};
+    use std::{marker::PhantomData, rc::Rc};
+
+    pub trait Store {
+        type Key;
+    }
+
+    /// Has no `DebugPls` impl
+    pub struct Disk;
+
+    impl Store for Disk {
+        type Key = u32;
+    }
+
+    #[derive(DebugPls)]
+    pub struct Index<S: Store> {
+        pub keys: Vec<S::Key>,
+        pub store: Rc<str>,
+    }
+
+    #[derive(DebugPls)]
+    pub struct Chain<T> {
+        pub value: T,
+        pub next: Option<Box<Chain<T>>>,
+    }
+
+    #[derive(DebugPls)]
+    pub struct Lookup<S: Store> {
+        pub key: <S as Store>::Key,
+    }
+
+    /// Needs no `DebugPls` impl of `T`
+    pub struct Handle<T>(pub PhantomData<T>);
+
+    impl<T> DebugPls for Handle<T> {
+        fn fmt(&self, f: Formatter<'_>) {
+            f.debug_ident("Handle");
+        }
+    }
+
+    #[derive(DebugPls)]
+    pub struct Owner<T> {
+        pub handle: Handle<T>,
+        pub handles: Vec<Handle<T>>,
+    }
+
+    // `Tree<T>: DebugPls` needs `Forest<T>: DebugPls`, which needs
+    // `Tree<T>: DebugPls` again, so one of them has to be bounded by hand
+    #[derive(DebugPls)]
+    #[dbg_pls(bound = "T: DebugPls")]
+    pub struct Tree<T> {
+        pub forest: Box<Forest<T>>,
+    }
+
+    #[derive(DebugPls)]
+    pub struct Forest<T> {
+        pub tree: Option<Box<Tree<T>>>,
+        pub value: T,
+    }
+}
+
+#[test]
+fn field_type_bounds() {
+    let index = field_bounds::Index::<field_bounds::Disk> {
+        keys: vec![1, 2],
+        store: "disk".into(),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&index).to_string(),
+        r#"Index {
+    keys: [1, 2],
+    store: "disk",
+}"#,
+    );
+    let lookup = field_bounds::Lookup::<field_bounds::Disk> { key: 3 };
+    assert_eq!(dbg_pls::pretty(&lookup).to_string(), "Lookup { key: 3 }");
+    let chain = field_bounds::Chain {
+        value: 1,
+        next: Some(Box::new(field_bounds::Chain {
+            value: 2,
+            next: None,
+        })),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&chain).to_string(),
+        "Chain {
+    value: 1,
+    next: Some(Chain { value: 2, next: None }),
+}",
+    );
+    // `Disk` has no `DebugPls` impl, which `Handle<Disk>` doesn't need
+    let owner = field_bounds::Owner::<field_bounds::Disk> {
+        handle: field_bounds::Handle(std::marker::PhantomData),
+        handles: vec![],
+    };
+    assert_eq!(
+        dbg_pls::pretty(&owner).to_string(),
+        "Owner {
+    handle: Handle,
+    handles: [],
+}",
+    );
+    // mutually recursive types
+    let tree = field_bounds::Tree {
+        forest: Box::new(field_bounds::Forest {
+            tree: None,
+            value: 1_i32,
+        }),
+    };
+    assert_eq!(
+        dbg_pls::pretty(&tree).to_string(),
+        "Tree {
+    forest: Forest { tree: None, value: 1 },
+}",
+    );
+}
+
+mod defaults {
+    use dbg_pls::DebugPls;
+
+    #[derive(DebugPls)]
+    pub struct Buf<const N: usize> {
+        pub data: [u8; N],
+    }
+
+    #[derive(DebugPls)]
+    #[dbg_pls(generics)]
+    pub struct Ring<T = u8, const N: usize = 2> {
+        pub slots: [T; N],
+        pub head: usize,
+    }
+
+    #[derive(DebugPls)]
+    pub enum Slot<T, E = ()> {
+        Full(T),
+        Empty(E),
+    }
+}
+
+#[test]
+fn const_and_default_params() {
+    use defaults::{Buf, Ring, Slot};
+
+    assert_eq!(
+        dbg_pls::pretty(&Buf { data: [1, 2, 3] }).to_string(),
+        "Buf { data: [1, 2, 3] }",
+    );
+    let ring: Ring = Ring {
+        slots: [1, 2],
+        head: 0,
+    };
+    assert_eq!(
+        dbg_pls::pretty(&ring).to_string(),
+        "Ring::<u8, 2> {
+    slots: [1, 2],
+    head: 0,
+}",
+    );
+    assert_eq!(
+        dbg_pls::pretty(&[Slot::<_>::Full(1), Slot::Empty(())]).to_string(),
+        "[Full(1), Empty(())]",
+    );
+}
+
+mod variants {
+    use dbg_pls::DebugPls;
+
+    /// Has no `DebugPls` impl
+    pub struct Socket;
+
+    // the fields of skipped variants are never read
+    #[allow(dead_code)]
+    #[derive(DebugPls)]
+    #[dbg_pls(qualify_variants)]
+    pub enum Conn<S> {
+        #[dbg_pls(skip)]
+        Open(S, u16),
+        #[dbg_pls(skip, rename = "Shut")]
+        Closed {
+            reason: S,
+        },
+        #[dbg_pls(transparent)]
+        Pending {
+            #[dbg_pls(skip)]
+            socket: Option<S>,
+            attempts: u8,
+        },
+        Idle,
+    }
+}
+
+#[test]
+fn variant_args() {
+    use variants::{Conn, Socket};
+
+    let conns = [
+        Conn::Open(Socket, 80),
+        Conn::Closed { reason: Socket },
+        Conn::Pending {
+            socket: None,
+            attempts: 3,
+        },
+        Conn::Idle,
+    ];
+    assert_eq!(
+        dbg_pls::pretty(&conns).to_string(),
+        "[Conn::Open(..), Conn::Shut { .. }, 3, Conn::Idle]",
+    );
+}
+
+mod non_exhaustive {
+    use dbg_pls::DebugPls;
+
+    #[derive(DebugPls)]
+    #[dbg_pls(non_exhaustive, qualify_variants)]
+    pub enum Request {
+        Get {
+            path: &'static str,
+            #[dbg_pls(redact)]
+            token: &'static str,
+        },
+        Ping(u8),
+        Close,
+    }
+
+    #[derive(DebugPls)]
+    pub struct Limits {
+        pub max: u32,
+    }
+
+    #[derive(DebugPls)]
+    #[dbg_pls(non_exhaustive)]
+    pub struct Server {
+        pub port: u16,
+        #[dbg_pls(flatten)]
+        pub limits: Limits,
+    }
+}
+
+#[test]
+fn non_exhaustive_types() {
+    use non_exhaustive::{Limits, Request, Server};
+
+    let requests = [
+        Request::Get {
+            path: "/",
+            token: "secret",
+        },
+        Request::Ping(1),
+        Request::Close,
+    ];
+    assert_eq!(
+        dbg_pls::pretty(&requests).to_string(),
+        r#"[
+    Request::Get {
+        path: "/",
+        token: "[REDACTED]",
+        ..
+    },
+    Request::Ping(1, ..),
+    Request::Close,
+]"#,
+    );
+    let server = Server {
+        port: 80,
+        limits: Limits { max: 8 },
+    };
+    assert_eq!(
+        dbg_pls::pretty(&server).to_string(),
+        "Server { port: 80, max: 8, .. }",
+    );
+}
+
+mod sorted {
+    use dbg_pls::DebugPls;
+
+    #[derive(DebugPls)]
+    #[dbg_pls(sort_fields, rename_all = "camelCase")]
+    pub struct Job {
+        pub retry_count: u8,
+        #[dbg_pls(skip_if = "Option::is_none")]
+        pub deadline: Option<u32>,
+        pub name: &'static str,
+    }
+
+    #[derive(DebugPls)]
+    #[dbg_pls(sort_fields)]
+    pub enum Shape {
+        Rect { w: u8, h: u8 },
+        Line(u8, u8),
+    }
+}
+
+#[test]
+fn sorted_fields() {
+    use sorted::{Job, Shape};
+
+    let job = Job {
+        retry_count: 1,
+        deadline: Some(5),
+        name: "build",
+    };
+    assert_eq!(
+        dbg_pls::pretty(&job).to_string(),
+        r#"Job {
+    deadline: Some(5),
+    name: "build",
+    retryCount: 1,
+}"#,
+    );
+    assert_eq!(
+        dbg_pls::pretty(&[Shape::Rect { w: 1, h: 2 }, Shape::Line(3, 4)]).to_string(),
+        "[Rect { h: 2, w: 1 }, Line(3, 4)]",
+    );
+}
+
+mod truncated {
+    use dbg_pls::DebugPls;
+    use std::collections::BTreeMap;
+
+    #[derive(DebugPls)]
+    pub struct Dump {
+        #[dbg_pls(truncate = 2)]
+        pub rows: Vec<Vec<u8>>,
+        #[dbg_pls(truncate = 1)]
+        pub index: BTreeMap<&'static str, u8>,
+        #[dbg_pls(truncate = 4)]
+        pub name: Box<str>,
+        #[dbg_pls(truncate = 4)]
+        pub short: &'static str,
+    }
+}
+
+#[test]
+fn truncated_fields() {
+    let dump = truncated::Dump {
+        rows: vec![vec![1, 2, 3]; 5],
+        index: [("a", 1), ("b", 2), ("c", 3)].iter().copied().collect(),
+        name: "résumé".into(),
+        short: "ok",
+    };
+    assert_eq!(
+        dbg_pls::pretty(&dump).to_string(),
+        r#"Dump {
+    rows: [[1, 2, 3], [1, 2, 3], .. /* 3 more */],
+    index: {
+        ["a"] = 1;
+        .. /* 2 more */
+    },
+    name: "résu" /* 2 more chars */,
+    short: "ok",
+}"#,
+    );
+}
@@ -0,0 +1,393 @@
+//! Golden tests of the built-in layout, whose output is kept stable across
+//! releases. Only the layout decisions are pinned here, so the snapshots
+//! hold the plain output.
+#![cfg(not(feature = "syn-backend"))]
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use dbg_pls::{pretty, Budget, DebugPls, Formatter};
+
+macro_rules! assert_layout_snapshot {
+    ($expr:expr) => {
+        let expr = $expr;
+        insta::assert_snapshot!(
+            insta::_macro_support::AutoName,
+            &pretty(&expr).to_string(),
+            stringify!($expr)
+        );
+    };
+}
+
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+impl DebugPls for Point {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_struct("Point")
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .finish();
+    }
+}
+
+struct Wrapper<T>(T);
+
+impl<T: DebugPls> DebugPls for Wrapper<T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_tuple_struct("Wrapper").field(&self.0).finish();
+    }
+}
+
+#[test]
+fn list_at_the_margin() {
+    assert_layout_snapshot!((0..21).collect::<Vec<u32>>());
+}
+
+#[test]
+fn list_past_the_margin() {
+    assert_layout_snapshot!((0..22).collect::<Vec<u32>>());
+}
+
+#[test]
+fn struct_at_the_max_width() {
+    assert_layout_snapshot!(Point {
+        x: 1_000_000,
+        y: 2_000_000,
+    });
+}
+
+#[test]
+fn struct_past_the_max_width() {
+    assert_layout_snapshot!(Point {
+        x: 100_000_000,
+        y: 200_000_000,
+    });
+}
+
+#[test]
+fn structs_in_a_list() {
+    assert_layout_snapshot!((0..6).map(|i| Point { x: i, y: -i }).collect::<Vec<_>>());
+}
+
+#[test]
+fn tuple_struct_hugging_a_block() {
+    assert_layout_snapshot!(Wrapper(vec!["a string long enough to break the line"; 3]));
+}
+
+#[test]
+fn tuple_struct_around_a_literal() {
+    assert_layout_snapshot!(Wrapper(
+        "a string literal that is long enough to go past the margin on its own line"
+    ));
+}
+
+#[test]
+fn single_element_tuples() {
+    assert_layout_snapshot!(((1,), (("a string long enough to break the line", 2),), ()));
+}
+
+#[test]
+fn long_string() {
+    assert_layout_snapshot!(vec!["a string that is much longer than the margin, which is never broken up however long it gets"]);
+}
+
+#[test]
+fn map_of_structs() {
+    assert_layout_snapshot!((0..3)
+        .map(|i| (format!("key {i}"), Point { x: i, y: i * i }))
+        .collect::<BTreeMap<_, _>>());
+}
+
+#[test]
+fn map_with_a_long_key() {
+    assert_layout_snapshot!(BTreeMap::from([(
+        vec!["a key long enough to be broken over several lines, on its own"; 2],
+        1
+    )]));
+}
+
+#[test]
+fn sets() {
+    assert_layout_snapshot!((
+        BTreeSet::from([1]),
+        BTreeSet::from([1, 2, 3]),
+        BTreeSet::<u8>::new()
+    ));
+}
+
+#[test]
+fn ranges() {
+    assert_layout_snapshot!((
+        1..2,
+        ..=3,
+        Point { x: 4, y: 5 }..Point { x: 6, y: 7 },
+        Some(..)
+    ));
+}
+
+#[test]
+fn deeply_nested() {
+    assert_layout_snapshot!(Some(Some(vec![Some(Wrapper(vec![(
+        Point { x: 1, y: 2 },
+        "a string long enough to break the line",
+        [1.5, -0.0, f64::NAN]
+    )]))])));
+}
+
+#[test]
+fn elided() {
+    let map = (0..50)
+        .map(|i| (i, Point { x: i, y: i }))
+        .collect::<BTreeMap<_, _>>();
+    insta::assert_snapshot!(pretty(&map).budget(Budget::new().bytes(160)).to_string());
+}
+
+struct Table<T>(Vec<T>);
+
+impl<T: DebugPls> DebugPls for Table<T> {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_table().entries(&self.0).finish();
+    }
+}
+
+struct Wide(&'static str);
+
+impl DebugPls for Wide {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_struct("Point").field("x", &self.0).finish();
+    }
+}
+
+#[test]
+fn table() {
+    assert_layout_snapshot!(Table(vec![
+        Point { x: 1, y: -20 },
+        Point { x: 300, y: 4 },
+        Point { x: -5, y: 60_000 },
+    ]));
+}
+
+#[test]
+fn table_of_different_rows() {
+    assert_layout_snapshot!((
+        Table(vec![Some(Point { x: 1, y: 2 }), None]),
+        Table(vec![Wrapper(1), Wrapper(2)]),
+    ));
+}
+
+#[test]
+fn table_too_wide() {
+    assert_layout_snapshot!(Table(vec![
+        Wide("short"),
+        Wide("a string long enough that the row would go past the margin"),
+    ]));
+}
+
+#[test]
+fn elided_table() {
+    let table = Table((0..50).map(|i| Point { x: i, y: i * i }).collect());
+    insta::assert_snapshot!(pretty(&table).budget(Budget::new().bytes(155)).to_string());
+}
+
+enum Entry {
+    File(&'static str),
+    Dir(&'static str, Vec<Entry>),
+}
+
+impl DebugPls for Entry {
+    fn fmt(&self, f: Formatter<'_>) {
+        match self {
+            Entry::File(name) => f.debug_ident(name),
+            Entry::Dir(name, entries) => f.debug_tree(name).children(entries).finish(),
+        }
+    }
+}
+
+fn src() -> Entry {
+    Entry::Dir(
+        "src",
+        vec![
+            Entry::Dir(
+                "layout",
+                vec![
+                    Entry::File("branch"),
+                    Entry::File("node"),
+                    Entry::File("table"),
+                ],
+            ),
+            Entry::Dir("node", vec![Entry::Dir("expr", vec![])]),
+            Entry::File("lib"),
+        ],
+    )
+}
+
+#[test]
+fn tree() {
+    assert_layout_snapshot!(src());
+}
+
+#[test]
+fn tree_of_values_broken_over_lines() {
+    struct Scene;
+
+    impl DebugPls for Scene {
+        fn fmt(&self, f: Formatter<'_>) {
+            f.debug_tree("scene")
+                .child(&Point { x: 1, y: 2 })
+                .child(&vec!["a string long enough to break the line"; 3])
+                .child_with(|f| {
+                    f.debug_tree("camera")
+                        .child(&Wrapper(BTreeMap::from([(1, 2), (3, 4)])))
+                        .finish();
+                })
+                .finish();
+        }
+    }
+
+    assert_layout_snapshot!(Scene);
+}
+
+#[test]
+fn tree_within_a_value() {
+    assert_layout_snapshot!((Some(src()), vec![src()]));
+}
+
+#[test]
+fn multiline_strings() {
+    let request = Wrapper(BTreeMap::from([
+        ("body", "GET / HTTP/1.1\r\nHost: a\r\n\r\n"),
+        ("line", "GET / HTTP/1.1\n"),
+    ]));
+    insta::assert_snapshot!(pretty(&request).multiline_strings().to_string());
+}
+
+/// Lays out its fields with the layout hints of the builders.
+struct Hinted;
+
+impl DebugPls for Hinted {
+    fn fmt(&self, f: Formatter<'_>) {
+        let short = [1, 2];
+        let long: Vec<u32> = (100..130).collect();
+        let map = BTreeMap::from([("a", 1), ("b", 2)]);
+        f.debug_struct("Hinted")
+            .expanded()
+            .field_with("expanded", |f| {
+                f.debug_list().expanded().entries(short).finish()
+            })
+            .field_with("empty", |f| f.debug_list().expanded().finish())
+            .field_with("one", |f| f.debug_set().expanded().entry(&1).finish())
+            .field_with("compact", |f| {
+                f.debug_list().compact().entries(&long).finish()
+            })
+            .field_with("nested", |f| {
+                f.debug_struct("Nested")
+                    .compact()
+                    .field("map", &map)
+                    .field("set", &BTreeSet::from([1, 2]))
+                    .field("point", &Point { x: 1, y: 2 })
+                    .finish()
+            })
+            .field_with("map", |f| {
+                f.debug_map().compact().entries(map.clone()).finish()
+            })
+            .finish();
+    }
+}
+
+#[test]
+fn layout_hints() {
+    assert_layout_snapshot!(Hinted);
+}
+
+/// Comments its fields and entries.
+struct Commented;
+
+impl DebugPls for Commented {
+    fn fmt(&self, f: Formatter<'_>) {
+        f.debug_struct("Commented")
+            .field_commented("mode", &0o755, "rwxr-xr-x")
+            .field_with("registers", |f| {
+                f.debug_list()
+                    .entry_commented(&1, "status")
+                    .entry(&0)
+                    .entry_commented(&Point { x: 1, y: 2 }, "cursor")
+                    .finish();
+            })
+            .field_with("limits", |f| {
+                f.debug_map()
+                    .entry_commented(&"timeout", &500, "in ms")
+                    .entry(&"retries", &3)
+                    .finish();
+            })
+            .field_with("compact", |f| {
+                f.debug_struct("Compact")
+                    .compact()
+                    .field_commented("len", &4, "in */ bytes")
+                    .field("cap", &8)
+                    .finish();
+            })
+            .field_commented("last", &(), "the end")
+            .finish();
+    }
+}
+
+#[test]
+fn comments() {
+    assert_layout_snapshot!(Commented);
+}
+
+/// Leaves the trailing commas out of some of its fields.
+struct Trailing;
+
+impl DebugPls for Trailing {
+    fn fmt(&self, f: Formatter<'_>) {
+        let long =
+            "a string long enough to break whichever tuple or list it is written in over lines";
+        f.debug_struct("Trailing")
+            .expanded()
+            .trailing_comma(false)
+            .field_with("tuple", |f| {
+                f.debug_tuple()
+                    .trailing_comma(false)
+                    .field(&long)
+                    .field(&1)
+                    .finish();
+            })
+            .field_with("one", |f| {
+                f.debug_tuple().trailing_comma(false).field(&long).finish()
+            })
+            .field_with("commented", |f| {
+                f.debug_list()
+                    .expanded()
+                    .trailing_comma(false)
+                    .entry(&1)
+                    .entry_commented(&2, "the last")
+                    .finish();
+            })
+            .field("kept", &[long, long])
+            .finish();
+    }
+}
+
+#[test]
+fn trailing_commas() {
+    assert_layout_snapshot!(Trailing);
+}
+
+#[test]
+fn no_trailing_commas() {
+    let value = (
+        Table(vec![Point { x: 1, y: -20 }, Point { x: 300, y: 4 }]),
+        BTreeMap::from([(
+            vec!["a key long enough to be broken over several lines, on its own"; 2],
+            1,
+        )]),
+        Wrapper("a multi-line\nstring"),
+    );
+    insta::assert_snapshot!(pretty(&value)
+        .trailing_commas(false)
+        .multiline_strings()
+        .to_string());
+}